@@ -5,6 +5,15 @@ use std::io;
 
 use super::{App, AppMode, Focus};
 
+/// Terminal cells the preview moves per pan key
+const PAN_CELLS: i32 = 4;
+
+/// Zoom factor per zoom key
+const ZOOM_STEP: f64 = 1.25;
+
+/// Lines a page key scrolls a document
+const DOCUMENT_PAGE: i32 = 20;
+
 fn key_to_explorer_input(code: KeyCode) -> Input {
     match code {
         KeyCode::Char('j') | KeyCode::Down => Input::Down,
@@ -21,7 +30,7 @@ fn key_to_explorer_input(code: KeyCode) -> Input {
 
 impl App {
     pub fn handle_events(&mut self) -> io::Result<()> {
-        if event::poll(std::time::Duration::from_millis(100))? {
+        if event::poll(self.preview.poll_timeout())? {
             match event::read()? {
                 Event::Key(key) if key.kind == KeyEventKind::Press => {
                     if self.layout.mode == AppMode::Direct {
@@ -33,6 +42,15 @@ impl App {
                 Event::Mouse(mouse) => match mouse.kind {
                     MouseEventKind::ScrollDown => self.tags.scroll_down(3),
                     MouseEventKind::ScrollUp => self.tags.scroll_up(3),
+                    MouseEventKind::Down(MouseButton::Left)
+                        if self.preview.show
+                            && self.preview.press_cell(mouse.column, mouse.row) => {}
+                    MouseEventKind::Drag(MouseButton::Left)
+                        if self.preview.show
+                            && self.preview.drag_cell(mouse.column, mouse.row) => {}
+                    MouseEventKind::Up(MouseButton::Left) if self.preview.show => {
+                        self.preview.release_cell(mouse.column, mouse.row);
+                    }
                     MouseEventKind::Down(MouseButton::Left) => {
                         if self.layout.mode == AppMode::Explorer {
                             let hit = |area: Rect| {
@@ -76,6 +94,107 @@ impl App {
         self.tags.clear_filter();
     }
 
+    /// Window/level, frame, display and measurement keys for the preview,
+    /// shared by every focus while the preview, and any MPR view in it, is
+    /// shown
+    fn handle_preview_key(&mut self, code: KeyCode) -> bool {
+        if !self.preview.show {
+            return false;
+        }
+        match code {
+            KeyCode::Char('M') => self.preview.toggle_mpr(),
+            KeyCode::Char('R') => self.preview.cycle_reference(),
+            KeyCode::Char('P') => self.preview.cycle_presentation(),
+            KeyCode::Char('S') => self.preview.cycle_segmentation(),
+            KeyCode::Char('s') => self.preview.select_next_roi(),
+            KeyCode::Char(' ') => self.preview.toggle_roi(),
+            KeyCode::Char('.') if self.preview.mpr.is_some() => self.preview.step_mpr(1),
+            KeyCode::Char(',') if self.preview.mpr.is_some() => self.preview.step_mpr(-1),
+            KeyCode::Char('v') => self.preview.cycle_mpr_view(),
+            KeyCode::Char('H') if self.preview.mpr.is_some() => self.preview.move_mpr_point(0, -1),
+            KeyCode::Char('J') if self.preview.mpr.is_some() => self.preview.move_mpr_point(1, 0),
+            KeyCode::Char('K') if self.preview.mpr.is_some() => self.preview.move_mpr_point(-1, 0),
+            KeyCode::Char('L') if self.preview.mpr.is_some() => self.preview.move_mpr_point(0, 1),
+            KeyCode::Char('.') => self.preview.next_frame(),
+            KeyCode::Char(',') => self.preview.previous_frame(),
+            KeyCode::Char('c') => self.preview.toggle_cine(),
+            KeyCode::Char('i') => self.preview.toggle_inspector(),
+            KeyCode::Char('H') if self.preview.cursor.is_some() => self.preview.move_cursor(0, -1),
+            KeyCode::Char('J') if self.preview.cursor.is_some() => self.preview.move_cursor(1, 0),
+            KeyCode::Char('K') if self.preview.cursor.is_some() => self.preview.move_cursor(-1, 0),
+            KeyCode::Char('L') if self.preview.cursor.is_some() => self.preview.move_cursor(0, 1),
+            KeyCode::Char('H') => self.preview.pan(-PAN_CELLS, 0),
+            KeyCode::Char('J') => self.preview.pan(0, PAN_CELLS),
+            KeyCode::Char('K') => self.preview.pan(0, -PAN_CELLS),
+            KeyCode::Char('L') => self.preview.pan(PAN_CELLS, 0),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.preview.zoom_by(ZOOM_STEP),
+            KeyCode::Char('-') => self.preview.zoom_by(1.0 / ZOOM_STEP),
+            KeyCode::Char('f') => self.preview.toggle_fullscreen(),
+            KeyCode::Esc if self.preview.fullscreen => self.preview.toggle_fullscreen(),
+            KeyCode::Char('w') => self.preview.next_file_window(),
+            KeyCode::Char(c @ '1'..='4') => self.preview.apply_preset(c as usize - '1' as usize),
+            KeyCode::Char('[') => self.preview.adjust_window(0, -1),
+            KeyCode::Char(']') => self.preview.adjust_window(0, 1),
+            KeyCode::Char('{') => self.preview.adjust_window(-1, 0),
+            KeyCode::Char('}') => self.preview.adjust_window(1, 0),
+            KeyCode::Char('0') => self.preview.reset_window(),
+            KeyCode::Char('o') => self.preview.toggle_overlays(),
+            KeyCode::Char('t') => self.preview.toggle_corners(),
+            KeyCode::Char('g') => self.preview.toggle_histogram(),
+            KeyCode::Char('m') => self.preview.cycle_tool(),
+            KeyCode::Char('a') if self.preview.measure.tool.is_some() => self.preview.mark_point(),
+            KeyCode::Char('d') if self.preview.measure.tool.is_some() => {
+                self.preview.delete_last_measurement()
+            }
+            KeyCode::Char('E') => {
+                let saved = self.preview.export_measurements(&self.preview.export_dir());
+                self.preview.report_export(&saved);
+            }
+            KeyCode::Char('x') => self.preview.export_frame(&self.preview.export_dir()),
+            KeyCode::Char('X') => self.preview.export_cine(&self.preview.export_dir()),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Time, channel and group keys while the waveform plot is shown
+    fn handle_waveform_key(&mut self, code: KeyCode) -> bool {
+        let Some(Ok(view)) = &mut self.waveform else {
+            return false;
+        };
+        match code {
+            KeyCode::Left | KeyCode::Char('h') => view.scroll(-1),
+            KeyCode::Right | KeyCode::Char('l') => view.scroll(1),
+            KeyCode::Up | KeyCode::Char('k') => view.select_channel(-1),
+            KeyCode::Down | KeyCode::Char('j') => view.select_channel(1),
+            KeyCode::Char(' ') => view.toggle_channel(),
+            KeyCode::Char('+') | KeyCode::Char('=') => view.zoom_by(1.0 / ZOOM_STEP),
+            KeyCode::Char('-') => view.zoom_by(ZOOM_STEP),
+            KeyCode::Char('g') => view.next_group(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Scroll and save keys while a document's text is shown
+    fn handle_document_key(&mut self, code: KeyCode) -> bool {
+        let Some(Ok(view)) = &mut self.document else {
+            return false;
+        };
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => view.scroll_by(1),
+            KeyCode::Up | KeyCode::Char('k') => view.scroll_by(-1),
+            KeyCode::PageDown => view.scroll_by(DOCUMENT_PAGE),
+            KeyCode::PageUp => view.scroll_by(-DOCUMENT_PAGE),
+            KeyCode::Char('x') => {
+                let saved = view.save(&view.save_dir());
+                view.report(&saved);
+            }
+            _ => return false,
+        }
+        true
+    }
+
     fn handle_direct_key(&mut self, code: KeyCode) {
         if self.search.active {
             match code {
@@ -96,7 +215,10 @@ impl App {
                 }
                 _ => {}
             }
-        } else {
+        } else if !self.handle_document_key(code)
+            && !self.handle_waveform_key(code)
+            && !self.handle_preview_key(code)
+        {
             match code {
                 KeyCode::Char('q') | KeyCode::Esc => {
                     if self.search.query.is_empty() {
@@ -109,6 +231,13 @@ impl App {
                 KeyCode::Up | KeyCode::Char('k') => self.tags.scroll_up(1),
                 KeyCode::Char('/') => self.search.active = true,
                 KeyCode::Char('p') => self.preview.toggle(self.meta.path.as_deref()),
+                KeyCode::Char('n') => self.reveal_next_finding(),
+                KeyCode::Char('u') => self.toggle_unexpected_filter(),
+                KeyCode::Char('C') => self.toggle_content_tree(),
+                KeyCode::Char('F') => self.toggle_frame_groups(),
+                KeyCode::Char('D') => self.toggle_dose_panel(),
+                KeyCode::Char('W') => self.toggle_waveform(),
+                KeyCode::Char('O') => self.toggle_document(),
                 KeyCode::Right | KeyCode::Char('l') => self.tags.expand_selected(),
                 KeyCode::Left | KeyCode::Char('h') => self.tags.collapse_parent(),
                 _ => {}
@@ -124,6 +253,9 @@ impl App {
     }
 
     fn handle_explorer_focus_key(&mut self, code: KeyCode) {
+        if self.handle_preview_key(code) {
+            return;
+        }
         match code {
             KeyCode::Char('q') | KeyCode::Esc => {
                 self.should_quit = true;
@@ -176,6 +308,12 @@ impl App {
             }
             return;
         }
+        if self.handle_document_key(code)
+            || self.handle_waveform_key(code)
+            || self.handle_preview_key(code)
+        {
+            return;
+        }
 
         match code {
             KeyCode::Tab => {
@@ -193,6 +331,13 @@ impl App {
             KeyCode::Up | KeyCode::Char('k') => self.tags.scroll_up(1),
             KeyCode::Char('/') => self.search.active = true,
             KeyCode::Char('p') => self.preview.toggle(self.meta.path.as_deref()),
+            KeyCode::Char('n') => self.reveal_next_finding(),
+            KeyCode::Char('u') => self.toggle_unexpected_filter(),
+            KeyCode::Char('C') => self.toggle_content_tree(),
+            KeyCode::Char('F') => self.toggle_frame_groups(),
+            KeyCode::Char('D') => self.toggle_dose_panel(),
+            KeyCode::Char('W') => self.toggle_waveform(),
+            KeyCode::Char('O') => self.toggle_document(),
             KeyCode::Right | KeyCode::Char('l') => self.tags.expand_selected(),
             KeyCode::Left | KeyCode::Char('h') => self.tags.collapse_parent(),
             _ => {}
@@ -248,6 +393,9 @@ impl App {
         match current_path {
             Some(path) if path.is_file() => {
                 self.load_dicom_file(&path);
+                if self.has_dicom_loaded() {
+                    self.thumbnails.request(&path);
+                }
             }
             _ => {
                 self.clear_dicom_state();
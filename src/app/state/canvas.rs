@@ -0,0 +1,65 @@
+use dicom_pixeldata::image::{Rgb, RgbImage};
+
+/// Colors of the preview's drawn annotations
+pub const CURSOR: Rgb<u8> = Rgb([255, 255, 0]);
+pub const MEASUREMENT: Rgb<u8> = Rgb([0, 255, 255]);
+pub const REFERENCE: Rgb<u8> = Rgb([255, 140, 0]);
+pub const PRESENTATION: Rgb<u8> = Rgb([255, 0, 255]);
+
+fn plot(canvas: &mut RgbImage, x: i64, y: i64, color: Rgb<u8>) {
+    if x >= 0 && y >= 0 && (x as u32) < canvas.width() && (y as u32) < canvas.height() {
+        canvas.put_pixel(x as u32, y as u32, color);
+    }
+}
+
+/// Draws a cross with an open center, so the pixel under it stays visible
+pub fn draw_crosshair(canvas: &mut RgbImage, center: (f64, f64), color: Rgb<u8>) {
+    const GAP: i64 = 3;
+    const ARM: i64 = 10;
+    let (cx, cy) = (center.0 as i64, center.1 as i64);
+    for offset in GAP..=ARM {
+        plot(canvas, cx - offset, cy, color);
+        plot(canvas, cx + offset, cy, color);
+        plot(canvas, cx, cy - offset, color);
+        plot(canvas, cx, cy + offset, color);
+    }
+}
+
+pub fn draw_line(canvas: &mut RgbImage, from: (f64, f64), to: (f64, f64), color: Rgb<u8>) {
+    let steps = (to.0 - from.0)
+        .abs()
+        .max((to.1 - from.1).abs())
+        .ceil()
+        .max(1.0) as i64;
+    for step in 0..=steps {
+        let t = step as f64 / steps as f64;
+        let x = from.0 + (to.0 - from.0) * t;
+        let y = from.1 + (to.1 - from.1) * t;
+        plot(canvas, x.round() as i64, y.round() as i64, color);
+    }
+}
+
+/// Outline of the axis-aligned box between two corners
+pub fn draw_rect(canvas: &mut RgbImage, a: (f64, f64), b: (f64, f64), color: Rgb<u8>) {
+    draw_line(canvas, (a.0, a.1), (b.0, a.1), color);
+    draw_line(canvas, (b.0, a.1), (b.0, b.1), color);
+    draw_line(canvas, (b.0, b.1), (a.0, b.1), color);
+    draw_line(canvas, (a.0, b.1), (a.0, a.1), color);
+}
+
+/// Outline of the ellipse inscribed in the box between two corners
+pub fn draw_ellipse(canvas: &mut RgbImage, a: (f64, f64), b: (f64, f64), color: Rgb<u8>) {
+    let center = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    let radii = ((b.0 - a.0).abs() / 2.0, (b.1 - a.1).abs() / 2.0);
+    let steps = ((radii.0 + radii.1) * 4.0).ceil().max(16.0) as usize;
+    let point = |i: usize| {
+        let angle = i as f64 / steps as f64 * std::f64::consts::TAU;
+        (
+            center.0 + radii.0 * angle.cos(),
+            center.1 + radii.1 * angle.sin(),
+        )
+    };
+    for i in 0..steps {
+        draw_line(canvas, point(i), point(i + 1), color);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::dicom::{
+    corner_text, file_vois, frame_interval, frame_of_reference, frame_planes, overlay_planes,
+    pixel_padding, sop_instance_uid, Calibration, CornerText, FramePixels, ImagePlane,
+    OverlayPlane, PaletteLut, ValueUnits, Voi,
+};
+use dicom_pixeldata::DecodedPixelData;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Everything the preview needs from a file, read off the UI thread
+pub struct DecodedImage {
+    pub source: DecodedPixelData<'static>,
+    pub first_frame: FramePixels,
+    pub vois: Vec<Voi>,
+    pub frame_interval: Option<Duration>,
+    pub planes: Vec<ImagePlane>,
+    pub frame_of_reference: Option<String>,
+    pub sop_instance_uid: Option<String>,
+    pub units: ValueUnits,
+    pub calibration: Option<Calibration>,
+    pub overlays: Vec<OverlayPlane>,
+    pub corners: CornerText,
+    /// Stored values marking padding
+    pub padding: Option<(f64, f64)>,
+    pub palette: Option<PaletteLut>,
+}
+
+/// Work running on a worker thread. Dropping the job cancels it.
+pub struct Job<T> {
+    receiver: Receiver<Result<T, String>>,
+    cancel: Arc<AtomicBool>,
+    pub started: Instant,
+}
+
+/// Pixel decoding of the previewed file
+pub type DecodeJob = Job<DecodedImage>;
+
+impl<T: Send + 'static> Job<T> {
+    /// Runs `work`, which returns `None` once it notices the cancel flag
+    pub fn start(
+        work: impl FnOnce(&AtomicBool) -> Option<Result<T, String>> + Send + 'static,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let cancelled = Arc::clone(&cancel);
+        thread::spawn(move || {
+            if let Some(result) = work(&cancelled) {
+                let _ = sender.send(result);
+            }
+        });
+        Self {
+            receiver,
+            cancel,
+            started: Instant::now(),
+        }
+    }
+
+    /// Runs `work`, which stops early once the cancel flag is set; what it
+    /// returns then is dropped
+    pub fn cancellable(
+        work: impl FnOnce(&AtomicBool) -> Result<T, String> + Send + 'static,
+    ) -> Self {
+        Self::start(move |cancelled| {
+            let result = work(cancelled);
+            (!cancelled.load(Ordering::Relaxed)).then_some(result)
+        })
+    }
+
+    /// The outcome, once the worker has finished
+    pub fn try_take(&self) -> Option<Result<T, String>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err("Worker stopped unexpectedly".into())),
+        }
+    }
+}
+
+impl DecodeJob {
+    pub fn spawn(path: PathBuf) -> Self {
+        Self::start(move |cancelled| decode_file(&path, cancelled))
+    }
+}
+
+/// Conversion of the frames after the displayed one
+pub type PrefetchJob = Job<Vec<(u32, FramePixels)>>;
+
+impl PrefetchJob {
+    /// Converts `frames` in turn, stopping at the first one after a cancel
+    pub fn spawn(
+        source: Arc<DecodedPixelData<'static>>,
+        palette: Option<PaletteLut>,
+        frames: Vec<u32>,
+    ) -> Self {
+        Self::start(move |cancelled| {
+            let mut converted = Vec::with_capacity(frames.len());
+            for frame in frames {
+                if cancelled.load(Ordering::Relaxed) {
+                    return None;
+                }
+                // A frame that fails is converted again, and the error
+                // shown, when it is displayed
+                if let Ok(pixels) = FramePixels::from_decoded(&source, frame, palette.as_ref()) {
+                    converted.push((frame, pixels));
+                }
+            }
+            Some(Ok(converted))
+        })
+    }
+}
+
+impl<T> Drop for Job<T> {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Returns `None` when cancelled between stages
+fn decode_file(path: &Path, cancelled: &AtomicBool) -> Option<Result<DecodedImage, String>> {
+    use dicom_pixeldata::PixelDecoder;
+
+    let obj = match dicom::object::open_file(path) {
+        Ok(obj) => obj,
+        Err(e) => return Some(Err(format!("Failed to open DICOM file: {e}"))),
+    };
+    if cancelled.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let pixel_data = match obj.decode_pixel_data() {
+        Ok(pd) => pd,
+        Err(e) => return Some(Err(format!("Failed to decode pixel data: {e}"))),
+    };
+    if cancelled.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let palette = PaletteLut::from_obj(&obj);
+    let first_frame = match FramePixels::from_decoded(&pixel_data, 0, palette.as_ref()) {
+        Ok(pixels) => pixels,
+        Err(e) => return Some(Err(format!("Failed to convert to image: {e}"))),
+    };
+    if cancelled.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    Some(Ok(DecodedImage {
+        source: pixel_data.to_owned(),
+        first_frame,
+        vois: file_vois(&obj),
+        frame_interval: frame_interval(&obj),
+        planes: frame_planes(&obj),
+        frame_of_reference: frame_of_reference(&obj),
+        sop_instance_uid: sop_instance_uid(&obj),
+        units: ValueUnits::from_obj(&obj),
+        calibration: Calibration::from_obj(&obj, 0),
+        overlays: overlay_planes(&obj),
+        corners: corner_text(&obj),
+        padding: pixel_padding(&obj),
+        palette,
+    }))
+}
@@ -0,0 +1,69 @@
+use crate::dicom::EncapsulatedDocument;
+use std::path::{Path, PathBuf};
+
+/// Text of an encapsulated document, scrolled by line
+pub struct DocumentView {
+    pub document: EncapsulatedDocument,
+    /// DICOM file the document came from
+    pub source: PathBuf,
+    /// Extracted text, or why there is none
+    pub lines: Result<Vec<String>, String>,
+    /// First line shown
+    pub scroll: usize,
+    /// Outcome of the last save
+    pub notice: Option<String>,
+}
+
+impl DocumentView {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let document = EncapsulatedDocument::open(path)?;
+        Ok(Self {
+            lines: document.text(),
+            document,
+            source: path.to_path_buf(),
+            scroll: 0,
+            notice: None,
+        })
+    }
+
+    pub fn scroll_by(&mut self, lines: i32) {
+        let count = self.lines.as_ref().map_or(0, |lines| lines.len());
+        self.scroll = (self.scroll as i64 + lines as i64).clamp(0, count.saturating_sub(1) as i64)
+            as usize;
+    }
+
+    /// Folder of the DICOM file, where the document is saved
+    pub fn save_dir(&self) -> PathBuf {
+        match self.source.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        }
+    }
+
+    /// Writes the document into `dir`, named after the DICOM file with the
+    /// extension of its MIME type
+    pub fn save(&self, dir: &Path) -> Result<PathBuf, String> {
+        let output = self.document.file_name(&self.source, dir);
+        self.document.save(&output).map(|_| output)
+    }
+
+    /// Shows the full path the document was saved to, or why it was not
+    pub fn report(&mut self, result: &Result<PathBuf, String>) {
+        self.notice = Some(match result {
+            Ok(path) => {
+                let full = std::path::absolute(path).unwrap_or_else(|_| path.clone());
+                format!("Saved {}", full.display())
+            }
+            Err(e) => e.clone(),
+        });
+    }
+
+    /// e.g. "Lab report (application/pdf)"
+    pub fn title(&self) -> String {
+        let name = self.document.title.as_deref().unwrap_or("Document");
+        match self.document.mime_type.as_str() {
+            "" => name.to_string(),
+            mime => format!("{name} ({mime})"),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use super::decoder::Job;
+use crate::dicom::{dose_summary, DoseScope, DoseSummary};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Dose summary reading, which may open every file of a folder
+pub type DoseJob = Job<DoseSummary>;
+
+impl DoseJob {
+    pub fn spawn(path: PathBuf) -> Self {
+        Self::cancellable(move |cancelled| dose_summary(&path, cancelled))
+    }
+}
+
+/// Dose summaries kept per scope, so the files of a study read its folder
+/// once
+#[derive(Default)]
+pub struct Doses {
+    cache: HashMap<DoseScope, Result<DoseSummary, String>>,
+    /// The one being read; a newer request replaces and cancels it
+    pending: Option<(DoseScope, DoseJob)>,
+}
+
+impl Doses {
+    /// Starts reading the summary of `scope` from `path` unless it is known
+    /// or on its way
+    pub fn request(&mut self, scope: &DoseScope, path: PathBuf) {
+        if self.cache.contains_key(scope) || self.is_pending(scope) {
+            return;
+        }
+        self.pending = Some((scope.clone(), DoseJob::spawn(path)));
+    }
+
+    /// Keeps the summary that has been read, if any
+    pub fn poll(&mut self) {
+        let Some((scope, job)) = &self.pending else {
+            return;
+        };
+        if let Some(result) = job.try_take() {
+            self.cache.insert(scope.clone(), result);
+            self.pending = None;
+        }
+    }
+
+    pub fn get(&self, scope: &DoseScope) -> Option<&Result<DoseSummary, String>> {
+        self.cache.get(scope)
+    }
+
+    pub fn is_pending(&self, scope: &DoseScope) -> bool {
+        self.pending.as_ref().is_some_and(|(s, _)| s == scope)
+    }
+}
@@ -0,0 +1,57 @@
+use super::decoder::Job;
+use crate::dicom::{rendered_frame, save_frames, FramePixels, OverlayPlane, PaletteLut, Voi};
+use dicom_pixeldata::DecodedPixelData;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How exported frames are drawn, as the preview shows them
+pub struct ExportStyle {
+    pub voi: Option<Voi>,
+    pub overlays: Vec<OverlayPlane>,
+    pub frame_interval: Duration,
+}
+
+/// Rendering and encoding of exported frames
+pub type ExportJob = Job<PathBuf>;
+
+impl ExportJob {
+    /// Writes one converted frame to `output`
+    pub fn frame(pixels: FramePixels, frame: u32, style: ExportStyle, output: PathBuf) -> Self {
+        Self::cancellable(move |cancelled| {
+            let image = rendered_frame(&pixels, style.voi.as_ref(), &style.overlays, frame);
+            if cancelled.load(Ordering::Relaxed) {
+                return Err("Cancelled".into());
+            }
+            save_frames(&[image], style.frame_interval, &output).map(|_| output)
+        })
+    }
+
+    /// Converts every frame and writes them to `output`, stopping between
+    /// frames once cancelled
+    pub fn cine(
+        source: Arc<DecodedPixelData<'static>>,
+        palette: Option<PaletteLut>,
+        style: ExportStyle,
+        output: PathBuf,
+    ) -> Self {
+        Self::cancellable(move |cancelled| {
+            let mut frames = Vec::new();
+            for frame in 0..source.number_of_frames() {
+                if cancelled.load(Ordering::Relaxed) {
+                    return Err("Cancelled".into());
+                }
+                let pixels = FramePixels::from_decoded(&source, frame, palette.as_ref())
+                    .map_err(|e| format!("Failed to convert to image: {e}"))?;
+                frames.push(rendered_frame(
+                    &pixels,
+                    style.voi.as_ref(),
+                    &style.overlays,
+                    frame,
+                ));
+            }
+            save_frames(&frames, style.frame_interval, &output).map(|_| output)
+        })
+    }
+}
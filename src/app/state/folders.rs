@@ -0,0 +1,69 @@
+use super::decoder::Job;
+use crate::dicom::FolderHeaders;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Folder header reading, which opens every file of a folder
+pub type FolderJob = Job<Arc<FolderHeaders>>;
+
+impl FolderJob {
+    pub fn spawn(folder: PathBuf) -> Self {
+        Self::cancellable(move |cancelled| FolderHeaders::read(&folder, cancelled).map(Arc::new))
+    }
+}
+
+/// Search of the displayed image's folder waiting for its headers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FolderSearch {
+    Presentations,
+    Segmentations,
+    ReferenceSeries,
+    Volume,
+}
+
+/// Folder headers kept per folder, so presentation states, segmentations
+/// and series slices are searched for without reading the folder again
+#[derive(Default)]
+pub struct Folders {
+    cache: HashMap<PathBuf, Result<Arc<FolderHeaders>, String>>,
+    /// The one being read; a newer request replaces and cancels it
+    pending: Option<(PathBuf, FolderJob)>,
+}
+
+impl Folders {
+    /// Starts reading `folder` unless it is known or on its way
+    pub fn request(&mut self, folder: &Path) {
+        if self.cache.contains_key(folder) || self.is_pending(folder) {
+            return;
+        }
+        self.pending = Some((folder.to_path_buf(), FolderJob::spawn(folder.to_path_buf())));
+    }
+
+    /// Keeps the headers that have been read, if any. Returns whether a
+    /// folder was read.
+    pub fn poll(&mut self) -> bool {
+        let Some((folder, job)) = &self.pending else {
+            return false;
+        };
+        let Some(result) = job.try_take() else {
+            return false;
+        };
+        self.cache.insert(folder.clone(), result);
+        self.pending = None;
+        true
+    }
+
+    pub fn get(&self, folder: &Path) -> Option<Result<Arc<FolderHeaders>, String>> {
+        self.cache.get(folder).cloned()
+    }
+
+    pub fn is_pending(&self, folder: &Path) -> bool {
+        self.pending.as_ref().is_some_and(|(f, _)| f == folder)
+    }
+
+    /// Whether any folder is being read
+    pub fn is_reading(&self) -> bool {
+        self.pending.is_some()
+    }
+}
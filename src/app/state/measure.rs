@@ -0,0 +1,85 @@
+use super::canvas;
+use crate::dicom::{Measurement, MeasurementKind, Shape};
+use dicom_pixeldata::image::{Rgb, RgbImage};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Measurement drawn between the anchor and the cursor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+    Roi(Shape),
+    Distance,
+}
+
+impl Tool {
+    pub fn of(kind: &MeasurementKind) -> Self {
+        match kind {
+            MeasurementKind::Roi { shape, .. } => Self::Roi(*shape),
+            MeasurementKind::Distance { .. } => Self::Distance,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Roi(Shape::Rectangle) => "Rect ROI",
+            Self::Roi(Shape::Ellipse) => "Ellipse ROI",
+            Self::Distance => "Distance",
+        }
+    }
+
+    pub fn draw(self, canvas: &mut RgbImage, from: (f64, f64), to: (f64, f64), color: Rgb<u8>) {
+        match self {
+            Self::Roi(Shape::Rectangle) => canvas::draw_rect(canvas, from, to, color),
+            Self::Roi(Shape::Ellipse) => canvas::draw_ellipse(canvas, from, to, color),
+            Self::Distance => canvas::draw_line(canvas, from, to, color),
+        }
+    }
+}
+
+/// Measurements of the displayed file and the tool drawing them, with those
+/// of the other files viewed this session put aside
+#[derive(Default)]
+pub struct MeasurementSession {
+    pub tool: Option<Tool>,
+    /// First point of the measurement being drawn
+    pub anchor: Option<(u32, u32)>,
+    /// Measurements taken on the displayed file
+    pub measurements: Vec<Measurement>,
+    saved: HashMap<PathBuf, Vec<Measurement>>,
+}
+
+impl MeasurementSession {
+    /// Keeps the displayed file's measurements for when it is shown again
+    /// and puts the tool down
+    pub fn put_aside(&mut self, path: Option<PathBuf>) {
+        let measurements = std::mem::take(&mut self.measurements);
+        if let (Some(path), false) = (path, measurements.is_empty()) {
+            self.saved.insert(path, measurements);
+        }
+        self.tool = None;
+        self.anchor = None;
+    }
+
+    /// Brings back the measurements taken on `path` earlier
+    pub fn restore(&mut self, path: &Path) {
+        if let Some(saved) = self.saved.remove(path) {
+            self.measurements = saved;
+        }
+    }
+
+    pub fn on_frame(&self, frame: u32) -> impl Iterator<Item = &Measurement> {
+        self.measurements.iter().filter(move |m| m.frame == frame)
+    }
+
+    /// Removes the newest measurement of a frame. Returns false when it has
+    /// none.
+    pub fn delete_last(&mut self, frame: u32) -> bool {
+        match self.measurements.iter().rposition(|m| m.frame == frame) {
+            Some(index) => {
+                self.measurements.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use crate::validation::{SopClass, ValidationResult};
+use crate::dicom::{DicomTag, DoseScope, FunctionalGroups, Waveform};
+use crate::validation::{Finding, SopClass, ValidationResult};
+use dicom::object::{FileDicomObject, InMemDicomObject};
 use std::path::PathBuf;
 
 pub struct FileMeta {
@@ -6,17 +8,49 @@ pub struct FileMeta {
     pub name: String,
     pub selected_path: Option<PathBuf>,
     pub validation_result: ValidationResult,
+    pub findings: Vec<Finding>,
+    pub finding_cursor: usize,
     pub sop_class: SopClass,
     pub diff_mode: bool,
     pub modified_name: Option<String>,
+    /// Content tree of a Structured Report
+    pub content_tree: Option<Vec<DicomTag>>,
+    /// Multiplex groups of a waveform object, or why they failed to decode
+    pub waveforms: Result<Vec<Waveform>, String>,
+    /// Functional groups of an enhanced multi-frame object
+    pub functional_groups: Option<FunctionalGroups>,
+    /// Whether the dose panel is shown, kept across files
+    pub show_dose: bool,
+    /// What the file's dose summary is read from
+    pub dose_scope: Option<DoseScope>,
 }
 
 impl FileMeta {
+    /// Fills in everything read from a file's object: its SOP class,
+    /// validation and the optional views. Shared by every way a file is
+    /// opened.
+    pub fn apply_obj(&mut self, obj: &FileDicomObject<InMemDicomObject>) {
+        self.sop_class = crate::validation::get_sop_class_from_obj(obj);
+        self.validation_result = crate::validation::validate_type1_fields_from_obj(obj);
+        self.findings = crate::validation::collect_findings(obj);
+        self.finding_cursor = 0;
+        self.content_tree = crate::dicom::content_tree(obj);
+        self.waveforms = crate::dicom::waveforms(obj);
+        self.functional_groups = crate::dicom::FunctionalGroups::from_obj(obj);
+        self.dose_scope = self.path.as_deref().map(|path| DoseScope::of(path, obj));
+    }
+
     pub fn clear(&mut self) {
         self.path = None;
         self.name = String::new();
         self.selected_path = None;
         self.validation_result = ValidationResult::NotApplicable;
+        self.findings.clear();
+        self.finding_cursor = 0;
         self.sop_class = SopClass::Unknown;
+        self.content_tree = None;
+        self.waveforms = Ok(Vec::new());
+        self.functional_groups = None;
+        self.dose_scope = None;
     }
 }
@@ -1,11 +1,25 @@
+pub mod canvas;
+pub mod decoder;
+pub mod document;
+pub mod dose;
+pub mod export;
+pub mod folders;
 pub mod layout;
+pub mod measure;
 pub mod meta;
+pub mod mpr;
+pub mod presentation;
 pub mod preview;
+pub mod reference;
 pub mod search;
+pub mod segmentation;
 pub mod tags;
+pub mod thumbnails;
+pub mod viewport;
+pub mod waveform;
 
-use crate::dicom::DicomTag;
-use crate::validation::{SopClass, ValidationResult};
+use crate::dicom::{DicomTag, DoseSummary};
+use crate::validation::{Finding, FindingKind, SopClass, ValidationResult};
 use ratatui::layout::Rect;
 use ratatui::widgets::Block;
 use ratatui_explorer::{FileExplorer, Theme};
@@ -15,11 +29,15 @@ use std::time::Instant;
 
 pub use self::layout::{AppMode, Focus};
 
+use self::document::DocumentView;
+use self::dose::Doses;
 use self::layout::Layout;
 use self::meta::FileMeta;
 use self::preview::Preview;
 use self::search::Search;
 use self::tags::Tags;
+use self::thumbnails::Thumbnails;
+use self::waveform::WaveformView;
 
 pub struct App {
     pub tags: Tags,
@@ -27,25 +45,41 @@ pub struct App {
     pub preview: Preview,
     pub meta: FileMeta,
     pub layout: Layout,
+    /// Tag table put aside while the SR content tree or the frame's
+    /// functional groups are shown in its place
+    pub tag_view: Option<Tags>,
+    /// Frame whose functional groups are shown in place of the tag table
+    pub frame_groups: Option<u32>,
+    /// Waveform plot shown in place of the tag table
+    pub waveform: Option<Result<WaveformView, String>>,
+    /// Text of an encapsulated document shown in place of the tag table
+    pub document: Option<Result<DocumentView, String>>,
+    pub thumbnails: Thumbnails,
+    /// Dose summaries read for the dose panel
+    pub doses: Doses,
     pub should_quit: bool,
 }
 
 impl App {
+    #[allow(dead_code)] // used by the tests only
     pub fn new(
         tags: Vec<DicomTag>,
         file_name: String,
         validation_result: ValidationResult,
         sop_class: SopClass,
     ) -> Self {
-        Self::new_with_diff(tags, file_name, None, validation_result, sop_class, false, None, None)
+        let mut app = Self::new_with_diff(tags, file_name, None, false, None, None);
+        app.meta.validation_result = validation_result;
+        app.meta.sop_class = sop_class;
+        app
     }
 
+    /// The SOP class and validation result are left unknown until
+    /// `FileMeta::apply_obj` reads them from the object
     pub fn new_with_diff(
         tags: Vec<DicomTag>,
         file_name: String,
         modified_name: Option<String>,
-        validation_result: ValidationResult,
-        sop_class: SopClass,
         diff_mode: bool,
         dicom_file_path: Option<PathBuf>,
         picker: Option<Picker>,
@@ -58,10 +92,17 @@ impl App {
                 path: dicom_file_path,
                 name: file_name,
                 selected_path: None,
-                validation_result,
-                sop_class,
+                validation_result: ValidationResult::NotApplicable,
+                findings: Vec::new(),
+                finding_cursor: 0,
+                sop_class: SopClass::Unknown,
                 diff_mode,
                 modified_name,
+                content_tree: None,
+                waveforms: Ok(Vec::new()),
+                functional_groups: None,
+                show_dose: false,
+                dose_scope: None,
             },
             layout: Layout {
                 mode: AppMode::Direct,
@@ -69,6 +110,12 @@ impl App {
                 explorer: None,
                 explorer_area: Rect::default(),
             },
+            tag_view: None,
+            frame_groups: None,
+            waveform: None,
+            document: None,
+            thumbnails: Thumbnails::default(),
+            doses: Doses::default(),
             should_quit: false,
         };
         app.preview.decode(app.meta.path.as_deref());
@@ -88,9 +135,16 @@ impl App {
                 name: String::new(),
                 selected_path: None,
                 validation_result: ValidationResult::NotApplicable,
+                findings: Vec::new(),
+                finding_cursor: 0,
                 sop_class: SopClass::Unknown,
                 diff_mode: false,
                 modified_name: None,
+                content_tree: None,
+                waveforms: Ok(Vec::new()),
+                functional_groups: None,
+                show_dose: false,
+                dose_scope: None,
             },
             layout: Layout {
                 mode: AppMode::Explorer,
@@ -98,6 +152,12 @@ impl App {
                 explorer: Some(explorer),
                 explorer_area: Rect::default(),
             },
+            tag_view: None,
+            frame_groups: None,
+            waveform: None,
+            document: None,
+            thumbnails: Thumbnails::default(),
+            doses: Doses::default(),
             should_quit: false,
         }
     }
@@ -109,6 +169,9 @@ impl App {
 
         self.meta.selected_path = Some(path.to_path_buf());
         self.meta.path = Some(path.to_path_buf());
+        self.close_tag_view();
+        self.waveform = None;
+        self.document = None;
 
         let obj = match dicom::object::open_file(path) {
             Ok(obj) => obj,
@@ -119,18 +182,22 @@ impl App {
         };
 
         let tags = crate::dicom::extract_tags(&obj);
-        self.meta.sop_class = crate::validation::get_sop_class_from_obj(&obj);
-        self.meta.validation_result = crate::validation::validate_type1_fields_from_obj(&obj);
+        self.meta.apply_obj(&obj);
+        if self.meta.show_dose {
+            self.request_dose();
+        }
         self.meta.name = path
             .file_name()
             .map(|s| s.to_string_lossy().to_string())
             .unwrap_or_default();
 
         self.tags.all = tags;
+        if self.tags.only.is_some() {
+            self.tags.only = Some(self.unexpected_tag_labels());
+        }
         self.tags.filter(&self.search.query);
 
-        self.preview.image = None;
-        self.preview.error = None;
+        self.preview.clear();
         if self.preview.show {
             self.preview.pending_since = Some(Instant::now());
         }
@@ -144,10 +211,12 @@ impl App {
     }
 
     fn clear_dicom_display(&mut self) {
+        self.close_tag_view();
+        self.waveform = None;
+        self.document = None;
         self.tags.clear();
         self.meta.clear();
-        self.preview.image = None;
-        self.preview.error = None;
+        self.preview.clear();
         self.preview.pending_since = None;
         self.search.query.clear();
         self.search.active = false;
@@ -156,11 +225,157 @@ impl App {
         }
     }
 
+    /// Shows a Structured Report's content tree in place of the tag table,
+    /// or the tags again
+    pub fn toggle_content_tree(&mut self) {
+        if self.tag_view.is_some() && self.frame_groups.is_none() {
+            self.close_tag_view();
+        } else if let Some(tree) = self.meta.content_tree.clone() {
+            self.close_tag_view();
+            self.tag_view = Some(std::mem::replace(&mut self.tags, Tags::from_tags(tree)));
+        }
+        self.tags.filter(&self.search.query);
+    }
+
+    /// Shows the merged functional groups of the preview's frame in place
+    /// of the tag table, or the tags again
+    pub fn toggle_frame_groups(&mut self) {
+        if self.frame_groups.is_some() {
+            self.close_tag_view();
+        } else if let Some(groups) = &self.meta.functional_groups {
+            let rows = groups.rows(self.preview.frame);
+            self.close_tag_view();
+            self.tag_view = Some(std::mem::replace(&mut self.tags, Tags::from_tags(rows)));
+            self.frame_groups = Some(self.preview.frame);
+        }
+        self.tags.filter(&self.search.query);
+    }
+
+    /// Rebuilds the functional groups view when the preview moves to
+    /// another frame, keeping the selected row
+    fn sync_frame_groups(&mut self) {
+        let frame = self.preview.frame;
+        if self.frame_groups.is_none_or(|shown| shown == frame) {
+            return;
+        }
+        let Some(groups) = &self.meta.functional_groups else {
+            return;
+        };
+        let selected = self.tags.table_state.selected();
+        self.tags.all = groups.rows(frame);
+        self.tags.filter(&self.search.query);
+        if let (Some(row), Some(last)) = (selected, self.tags.visible.len().checked_sub(1)) {
+            self.tags.table_state.select(Some(row.min(last)));
+        }
+        self.frame_groups = Some(frame);
+    }
+
+    /// Plots the waveforms of the file in place of the tag table, or shows
+    /// the tags again
+    pub fn toggle_waveform(&mut self) {
+        if self.waveform.take().is_some() {
+            return;
+        }
+        self.waveform = match &self.meta.waveforms {
+            Ok(waveforms) => WaveformView::new(waveforms.clone()).map(Ok),
+            Err(e) => Some(Err(e.clone())),
+        };
+    }
+
+    /// Shows the text of the file's encapsulated document in place of the
+    /// tag table, or the tags again
+    pub fn toggle_document(&mut self) {
+        if self.document.take().is_some() {
+            return;
+        }
+        self.document = self.meta.path.as_deref().map(DocumentView::open);
+    }
+
+    /// Shows or hides the CT dose of the file's study
+    pub fn toggle_dose_panel(&mut self) {
+        self.meta.show_dose = !self.meta.show_dose;
+        if self.meta.show_dose {
+            self.request_dose();
+        }
+    }
+
+    fn request_dose(&mut self) {
+        if let (Some(path), Some(scope)) = (&self.meta.path, &self.meta.dose_scope) {
+            self.doses.request(scope, path.clone());
+        }
+    }
+
+    /// Dose summary of the file's study while the dose panel is shown
+    pub fn dose(&self) -> Option<&Result<DoseSummary, String>> {
+        if !self.meta.show_dose {
+            return None;
+        }
+        self.doses.get(self.meta.dose_scope.as_ref()?)
+    }
+
+    /// Whether the shown dose summary is still being read
+    pub fn is_dose_pending(&self) -> bool {
+        self.meta.show_dose
+            && self
+                .meta
+                .dose_scope
+                .as_ref()
+                .is_some_and(|scope| self.doses.is_pending(scope))
+    }
+
+    fn close_tag_view(&mut self) {
+        if let Some(tags) = self.tag_view.take() {
+            self.tags = tags;
+        }
+        self.frame_groups = None;
+    }
+
     pub fn has_dicom_loaded(&self) -> bool {
         self.tags.has_loaded()
     }
 
+    /// Selects the tag table row holding the next nested validation finding
+    pub fn reveal_next_finding(&mut self) {
+        let nested: Vec<&Finding> = self
+            .meta
+            .findings
+            .iter()
+            .filter(|f| !f.location.is_empty())
+            .collect();
+        if nested.is_empty() {
+            return;
+        }
+
+        let finding = nested[self.meta.finding_cursor % nested.len()];
+        self.meta.finding_cursor = (self.meta.finding_cursor + 1) % nested.len();
+        self.tags.reveal(&finding.location);
+    }
+
+    /// Shows only the attributes that do not belong to the object's IOD,
+    /// or every attribute again
+    pub fn toggle_unexpected_filter(&mut self) {
+        self.tags.only = match self.tags.only {
+            Some(_) => None,
+            None => Some(self.unexpected_tag_labels()),
+        };
+        self.tags.filter(&self.search.query);
+    }
+
+    fn unexpected_tag_labels(&self) -> Vec<String> {
+        self.meta
+            .findings
+            .iter()
+            .filter(|f| f.kind == FindingKind::Unexpected)
+            .filter_map(|f| f.location.first().cloned())
+            .collect()
+    }
+
     pub fn tick_preview_debounce(&mut self) {
         self.preview.tick_debounce(self.meta.path.as_deref());
+        self.preview.poll_decode();
+        self.preview.tick_cine();
+        self.thumbnails.poll();
+        self.doses.poll();
+        self.sync_frame_groups();
     }
 }
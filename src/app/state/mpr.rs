@@ -0,0 +1,166 @@
+use super::canvas;
+use super::decoder::Job;
+use super::viewport::Viewport;
+use crate::dicom::{load_volume, Axis, FolderHeaders, Voi, VoiTransform, Volume};
+use dicom_pixeldata::image::DynamicImage;
+use ratatui::layout::Rect;
+use ratatui_image::picker::Picker;
+use ratatui_image::protocol::StatefulProtocol;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Volume building for the reformatted views
+pub type VolumeJob = Job<Volume>;
+
+impl VolumeJob {
+    pub fn spawn(path: PathBuf, headers: Arc<FolderHeaders>) -> Self {
+        Self::cancellable(move |cancelled| load_volume(&path, &headers, cancelled))
+    }
+}
+
+/// One of the three reformatted views
+pub struct MprView {
+    pub axis: Axis,
+    pub viewport: Viewport,
+    pub image: Option<StatefulProtocol>,
+}
+
+/// Axial, coronal and sagittal views of a volume, linked through a shared
+/// crosshair point
+pub struct Mpr {
+    pub volume: Volume,
+    /// Voxel under the crosshair, as [column, row, slice]
+    pub point: [usize; 3],
+    /// View that slice keys and the cursor keys act on
+    pub active: Axis,
+    pub views: [MprView; 3],
+}
+
+impl Mpr {
+    /// Starts on the given slice, with the crosshair at its center
+    pub fn new(volume: Volume, slice: usize) -> Self {
+        let point = [volume.columns / 2, volume.rows / 2, slice];
+        Self {
+            volume,
+            point,
+            active: Axis::Slice,
+            views: Axis::ALL.map(|axis| MprView {
+                axis,
+                viewport: Viewport::default(),
+                image: None,
+            }),
+        }
+    }
+
+    /// Index of a view's slice, and how many there are
+    pub fn position(&self, axis: Axis) -> (usize, usize) {
+        (self.point[axis.index()], self.volume.count(axis))
+    }
+
+    /// Title of a view, e.g. "Axial 12/40"
+    pub fn title(&self, axis: Axis) -> String {
+        let (index, count) = self.position(axis);
+        format!("{} {}/{count}", self.volume.name(axis), index + 1)
+    }
+
+    /// Picks the next view to navigate
+    pub fn cycle_active(&mut self) {
+        let position = Axis::ALL.iter().position(|&a| a == self.active);
+        let next = position.map_or(0, |i| (i + 1) % Axis::ALL.len());
+        self.active = Axis::ALL[next];
+    }
+
+    /// Moves the active view through the volume, staying inside it
+    pub fn step(&mut self, slices: i64) {
+        let index = self.active.index();
+        let count = self.volume.count(self.active) as i64;
+        self.point[index] = (self.point[index] as i64 + slices).clamp(0, count - 1) as usize;
+    }
+
+    /// Moves the crosshair within the active view, by view pixels
+    pub fn move_point(&mut self, rows: i64, columns: i64) {
+        let axis = self.active;
+        let (x, y) = self.volume.view_position(axis, self.point);
+        let (width, height) = self.volume.view_size(axis);
+        let row = (y.floor() as i64 + rows).clamp(0, height as i64 - 1) as usize;
+        let column = (x.floor() as i64 + columns).clamp(0, width as i64 - 1) as usize;
+        self.point = self.volume.view_voxel(axis, self.point, (row, column));
+    }
+
+    /// Records where each view is drawn
+    pub fn set_areas(&mut self, areas: [Rect; 3], font_size: (u16, u16)) -> bool {
+        let mut changed = false;
+        for (view, area) in self.views.iter_mut().zip(areas) {
+            let viewport = Viewport {
+                area,
+                font_size,
+                ..view.viewport
+            };
+            changed |= viewport != view.viewport;
+            view.viewport = viewport;
+        }
+        changed
+    }
+
+    /// Moves the crosshair under a terminal cell and makes its view the
+    /// active one. Returns false when the cell is over no view.
+    pub fn click(&mut self, column: u16, row: u16) -> bool {
+        for view in &self.views {
+            let size = self.volume.view_size(view.axis);
+            let size = (size.0 as u32, size.1 as u32);
+            if let Some((y, x)) = view.viewport.cell_to_image(size, column, row) {
+                self.active = view.axis;
+                self.point =
+                    self.volume
+                        .view_voxel(view.axis, self.point, (y as usize, x as usize));
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Redraws every view with the window and the crosshair
+    pub fn render(&mut self, picker: &mut Picker, voi: Option<&Voi>) {
+        for view in &mut self.views {
+            let frame = self.volume.reformat(view.axis, self.point);
+            let transform = voi
+                .map(|v| v.transform.clone())
+                .unwrap_or_else(|| VoiTransform::Window(frame.full_range_window()));
+            let image = DynamicImage::ImageLuma8(frame.render(&transform)).to_rgb8();
+            let mut canvas = view.viewport.compose(&image);
+            let size = image.dimensions();
+            let (x, y) = self.volume.view_position(view.axis, self.point);
+            let (width, height) = (size.0 as f64, size.1 as f64);
+            let to_canvas = |point| view.viewport.image_to_canvas(size, point);
+            canvas::draw_line(
+                &mut canvas,
+                to_canvas((0.0, y)),
+                to_canvas((width, y)),
+                canvas::CURSOR,
+            );
+            canvas::draw_line(
+                &mut canvas,
+                to_canvas((x, 0.0)),
+                to_canvas((x, height)),
+                canvas::CURSOR,
+            );
+            view.image = Some(picker.new_resize_protocol(DynamicImage::ImageRgb8(canvas)));
+        }
+    }
+
+    /// Value and patient position under the crosshair, then the volume's
+    /// warnings
+    pub fn status_lines(&self, unit: Option<&str>) -> Vec<String> {
+        let [x, y, z] = self.volume.patient_point(self.point);
+        let mut readout = format!("({x:.1}, {y:.1}, {z:.1}) mm");
+        if let Some(value) = self.volume.value(self.point) {
+            readout.push_str(&format!(" | {value:.1}"));
+            if let Some(unit) = unit {
+                readout.push_str(&format!(" {unit}"));
+            }
+        }
+        let mut lines = vec![readout];
+        lines.extend(self.volume.warnings.iter().cloned());
+        lines
+    }
+}
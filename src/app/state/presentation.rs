@@ -0,0 +1,98 @@
+use super::canvas;
+use crate::dicom::{
+    presentation_states_for, DisplayTransform, FolderHeaders, GraphicType, PresentationState,
+};
+use dicom_pixeldata::image::RgbImage;
+use std::path::{Path, PathBuf};
+
+/// Presentation states offered for the displayed image, and the one applied
+#[derive(Default)]
+pub struct Presentations {
+    /// Files referencing the image, the one given on the command line first
+    pub files: Vec<PathBuf>,
+    /// Whether the image's folder was searched
+    searched: bool,
+    /// Index of the applied file in `files`
+    cursor: Option<usize>,
+    pub applied: Option<PresentationState>,
+}
+
+impl Presentations {
+    /// Whether the image's folder was searched
+    pub fn searched(&self) -> bool {
+        self.searched
+    }
+
+    /// Adds the presentation states among the headers of the image's folder
+    /// that reference it, the first time only
+    pub fn search(&mut self, headers: &FolderHeaders, path: &Path, uid: &str) {
+        if self.searched {
+            return;
+        }
+        self.searched = true;
+        for file in presentation_states_for(headers, path, uid) {
+            if !self.files.contains(&file) {
+                self.files.push(file);
+            }
+        }
+    }
+
+    /// Whether a file has been picked since the image was shown
+    pub fn is_cycling(&self) -> bool {
+        self.cursor.is_some()
+    }
+
+    /// Moves on to the next file; after the last one, starts over and
+    /// returns `None`
+    pub fn advance(&mut self) -> Option<PathBuf> {
+        let next = self.cursor.map_or(0, |i| i + 1);
+        let file = self.files.get(next).cloned();
+        self.cursor = file.as_ref().map(|_| next);
+        file
+    }
+
+    /// Texts of the applied state on a frame, for the status lines
+    pub fn texts_on(&self, frame: u32) -> impl Iterator<Item = String> + '_ {
+        self.applied
+            .iter()
+            .flat_map(|presentation| &presentation.texts)
+            .filter(move |t| t.applies_on(frame) && !t.text.is_empty())
+            .map(|t| format!("GSPS: {}", t.text))
+    }
+
+    /// Graphics of the applied state on a frame, with a marker where each
+    /// text is attached
+    pub fn draw(
+        &self,
+        canvas: &mut RgbImage,
+        frame: u32,
+        display: &DisplayTransform,
+        to_canvas: impl Fn((f64, f64)) -> (f64, f64),
+    ) {
+        let Some(presentation) = &self.applied else {
+            return;
+        };
+        for graphic in presentation.graphics.iter().filter(|g| g.applies_on(frame)) {
+            let outline: Vec<(f64, f64)> = graphic
+                .outline(display)
+                .into_iter()
+                .map(&to_canvas)
+                .collect();
+            match (graphic.kind, &outline[..]) {
+                (GraphicType::Point, [point, ..]) => {
+                    canvas::draw_crosshair(canvas, *point, canvas::PRESENTATION)
+                }
+                _ => {
+                    for pair in outline.windows(2) {
+                        canvas::draw_line(canvas, pair[0], pair[1], canvas::PRESENTATION);
+                    }
+                }
+            }
+        }
+        for text in presentation.texts.iter().filter(|t| t.applies_on(frame)) {
+            if let Some(anchor) = text.anchor(display) {
+                canvas::draw_crosshair(canvas, to_canvas(anchor), canvas::PRESENTATION);
+            }
+        }
+    }
+}
@@ -1,14 +1,168 @@
-use ratatui_image::picker::Picker;
+use super::canvas;
+use super::decoder::{DecodeJob, PrefetchJob};
+use super::export::{ExportJob, ExportStyle};
+use super::folders::{FolderSearch, Folders};
+use super::measure::{MeasurementSession, Tool};
+use super::mpr::{Mpr, VolumeJob};
+use super::presentation::Presentations;
+use super::reference::ReferenceLines;
+use super::segmentation::SegmentationLayer;
+use super::viewport::Viewport;
+use crate::dicom::{
+    measurements_json, rendered_frame, segmentations_for, series_reference_planes, Calibration,
+    CornerText, DisplayTransform, FolderHeaders, FramePixels, Histogram, ImagePlane, ImageTarget,
+    Measurement, OverlayPlane, PaletteLut, PresentationState, ReferencePlane, Shape, ValueUnits,
+    Voi, VoiTransform, Window, WINDOW_PRESETS,
+};
+use dicom_pixeldata::image::DynamicImage;
+use dicom_pixeldata::DecodedPixelData;
+use ratatui::layout::Rect;
+use ratatui_image::picker::{Picker, ProtocolType};
 use ratatui_image::protocol::StatefulProtocol;
-use std::path::Path;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+/// Frames decoded ahead of the displayed one
+const PREFETCH_FRAMES: u32 = 4;
+
+/// Bins of the histogram panel
+const HISTOGRAM_BINS: usize = 128;
+
+/// Cine speed when the file gives no frame timing
+const DEFAULT_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What the inspector cursor points at
+#[derive(Clone, Debug, PartialEq)]
+pub struct Inspection {
+    pub row: u32,
+    pub column: u32,
+    /// Stored value, for grayscale images
+    pub stored: Option<f64>,
+    /// Value after RescaleSlope/Intercept, for grayscale images
+    pub value: Option<f64>,
+    pub unit: Option<String>,
+    pub suv: Option<f64>,
+    pub rgb: Option<[u8; 3]>,
+    /// Patient coordinate in mm
+    pub patient: Option<[f64; 3]>,
+}
+
+impl Inspection {
+    /// One-line readout shown under the preview
+    pub fn describe(&self) -> String {
+        let mut parts = vec![format!("r{} c{}", self.row, self.column)];
+        if let Some(stored) = self.stored {
+            parts.push(format!("SV {stored}"));
+        }
+        if let Some(value) = self.value {
+            match &self.unit {
+                Some(unit) => parts.push(format!("{value:.1} {unit}")),
+                None => parts.push(format!("{value:.1}")),
+            }
+        }
+        if let Some(suv) = self.suv {
+            parts.push(format!("SUV {suv:.2}"));
+        }
+        if let Some([r, g, b]) = self.rgb {
+            parts.push(format!("RGB {r},{g},{b}"));
+        }
+        if let Some([x, y, z]) = self.patient {
+            parts.push(format!("({x:.1}, {y:.1}, {z:.1}) mm"));
+        }
+        parts.join(" | ")
+    }
+}
+
+/// Mouse drag panning a zoomed image
+struct PanDrag {
+    /// Cell under the mouse at the last move
+    last: (u16, u16),
+    moved: bool,
+}
+
+/// Cine playback state
+pub struct Cine {
+    pub interval: Duration,
+    pub last_advance: Instant,
+}
+
 pub struct Preview {
     pub show: bool,
     pub image: Option<StatefulProtocol>,
     pub error: Option<String>,
     pub pending_since: Option<Instant>,
     pub picker: Option<Picker>,
+    /// Decode running in the background
+    pub job: Option<DecodeJob>,
+    /// Decoded pixel data of every frame in the file
+    pub source: Option<Arc<DecodedPixelData<'static>>>,
+    /// Decoded values of the displayed frame
+    pub pixels: Option<FramePixels>,
+    /// Frames converted ahead of display, keyed by index
+    pub frames: HashMap<u32, FramePixels>,
+    /// Frames being converted ahead on a worker
+    pub prefetch_job: Option<PrefetchJob>,
+    /// Image export running in the background, kept across files
+    pub export_job: Option<ExportJob>,
+    /// Index of the displayed frame
+    pub frame: u32,
+    pub frame_count: u32,
+    pub frame_interval: Duration,
+    pub cine: Option<Cine>,
+    /// Frame geometry, one plane per frame or a single shared plane
+    pub planes: Vec<ImagePlane>,
+    pub units: ValueUnits,
+    /// Where the image is drawn on screen
+    pub viewport: Viewport,
+    /// Inspector cursor as (row, column), when the inspector is on
+    pub cursor: Option<(u32, u32)>,
+    /// VOI LUTs and windows stored in the file
+    pub vois: Vec<Voi>,
+    /// The VOI applied to the preview; `None` spans the full value range
+    pub voi: Option<Voi>,
+    voi_cursor: usize,
+    /// File the preview was decoded from
+    pub path: Option<PathBuf>,
+    pub calibration: Option<Calibration>,
+    /// Measurement tool and the measurements taken this session
+    pub measure: MeasurementSession,
+    /// Outcome of the last export
+    pub notice: Option<String>,
+    /// Overlay planes stored in the file
+    pub overlays: Vec<OverlayPlane>,
+    pub show_overlays: bool,
+    /// Patient, study and series text for the image corners
+    pub corners: CornerText,
+    pub show_corners: bool,
+    /// Stored values marking padding, left out of the histogram
+    pub padding: Option<(f64, f64)>,
+    /// Value distribution of the displayed frame, while the panel is shown
+    pub histogram: Option<Histogram>,
+    pub show_histogram: bool,
+    /// Colors of a PALETTE COLOR image
+    pub palette: Option<PaletteLut>,
+    /// Preview drawn over the whole terminal
+    pub fullscreen: bool,
+    drag: Option<PanDrag>,
+    /// Reformatted views of the file's volume, shown instead of the image
+    pub mpr: Option<Mpr>,
+    /// Volume being built for the reformatted views
+    pub volume_job: Option<VolumeJob>,
+    pub frame_of_reference: Option<String>,
+    /// Pinned reference lines, kept across files
+    pub reference: ReferenceLines,
+    pub sop_instance_uid: Option<String>,
+    /// Presentation states offered for the displayed image
+    pub presentations: Presentations,
+    /// Structure set or segmentation drawn over its series, kept across files
+    pub segmentation: SegmentationLayer,
+    /// Headers of the folders searched, kept across files
+    pub folders: Folders,
+    /// Search to run once the displayed image's folder is read
+    waiting: Option<FolderSearch>,
 }
 
 impl Preview {
@@ -19,17 +173,132 @@ impl Preview {
             error: None,
             pending_since: None,
             picker,
+            job: None,
+            source: None,
+            pixels: None,
+            frames: HashMap::new(),
+            prefetch_job: None,
+            export_job: None,
+            frame: 0,
+            frame_count: 0,
+            frame_interval: DEFAULT_FRAME_INTERVAL,
+            cine: None,
+            planes: Vec::new(),
+            units: ValueUnits::default(),
+            viewport: Viewport::default(),
+            cursor: None,
+            vois: Vec::new(),
+            voi: None,
+            voi_cursor: 0,
+            path: None,
+            calibration: None,
+            measure: MeasurementSession::default(),
+            notice: None,
+            overlays: Vec::new(),
+            show_overlays: true,
+            corners: CornerText::default(),
+            show_corners: false,
+            padding: None,
+            histogram: None,
+            show_histogram: false,
+            palette: None,
+            fullscreen: false,
+            drag: None,
+            mpr: None,
+            volume_job: None,
+            frame_of_reference: None,
+            reference: ReferenceLines::default(),
+            sop_instance_uid: None,
+            presentations: Presentations::default(),
+            segmentation: SegmentationLayer::default(),
+            folders: Folders::default(),
+            waiting: None,
+        }
+    }
+
+    /// Magnification, relative to fitting the image in the pane
+    pub fn zoom_description(&self) -> Option<String> {
+        self.pixels.as_ref()?;
+        Some(match self.viewport.zoom {
+            1.0 => "Fit".to_string(),
+            zoom => format!("Zoom {zoom:.1}x"),
+        })
+    }
+
+    /// Name of the graphics protocol used to draw the preview
+    pub fn protocol_name(&self) -> Option<&'static str> {
+        self.picker.map(|picker| match picker.protocol_type() {
+            ProtocolType::Kitty => "Kitty",
+            ProtocolType::Sixel => "Sixel",
+            ProtocolType::Iterm2 => "iTerm2",
+            ProtocolType::Halfblocks => "half-blocks",
+        })
+    }
+
+    /// Current window or VOI LUT, for grayscale images only
+    pub fn window_description(&self) -> Option<String> {
+        let Some(FramePixels::Monochrome(frame)) = &self.pixels else {
+            return None;
+        };
+        Some(match &self.voi {
+            Some(voi) => voi.describe(),
+            None => {
+                let window = frame.full_range_window();
+                Voi::window("Full range", window.center, window.width).describe()
+            }
+        })
+    }
+
+    /// Frame counter for multi-frame images, marked while cine plays
+    pub fn frame_description(&self) -> Option<String> {
+        if self.frame_count < 2 {
+            return None;
         }
+        let playing = if self.cine.is_some() { " ▶" } else { "" };
+        Some(format!(
+            "Frame {}/{}{playing}",
+            self.frame + 1,
+            self.frame_count
+        ))
     }
 
     pub fn toggle(&mut self, path: Option<&Path>) {
         self.show = !self.show;
 
-        if self.show && self.image.is_none() && self.error.is_none() {
+        if self.show && self.image.is_none() && self.error.is_none() && self.job.is_none() {
             self.decode(path);
         }
     }
 
+    /// Forgets the decoded image, before another file is shown. The
+    /// display settings, the measurements put aside, the pinned reference
+    /// lines, the segmentation, the folders read and an export in progress
+    /// are kept.
+    pub fn clear(&mut self) {
+        let mut measure = std::mem::take(&mut self.measure);
+        measure.put_aside(self.path.take());
+        *self = Self {
+            show: self.show,
+            pending_since: self.pending_since,
+            frame_interval: self.frame_interval,
+            viewport: Viewport {
+                zoom: 1.0,
+                pan: (0.0, 0.0),
+                ..self.viewport
+            },
+            show_overlays: self.show_overlays,
+            show_corners: self.show_corners,
+            show_histogram: self.show_histogram,
+            fullscreen: self.fullscreen,
+            measure,
+            reference: std::mem::take(&mut self.reference),
+            segmentation: std::mem::take(&mut self.segmentation),
+            folders: std::mem::take(&mut self.folders),
+            export_job: self.export_job.take(),
+            ..Self::new(self.picker)
+        };
+    }
+
     pub fn tick_debounce(&mut self, path: Option<&Path>) {
         if let Some(since) = self.pending_since {
             if since.elapsed() >= Duration::from_millis(100) {
@@ -39,17 +308,10 @@ impl Preview {
         }
     }
 
+    /// Starts decoding on a worker thread, cancelling any decode in flight
     pub fn decode(&mut self, path: Option<&Path>) {
-        use dicom_pixeldata::PixelDecoder;
-        use ratatui_image::picker::ProtocolType;
-
-        let Some(picker) = &self.picker else {
-            self.error = Some("Preview requires a Kitty-compatible terminal".into());
-            return;
-        };
-
-        if picker.protocol_type() != ProtocolType::Kitty {
-            self.error = Some("Preview requires a Kitty-compatible terminal".into());
+        if self.picker.is_none() {
+            self.error = Some("Preview requires terminal graphics support".into());
             return;
         }
 
@@ -58,32 +320,1090 @@ impl Preview {
             return;
         };
 
-        let obj = match dicom::object::open_file(path) {
-            Ok(obj) => obj,
+        self.path = Some(path.to_path_buf());
+        self.job = Some(DecodeJob::spawn(path.to_path_buf()));
+    }
+
+    /// Time spent on the decode in flight, if any
+    pub fn decoding_for(&self) -> Option<Duration> {
+        self.job.as_ref().map(|job| job.started.elapsed())
+    }
+
+    /// Takes the worker's result once it is ready and displays it
+    pub fn poll_decode(&mut self) {
+        self.poll_volume();
+        self.poll_prefetch();
+        self.poll_export();
+        self.poll_folders();
+        self.poll_segmentation();
+        let Some(result) = self.job.as_ref().and_then(DecodeJob::try_take) else {
+            return;
+        };
+        self.job = None;
+
+        let decoded = match result {
+            Ok(decoded) => decoded,
             Err(e) => {
-                self.error = Some(format!("Failed to open DICOM file: {e}"));
+                self.error = Some(e);
                 return;
             }
         };
 
-        let pixel_data = match obj.decode_pixel_data() {
-            Ok(pd) => pd,
-            Err(e) => {
-                self.error = Some(format!("Failed to decode pixel data: {e}"));
-                return;
+        self.vois = decoded.vois;
+        self.voi = self.vois.first().cloned();
+        self.voi_cursor = 0;
+        self.frame_interval = decoded.frame_interval.unwrap_or(DEFAULT_FRAME_INTERVAL);
+        self.planes = decoded.planes;
+        self.frame_of_reference = decoded.frame_of_reference;
+        self.sop_instance_uid = decoded.sop_instance_uid;
+        self.units = decoded.units;
+        self.calibration = decoded.calibration;
+        self.overlays = decoded.overlays;
+        self.corners = decoded.corners;
+        self.padding = decoded.padding;
+        self.palette = decoded.palette;
+        if let Some(path) = &self.path {
+            self.measure.restore(path);
+        }
+        self.frame_count = decoded.source.number_of_frames();
+        self.frames.clear();
+        self.frames.insert(0, decoded.first_frame);
+        self.source = Some(Arc::new(decoded.source));
+        self.show_frame(0);
+        if !self.presentations.files.is_empty() {
+            self.cycle_presentation();
+        }
+    }
+
+    /// Displays a frame, converting it unless it was decoded ahead, then
+    /// prepares the frames that follow it
+    pub fn show_frame(&mut self, frame: u32) {
+        let Some(source) = &self.source else {
+            return;
+        };
+        let pixels = match self.frames.remove(&frame) {
+            Some(pixels) => pixels,
+            None => match FramePixels::from_decoded(source, frame, self.palette.as_ref()) {
+                Ok(pixels) => pixels,
+                Err(e) => {
+                    self.error = Some(format!("Failed to convert to image: {e}"));
+                    return;
+                }
+            },
+        };
+        self.frame = frame;
+        self.pixels = Some(pixels);
+        self.update_histogram();
+        self.render();
+        self.prefetch();
+    }
+
+    /// Converts the next few frames on a worker so that stepping and cine
+    /// do not wait. While a conversion runs, the frames still missing are
+    /// left for when it is done.
+    fn prefetch(&mut self) {
+        let Some(source) = &self.source else {
+            return;
+        };
+        let count = self.frame_count;
+        let ahead: Vec<u32> = (1..=PREFETCH_FRAMES.min(count.saturating_sub(1)))
+            .map(|offset| (self.frame + offset) % count)
+            .collect();
+        self.frames.retain(|frame, _| ahead.contains(frame));
+        if self.prefetch_job.is_some() {
+            return;
+        }
+        let missing: Vec<u32> = ahead
+            .into_iter()
+            .filter(|frame| !self.frames.contains_key(frame))
+            .collect();
+        if !missing.is_empty() {
+            let palette = self.palette.clone();
+            self.prefetch_job = Some(PrefetchJob::spawn(Arc::clone(source), palette, missing));
+        }
+    }
+
+    /// Keeps the frames converted ahead, then fills in those still missing
+    fn poll_prefetch(&mut self) {
+        let Some(result) = self.prefetch_job.as_ref().and_then(PrefetchJob::try_take) else {
+            return;
+        };
+        self.prefetch_job = None;
+        if let Ok(frames) = result {
+            self.frames.extend(frames);
+        }
+        self.prefetch();
+    }
+
+    pub fn next_frame(&mut self) {
+        if self.frame_count > 1 {
+            self.show_frame((self.frame + 1) % self.frame_count);
+        }
+    }
+
+    pub fn previous_frame(&mut self) {
+        if self.frame_count > 1 {
+            self.show_frame((self.frame + self.frame_count - 1) % self.frame_count);
+        }
+    }
+
+    /// Starts or stops playing the frames at the file's frame rate
+    pub fn toggle_cine(&mut self) {
+        self.cine = match self.cine {
+            Some(_) => None,
+            None if self.frame_count > 1 => Some(Cine {
+                interval: self.frame_interval,
+                last_advance: Instant::now(),
+            }),
+            None => None,
+        };
+    }
+
+    pub fn tick_cine(&mut self) {
+        let Some(cine) = &mut self.cine else {
+            return;
+        };
+        if cine.last_advance.elapsed() >= cine.interval {
+            cine.last_advance = Instant::now();
+            self.next_frame();
+        }
+    }
+
+    /// How long the event loop may wait before the preview needs a tick
+    pub fn poll_timeout(&self) -> Duration {
+        let working = self.job.is_some()
+            || self.prefetch_job.is_some()
+            || self.export_job.is_some()
+            || self.volume_job.is_some()
+            || self.folders.is_reading()
+            || self.segmentation.is_pending();
+        let idle = Duration::from_millis(if working { 50 } else { 100 });
+        match &self.cine {
+            Some(cine) => cine
+                .interval
+                .saturating_sub(cine.last_advance.elapsed())
+                .clamp(Duration::from_millis(1), idle),
+            None => idle,
+        }
+    }
+
+    /// Records where the image is drawn, redrawing it when the pane moves
+    /// or changes size
+    pub fn set_area(&mut self, area: Rect) {
+        let font_size = self.picker.map(|p| p.font_size()).unwrap_or_default();
+        let mut viewport = Viewport {
+            area,
+            font_size,
+            ..self.viewport
+        };
+        if viewport != self.viewport {
+            if let Some(size) = self.shown_size() {
+                viewport.pan_by((0.0, 0.0), size);
             }
+            self.viewport = viewport;
+            self.render();
+        }
+    }
+
+    /// Multiplies the zoom, from fitting the pane up to `MAX_ZOOM`
+    pub fn zoom_by(&mut self, factor: f64) {
+        let Some(size) = self.shown_size() else {
+            return;
+        };
+        self.viewport.set_zoom(self.viewport.zoom * factor, size);
+        self.render();
+    }
+
+    /// Moves the view by a number of terminal cells
+    pub fn pan(&mut self, columns: i32, rows: i32) {
+        let Some(image) = self.shown_size() else {
+            return;
+        };
+        let Some((x, y)) = self.viewport.cell_extent(image) else {
+            return;
+        };
+        self.viewport
+            .pan_by((columns as f64 * x, rows as f64 * y), image);
+        self.render();
+    }
+
+    pub fn toggle_fullscreen(&mut self) {
+        self.fullscreen = !self.fullscreen;
+    }
+
+    /// Builds the volume of the displayed file for the reformatted views,
+    /// or returns to the image
+    pub fn toggle_mpr(&mut self) {
+        let waiting = self
+            .waiting
+            .take_if(|search| *search == FolderSearch::Volume);
+        if self.mpr.take().is_some() || self.volume_job.take().is_some() || waiting.is_some() {
+            self.notice = None;
+            self.render();
+            return;
+        }
+        let (Some(path), Some(_)) = (self.path.clone(), &self.pixels) else {
+            return;
+        };
+        let Some(headers) = self.folder_headers(&path, FolderSearch::Volume) else {
+            return;
+        };
+        match headers {
+            Ok(headers) => {
+                self.notice = Some("Building volume...".into());
+                self.volume_job = Some(VolumeJob::spawn(path, headers));
+            }
+            Err(e) => self.notice = Some(e),
+        }
+    }
+
+    /// Takes the volume once it is built, starting on the displayed slice.
+    /// A volume that cannot be reformatted leaves the image and says why.
+    fn poll_volume(&mut self) {
+        let Some(result) = self.volume_job.as_ref().and_then(VolumeJob::try_take) else {
+            return;
+        };
+        self.volume_job = None;
+        match result {
+            Ok(volume) => {
+                let slice = self.displayed_plane().map_or(volume.slices / 2, |plane| {
+                    volume.nearest_slice(plane.position)
+                });
+                self.notice = None;
+                self.mpr = Some(Mpr::new(volume, slice));
+                self.render();
+            }
+            Err(e) => self.notice = Some(e),
+        }
+    }
+
+    /// Records where the reformatted views are drawn, redrawing them when
+    /// they move or change size
+    pub fn set_mpr_areas(&mut self, areas: [Rect; 3]) {
+        let font_size = self.picker.map(|p| p.font_size()).unwrap_or_default();
+        if let Some(mpr) = &mut self.mpr {
+            if mpr.set_areas(areas, font_size) {
+                self.render();
+            }
+        }
+    }
+
+    /// Picks the next reformatted view to navigate
+    pub fn cycle_mpr_view(&mut self) {
+        if let Some(mpr) = &mut self.mpr {
+            mpr.cycle_active();
+        }
+    }
+
+    /// Moves the active reformatted view through the volume
+    pub fn step_mpr(&mut self, slices: i64) {
+        if let Some(mpr) = &mut self.mpr {
+            mpr.step(slices);
+            self.render();
+        }
+    }
+
+    /// Moves the crosshair within the active reformatted view
+    pub fn move_mpr_point(&mut self, rows: i64, columns: i64) {
+        if let Some(mpr) = &mut self.mpr {
+            mpr.move_point(rows, columns);
+            self.render();
+        }
+    }
+
+    fn click_mpr(&mut self, column: u16, row: u16) -> bool {
+        let hit = self.mpr.as_mut().is_some_and(|mpr| mpr.click(column, row));
+        if hit {
+            self.render();
+        }
+        hit
+    }
+
+    /// Redraws the decoded frame with the current VOI, the overlays, the
+    /// measurements and the inspector cursor
+    fn render(&mut self) {
+        let (Some(pixels), Some(_)) = (&self.pixels, &self.picker) else {
+            return;
+        };
+        if let (Some(mpr), Some(picker)) = (&mut self.mpr, &mut self.picker) {
+            mpr.render(picker, self.voi.as_ref());
+            return;
+        }
+        let mut image =
+            rendered_frame(pixels, self.voi.as_ref(), self.shown_overlays(), self.frame);
+        if let Some(target) = self.segmentation_target() {
+            self.segmentation.paint(&mut image, &target);
+        }
+        let image = match self.display() {
+            Some(display) => display.apply(&image),
+            None => image,
+        };
+        let mut canvas = self.viewport.compose(&image);
+        let to_canvas =
+            |(row, column): (u32, u32)| self.to_canvas((column as f64 + 0.5, row as f64 + 0.5));
+        for measurement in self.measure.on_frame(self.frame) {
+            Tool::of(&measurement.kind).draw(
+                &mut canvas,
+                to_canvas((measurement.start[0], measurement.start[1])),
+                to_canvas((measurement.end[0], measurement.end[1])),
+                canvas::MEASUREMENT,
+            );
+        }
+        let to_point = |point| self.to_canvas(point);
+        if let Some(plane) = self.displayed_plane() {
+            let frame_of_reference = self.frame_of_reference.as_deref();
+            self.reference
+                .draw(&mut canvas, plane, frame_of_reference, to_point);
+        }
+        if let Some(display) = self.display() {
+            self.presentations
+                .draw(&mut canvas, self.frame, &display, to_point);
+        }
+        if let Some(target) = self.segmentation_target() {
+            self.segmentation.draw(&mut canvas, &target, to_point);
+        }
+        let measure = &self.measure;
+        if let (Some(tool), Some(anchor), Some(cursor)) =
+            (measure.tool, measure.anchor, self.cursor)
+        {
+            tool.draw(
+                &mut canvas,
+                to_canvas(anchor),
+                to_canvas(cursor),
+                canvas::CURSOR,
+            );
+        }
+        if let Some(cursor) = self.cursor {
+            canvas::draw_crosshair(&mut canvas, to_canvas(cursor), canvas::CURSOR);
+        }
+        if let Some(picker) = &mut self.picker {
+            self.image = Some(picker.new_resize_protocol(DynamicImage::ImageRgb8(canvas)));
+        }
+    }
+
+    /// Spatial transform of the applied presentation state
+    fn display(&self) -> Option<DisplayTransform> {
+        let presentation = self.presentations.applied.as_ref()?;
+        let pixels = self.pixels.as_ref()?;
+        Some(presentation.transform(pixels.dimensions()))
+    }
+
+    /// Size of the image as displayed, after the presentation state's
+    /// displayed area and rotation
+    fn shown_size(&self) -> Option<(u32, u32)> {
+        let size = self.pixels.as_ref()?.dimensions();
+        Some(self.display().map_or(size, |display| display.size()))
+    }
+
+    /// Canvas position of an image position given as (x, y)
+    fn to_canvas(&self, point: (f64, f64)) -> (f64, f64) {
+        let (Some(size), display) = (self.shown_size(), self.display()) else {
+            return point;
         };
+        let point = display.map_or(point, |display| display.to_display(point));
+        self.viewport.image_to_canvas(size, point)
+    }
 
-        let dyn_img = match pixel_data.to_dynamic_image(0) {
-            Ok(img) => img,
+    /// Image pixel, as (row, column), under a terminal cell
+    fn cell_pixel(&self, column: u16, row: u16) -> Option<(u32, u32)> {
+        let size = self.shown_size()?;
+        let (row, column) = self.viewport.cell_to_image(size, column, row)?;
+        let Some(display) = self.display() else {
+            return Some((row, column));
+        };
+        let (x, y) = display.to_image((column as f64 + 0.5, row as f64 + 0.5));
+        let (width, height) = self.pixels.as_ref()?.dimensions();
+        let inside = x >= 0.0 && y >= 0.0 && x < width as f64 && y < height as f64;
+        inside.then_some((y as u32, x as u32))
+    }
+
+    /// Applies the next presentation state that references the displayed
+    /// image: the one given on the command line, then those found in its
+    /// folder, then none
+    pub fn cycle_presentation(&mut self) {
+        let (Some(path), Some(uid)) = (self.path.clone(), self.sop_instance_uid.clone()) else {
+            self.notice = Some("No SOPInstanceUID to match presentation states with".into());
+            return;
+        };
+        if !self.presentations.searched() {
+            let Some(headers) = self.folder_headers(&path, FolderSearch::Presentations) else {
+                return;
+            };
+            let headers = headers.unwrap_or_default();
+            self.presentations.search(&headers, &path, &uid);
+        }
+        let cycling = self.presentations.is_cycling();
+        self.viewport.zoom = 1.0;
+        self.viewport.pan = (0.0, 0.0);
+        let Some(file) = self.presentations.advance() else {
+            self.notice = Some(match cycling {
+                true => "Presentation state off".into(),
+                false => "No presentation state references this image".into(),
+            });
+            if self.presentations.applied.take().is_some() {
+                self.voi = self.vois.first().cloned();
+            }
+            self.render();
+            return;
+        };
+        match PresentationState::open(&file, &uid) {
+            Ok(presentation) => {
+                let name = file.file_name().unwrap_or_default().to_string_lossy();
+                self.notice = Some(format!("{} ({name})", presentation.label));
+                if let Some(voi) = &presentation.voi {
+                    self.voi = Some(voi.clone());
+                }
+                self.presentations.applied = Some(presentation);
+            }
             Err(e) => {
-                self.error = Some(format!("Failed to convert to image: {e}"));
+                self.notice = Some(e);
+                self.presentations.applied = None;
+            }
+        }
+        self.render();
+    }
+
+    /// Reads an RT structure set or segmentation, kept over the images
+    /// shown after until another one is loaded
+    pub fn open_segmentation(&mut self, path: &Path) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        self.notice = Some(format!("Reading {name}..."));
+        self.segmentation.open(path);
+    }
+
+    /// Draws the segmentation once it has been read
+    fn poll_segmentation(&mut self) {
+        if let Some(notice) = self.segmentation.poll() {
+            self.notice = Some(notice);
+            self.render();
+        }
+    }
+
+    /// Loads the next RT structure set or segmentation in the displayed
+    /// image's folder that refers to it, then none
+    pub fn cycle_segmentation(&mut self) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        let Some(headers) = self.folder_headers(&path, FolderSearch::Segmentations) else {
+            return;
+        };
+        let found = segmentations_for(
+            &headers.unwrap_or_default(),
+            &path,
+            self.sop_instance_uid.as_deref(),
+            self.frame_of_reference.as_deref(),
+        );
+        let next = match self.segmentation.path() {
+            Some(current) => found.iter().position(|f| f == current).map_or(0, |i| i + 1),
+            None => 0,
+        };
+        if let Some(file) = found.get(next) {
+            self.open_segmentation(file);
+            return;
+        }
+        self.notice = Some(match self.segmentation.unload() {
+            true => "Segmentation off".into(),
+            false => "No RT structure set or segmentation refers to this image".into(),
+        });
+        self.render();
+    }
+
+    /// Moves the ROI selection on, for showing or hiding
+    pub fn select_next_roi(&mut self) {
+        if !self.segmentation.select_next_roi() {
+            self.notice = Some("No RT structure set or segmentation loaded".into());
+        }
+    }
+
+    /// Shows or hides the selected ROI
+    pub fn toggle_roi(&mut self) {
+        if self.segmentation.toggle_roi() {
+            self.render();
+        }
+    }
+
+    /// The displayed frame as segmentations see it. Its plane is left out
+    /// unless it shares the segmentation's frame of reference.
+    fn segmentation_target(&self) -> Option<ImageTarget<'_>> {
+        let segmentation = self.segmentation.loaded.as_ref()?;
+        let same_frame = self.frame_of_reference.is_some()
+            && self.frame_of_reference == segmentation.frame_of_reference;
+        Some(ImageTarget {
+            sop_instance_uid: self.sop_instance_uid.as_deref(),
+            frame: self.frame,
+            plane: self.displayed_plane().filter(|_| same_frame),
+            size: self.pixels.as_ref()?.dimensions(),
+        })
+    }
+
+    /// Geometry of the displayed frame
+    fn displayed_plane(&self) -> Option<&ImagePlane> {
+        self.planes
+            .get(self.frame as usize)
+            .or_else(|| self.planes.first())
+    }
+
+    /// Pins the displayed slice for reference lines; on the pinned file,
+    /// moves on to its whole series, then turns the lines off
+    pub fn cycle_reference(&mut self) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        let pinned = match self.reference.pinned_from(&path) {
+            Some(false) => {
+                let Some(headers) = self.folder_headers(&path, FolderSearch::ReferenceSeries)
+                else {
+                    return;
+                };
+                headers
+                    .and_then(|headers| series_reference_planes(&headers, &path))
+                    .map(|planes| (planes, true))
+            }
+            Some(true) => {
+                self.reference.clear();
+                self.notice = Some("Reference lines off".into());
+                self.render();
                 return;
             }
+            None => self
+                .displayed_reference(&path)
+                .map(|plane| (vec![plane], false))
+                .ok_or_else(|| "No image position or orientation to pin".to_string()),
+        };
+        self.notice = Some(match pinned {
+            Ok((planes, series)) => self.reference.pin(planes, path, series),
+            Err(e) => e,
+        });
+        self.render();
+    }
+
+    /// Headers of the folder of `path`. Until they are read, starts reading
+    /// them and returns `None`; `search` runs again once they are.
+    fn folder_headers(
+        &mut self,
+        path: &Path,
+        search: FolderSearch,
+    ) -> Option<Result<Arc<FolderHeaders>, String>> {
+        let folder = FolderHeaders::folder_of(path);
+        if let Some(headers) = self.folders.get(folder) {
+            return Some(headers);
+        }
+        self.folders.request(folder);
+        self.waiting = Some(search);
+        self.notice = Some("Reading the folder...".into());
+        None
+    }
+
+    /// Runs the search that waited for the folder once it has been read
+    fn poll_folders(&mut self) {
+        if !self.folders.poll() {
+            return;
+        }
+        match self.waiting.take() {
+            Some(FolderSearch::Presentations) => self.cycle_presentation(),
+            Some(FolderSearch::Segmentations) => self.cycle_segmentation(),
+            Some(FolderSearch::ReferenceSeries) => self.cycle_reference(),
+            Some(FolderSearch::Volume) => self.toggle_mpr(),
+            None => {}
+        }
+    }
+
+    fn displayed_reference(&self, path: &Path) -> Option<ReferencePlane> {
+        let name = path.file_name()?.to_string_lossy();
+        let label = match self.frame_count {
+            0 | 1 => name.into_owned(),
+            _ => format!("{name} frame {}", self.frame + 1),
+        };
+        Some(ReferencePlane {
+            label,
+            frame_of_reference: self.frame_of_reference.clone(),
+            plane: *self.displayed_plane()?,
+            size: self.pixels.as_ref()?.dimensions(),
+        })
+    }
+
+    fn shown_overlays(&self) -> &[OverlayPlane] {
+        if self.show_overlays {
+            &self.overlays
+        } else {
+            &[]
+        }
+    }
+
+    /// Shows or hides the overlay planes
+    pub fn toggle_overlays(&mut self) {
+        self.show_overlays = !self.show_overlays;
+        self.render();
+    }
+
+    pub fn toggle_corners(&mut self) {
+        self.show_corners = !self.show_corners;
+    }
+
+    /// Corner text when it is turned on, with the window and frame in the
+    /// bottom-right corner
+    pub fn corner_text(&self) -> Option<CornerText> {
+        if !self.show_corners || self.pixels.is_none() {
+            return None;
+        }
+        let mut corners = self.corners.clone();
+        corners.bottom_right = [self.window_description(), self.frame_description()]
+            .into_iter()
+            .flatten()
+            .collect();
+        Some(corners)
+    }
+
+    /// Shows or hides the histogram of the displayed frame
+    pub fn toggle_histogram(&mut self) {
+        self.show_histogram = !self.show_histogram;
+        self.update_histogram();
+    }
+
+    fn update_histogram(&mut self) {
+        self.histogram = match &self.pixels {
+            Some(FramePixels::Monochrome(frame)) if self.show_histogram => {
+                Histogram::from_frame(frame, self.padding, HISTOGRAM_BINS)
+            }
+            _ => None,
+        };
+    }
+
+    /// Lowest and highest values of the applied window
+    pub fn window_bounds(&self) -> Option<(f64, f64)> {
+        let Some(FramePixels::Monochrome(frame)) = &self.pixels else {
+            return None;
+        };
+        let window = self
+            .voi
+            .as_ref()
+            .map(|voi| voi.transform.as_window())
+            .unwrap_or_else(|| frame.full_range_window());
+        Some((
+            window.center - window.width / 2.0,
+            window.center + window.width / 2.0,
+        ))
+    }
+
+    fn is_monochrome(&self) -> bool {
+        matches!(self.pixels, Some(FramePixels::Monochrome(_)))
+    }
+
+    /// Applies the next VOI LUT or window stored in the file
+    pub fn next_file_window(&mut self) {
+        if !self.is_monochrome() || self.vois.is_empty() {
+            return;
+        }
+        if self.voi.as_ref() == self.vois.get(self.voi_cursor) {
+            self.voi_cursor = (self.voi_cursor + 1) % self.vois.len();
+        }
+        self.voi = Some(self.vois[self.voi_cursor].clone());
+        self.render();
+    }
+
+    /// Applies one of [`WINDOW_PRESETS`]
+    pub fn apply_preset(&mut self, index: usize) {
+        let Some(&(name, center, width)) = WINDOW_PRESETS.get(index) else {
+            return;
+        };
+        if !self.is_monochrome() {
+            return;
+        }
+        self.voi = Some(Voi::window(name, center, width));
+        self.render();
+    }
+
+    /// Moves the window center and widens or narrows it, in steps
+    /// proportional to the current width
+    pub fn adjust_window(&mut self, center_steps: i32, width_steps: i32) {
+        let Some(FramePixels::Monochrome(frame)) = &self.pixels else {
+            return;
+        };
+        let window = self
+            .voi
+            .as_ref()
+            .map(|voi| voi.transform.as_window())
+            .unwrap_or_else(|| frame.full_range_window());
+        let step = (window.width / 20.0).max(1.0);
+        let adjusted = Window {
+            center: window.center + center_steps as f64 * step,
+            width: (window.width + width_steps as f64 * 2.0 * step).max(1.0),
+            ..window
+        };
+        self.voi = Some(Voi {
+            label: "Custom".into(),
+            transform: VoiTransform::Window(adjusted),
+        });
+        self.render();
+    }
+
+    /// Returns to the first VOI stored in the file
+    pub fn reset_window(&mut self) {
+        if !self.is_monochrome() {
+            return;
+        }
+        self.voi_cursor = 0;
+        self.voi = self.vois.first().cloned();
+        self.render();
+    }
+
+    /// Shows or hides the pixel inspector, starting at the image center
+    pub fn toggle_inspector(&mut self) {
+        let Some(pixels) = &self.pixels else {
+            return;
+        };
+        self.cursor = match self.cursor {
+            Some(_) => None,
+            None => {
+                let (width, height) = pixels.dimensions();
+                Some((height / 2, width / 2))
+            }
+        };
+        self.render();
+    }
+
+    /// Moves the inspector cursor by whole pixels, staying on the image
+    pub fn move_cursor(&mut self, rows: i64, columns: i64) {
+        let (Some(pixels), Some((row, column))) = (&self.pixels, self.cursor) else {
+            return;
+        };
+        let (width, height) = pixels.dimensions();
+        self.cursor = Some((
+            (row as i64 + rows).clamp(0, height as i64 - 1) as u32,
+            (column as i64 + columns).clamp(0, width as i64 - 1) as u32,
+        ));
+        self.render();
+    }
+
+    /// Places the inspector cursor under a terminal cell. Returns false when
+    /// the cell is not over the image.
+    pub fn inspect_cell(&mut self, column: u16, row: u16) -> bool {
+        let Some(pixel) = self.cell_pixel(column, row) else {
+            return false;
+        };
+        self.cursor = Some(pixel);
+        self.render();
+        true
+    }
+
+    /// Values under the inspector cursor
+    pub fn inspection(&self) -> Option<Inspection> {
+        let (row, column) = self.cursor?;
+        let pixels = self.pixels.as_ref()?;
+        let plane = self.displayed_plane();
+        let mut inspection = Inspection {
+            row,
+            column,
+            stored: None,
+            value: None,
+            unit: None,
+            suv: None,
+            rgb: None,
+            patient: plane.map(|p| p.patient_point(row as f64, column as f64)),
+        };
+        match pixels {
+            FramePixels::Monochrome(frame) => {
+                let value = frame.value(row, column)?;
+                inspection.stored = frame.stored_value(row, column);
+                inspection.value = Some(value);
+                inspection.unit = self.units.unit.clone();
+                inspection.suv = self.units.suv_factor.map(|factor| value * factor);
+            }
+            FramePixels::Color(image) => {
+                inspection.rgb = image.get_pixel_checked(column, row).map(|p| p.0);
+            }
+        }
+        Some(inspection)
+    }
+
+    /// Picks the next measurement tool, or none after the last one. ROIs
+    /// need grayscale values, so color images only offer distances.
+    pub fn cycle_tool(&mut self) {
+        let Some(pixels) = &self.pixels else {
+            return;
+        };
+        let tools: &[Tool] = if self.is_monochrome() {
+            &[
+                Tool::Roi(Shape::Rectangle),
+                Tool::Roi(Shape::Ellipse),
+                Tool::Distance,
+            ]
+        } else {
+            &[Tool::Distance]
+        };
+        self.measure.tool = match self.measure.tool {
+            None => tools.first().copied(),
+            Some(tool) => tools
+                .iter()
+                .position(|t| *t == tool)
+                .and_then(|i| tools.get(i + 1))
+                .copied(),
+        };
+        self.measure.anchor = None;
+        if self.measure.tool.is_some() && self.cursor.is_none() {
+            let (width, height) = pixels.dimensions();
+            self.cursor = Some((height / 2, width / 2));
+        }
+        self.render();
+    }
+
+    /// Anchors the measurement at the cursor, or finishes it there
+    pub fn mark_point(&mut self) {
+        let (Some(_), Some(cursor)) = (self.measure.tool, self.cursor) else {
+            return;
+        };
+        match self.measure.anchor {
+            None => self.measure.anchor = Some(cursor),
+            Some(_) => self.commit_measurement(),
+        }
+        self.render();
+    }
+
+    fn commit_measurement(&mut self) {
+        let measure = &mut self.measure;
+        let (Some(tool), Some(anchor), Some(cursor)) =
+            (measure.tool, measure.anchor.take(), self.cursor)
+        else {
+            return;
+        };
+        let (start, end) = ([anchor.0, anchor.1], [cursor.0, cursor.1]);
+        let calibration = self.calibration.as_ref();
+        let measurement = match (tool, &self.pixels) {
+            (Tool::Roi(shape), Some(FramePixels::Monochrome(frame))) => {
+                Measurement::roi(frame, self.frame, shape, start, end, calibration)
+            }
+            (Tool::Roi(_), _) => None,
+            (Tool::Distance, _) => Some(Measurement::distance(self.frame, start, end, calibration)),
+        };
+        self.measure.measurements.extend(measurement);
+    }
+
+    /// Removes the newest measurement of the displayed frame
+    pub fn delete_last_measurement(&mut self) {
+        if self.measure.delete_last(self.frame) {
+            self.render();
+        }
+    }
+
+    /// Moves the cursor under a clicked cell, anchoring a measurement there
+    /// when a tool is picked. Without a tool or cursor, a press starts
+    /// panning instead. Returns false when the cell is not over the image.
+    pub fn press_cell(&mut self, column: u16, row: u16) -> bool {
+        if self.mpr.is_some() {
+            return self.click_mpr(column, row);
+        }
+        if self.pixels.is_none() {
+            return false;
+        }
+        if self.measure.tool.is_none() && self.cursor.is_none() {
+            let over_image = self.cell_pixel(column, row).is_some();
+            if over_image {
+                self.drag = Some(PanDrag {
+                    last: (column, row),
+                    moved: false,
+                });
+            }
+            return over_image;
+        }
+        if !self.inspect_cell(column, row) {
+            return false;
+        }
+        if self.measure.tool.is_some() {
+            self.measure.anchor = self.cursor;
+            self.render();
+        }
+        true
+    }
+
+    /// Pans the image or moves the cursor while the mouse is dragged
+    pub fn drag_cell(&mut self, column: u16, row: u16) -> bool {
+        if self.mpr.is_some() {
+            return self.click_mpr(column, row);
+        }
+        let Some(drag) = &mut self.drag else {
+            return self.inspect_cell(column, row);
+        };
+        let (last_column, last_row) = drag.last;
+        drag.last = (column, row);
+        drag.moved = true;
+        self.pan(
+            last_column as i32 - column as i32,
+            last_row as i32 - row as i32,
+        );
+        true
+    }
+
+    /// Finishes a pan or a measurement dragged with the mouse. A click that
+    /// did not pan turns on the inspector. Returns false when nothing was
+    /// being dragged.
+    pub fn release_cell(&mut self, column: u16, row: u16) -> bool {
+        if let Some(drag) = self.drag.take() {
+            if !drag.moved {
+                self.inspect_cell(column, row);
+            }
+            return true;
+        }
+        if self.measure.tool.is_none() || self.measure.anchor.is_none() {
+            return false;
+        }
+        self.inspect_cell(column, row);
+        if self.cursor != self.measure.anchor {
+            self.commit_measurement();
+            self.render();
+        }
+        true
+    }
+
+    /// Folder of the shown file, where its exports are written
+    pub fn export_dir(&self) -> PathBuf {
+        match self.path.as_deref().and_then(Path::parent) {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        }
+    }
+
+    /// Shows the full path an export went to, or why it failed
+    pub fn report_export(&mut self, result: &Result<PathBuf, String>) {
+        self.notice = Some(match result {
+            Ok(path) => {
+                let full = std::path::absolute(path).unwrap_or_else(|_| path.clone());
+                format!("Saved {}", full.display())
+            }
+            Err(e) => e.clone(),
+        });
+    }
+
+    /// The file being shown, and its name without extension
+    fn source_file(&self) -> Result<(&Path, String), String> {
+        let path = self
+            .path
+            .as_deref()
+            .ok_or_else(|| "No DICOM file path available".to_string())?;
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok((path, stem))
+    }
+
+    /// Writes the file's measurements as `<name>.measurements.json` in `dir`
+    pub fn export_measurements(&self, dir: &Path) -> Result<PathBuf, String> {
+        let (path, stem) = self.source_file()?;
+        if self.measure.measurements.is_empty() {
+            return Err("No measurements to export".into());
+        }
+        let output = dir.join(format!("{stem}.measurements.json"));
+        let json = measurements_json(
+            &path.display().to_string(),
+            self.calibration.as_ref(),
+            &self.measure.measurements,
+        )
+        .map_err(|e| format!("Failed to export measurements: {e}"))?;
+        fs::write(&output, json).map_err(|e| format!("Failed to export measurements: {e}"))?;
+        Ok(output)
+    }
+
+    /// Starts writing the displayed frame, as rendered, to
+    /// `<name>-frame<N>.png` in `dir`
+    pub fn export_frame(&mut self, dir: &Path) {
+        let job = self.source_file().and_then(|(_, stem)| {
+            let pixels = self.pixels.clone().ok_or("No image to export")?;
+            let output = dir.join(format!("{stem}-frame{}.png", self.frame + 1));
+            Ok(ExportJob::frame(
+                pixels,
+                self.frame,
+                self.export_style(),
+                output,
+            ))
+        });
+        self.start_export(job, format!("Exporting frame {}...", self.frame + 1));
+    }
+
+    /// Starts writing every frame, as rendered, to an animated `<name>.gif`
+    /// in `dir`
+    pub fn export_cine(&mut self, dir: &Path) {
+        let job = self.source_file().and_then(|(_, stem)| {
+            let source = self.source.clone().ok_or("No image to export")?;
+            let output = dir.join(format!("{stem}.gif"));
+            let palette = self.palette.clone();
+            Ok(ExportJob::cine(
+                source,
+                palette,
+                self.export_style(),
+                output,
+            ))
+        });
+        self.start_export(job, format!("Exporting {} frames...", self.frame_count));
+    }
+
+    fn export_style(&self) -> ExportStyle {
+        ExportStyle {
+            voi: self.voi.clone(),
+            overlays: self.shown_overlays().to_vec(),
+            frame_interval: self.frame_interval,
+        }
+    }
+
+    /// Runs an export in the background, replacing any in progress, or says
+    /// why it could not start
+    fn start_export(&mut self, job: Result<ExportJob, String>, progress: String) {
+        match job {
+            Ok(job) => {
+                self.export_job = Some(job);
+                self.notice = Some(progress);
+            }
+            Err(e) => self.report_export(&Err(e)),
+        }
+    }
+
+    /// Reports where the export went once it is written
+    fn poll_export(&mut self) {
+        let Some(result) = self.export_job.as_ref().and_then(ExportJob::try_take) else {
+            return;
         };
+        self.export_job = None;
+        self.report_export(&result);
+    }
 
-        let mut picker = self.picker.take().unwrap();
-        self.image = Some(picker.new_resize_protocol(dyn_img));
-        self.picker = Some(picker);
+    /// Lines shown under the preview: the inspector readout, the tool in
+    /// use and the measurements of the displayed frame
+    pub fn status_lines(&self) -> Vec<String> {
+        if let Some(mpr) = &self.mpr {
+            let mut lines = mpr.status_lines(self.units.unit.as_deref());
+            lines.extend(self.notice.clone());
+            return lines;
+        }
+        let mut lines: Vec<String> = self
+            .inspection()
+            .map(|i| i.describe())
+            .into_iter()
+            .collect();
+        if let Some(tool) = self.measure.tool {
+            let step = match self.measure.anchor {
+                None => "a or drag: first point",
+                Some(_) => "a: finish",
+            };
+            lines.push(format!("{}: {step} | d: delete | E: export", tool.name()));
+        }
+        lines.extend(self.measure.on_frame(self.frame).map(Measurement::describe));
+        lines.extend(self.presentations.texts_on(self.frame));
+        if let Some(target) = self.segmentation_target() {
+            lines.extend(self.segmentation.status(&target));
+        }
+        let frame_of_reference = self.frame_of_reference.as_deref();
+        if !self.reference.planes.is_empty()
+            && self.reference.shown(frame_of_reference).next().is_none()
+        {
+            lines.push("Reference lines need the same FrameOfReferenceUID".into());
+        }
+        if self.measure.tool.is_some() || !self.measure.measurements.is_empty() {
+            match &self.calibration {
+                Some(calibration) => lines.extend(calibration.warning().map(String::from)),
+                None => lines.push("No pixel spacing: sizes are in pixels".into()),
+            }
+        }
+        lines.extend(self.notice.clone());
+        lines
     }
 }
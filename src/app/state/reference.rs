@@ -0,0 +1,66 @@
+use super::canvas;
+use crate::dicom::{ImagePlane, ReferencePlane};
+use dicom_pixeldata::image::RgbImage;
+use std::path::{Path, PathBuf};
+
+/// Pinned images drawn as reference lines on the images shown after
+#[derive(Default)]
+pub struct ReferenceLines {
+    pub planes: Vec<ReferencePlane>,
+    /// File the lines were pinned from, and whether its whole series is
+    /// pinned
+    source: Option<(PathBuf, bool)>,
+}
+
+impl ReferenceLines {
+    /// Whether the lines were pinned from `path`: `Some(true)` for its whole
+    /// series, `Some(false)` for the one slice
+    pub fn pinned_from(&self, path: &Path) -> Option<bool> {
+        match &self.source {
+            Some((source, series)) if source == path => Some(*series),
+            _ => None,
+        }
+    }
+
+    /// Pins planes of `path`. Returns the notice describing them.
+    pub fn pin(&mut self, planes: Vec<ReferencePlane>, path: PathBuf, series: bool) -> String {
+        let notice = match (series, planes.as_slice()) {
+            (false, [plane]) => format!("Reference line of {}", plane.label),
+            _ => format!("Reference lines of {} series images", planes.len()),
+        };
+        self.planes = planes;
+        self.source = Some((path, series));
+        notice
+    }
+
+    pub fn clear(&mut self) {
+        self.planes.clear();
+        self.source = None;
+    }
+
+    /// Pinned planes that share a frame of reference
+    pub fn shown<'a>(
+        &'a self,
+        frame_of_reference: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a ReferencePlane> {
+        self.planes.iter().filter(move |reference| {
+            reference.frame_of_reference.is_some()
+                && reference.frame_of_reference.as_deref() == frame_of_reference
+        })
+    }
+
+    /// Draws where the shown planes cross the displayed one
+    pub fn draw(
+        &self,
+        canvas: &mut RgbImage,
+        plane: &ImagePlane,
+        frame_of_reference: Option<&str>,
+        to_canvas: impl Fn((f64, f64)) -> (f64, f64),
+    ) {
+        for reference in self.shown(frame_of_reference) {
+            if let Some([from, to]) = reference.line_on(plane) {
+                canvas::draw_line(canvas, to_canvas(from), to_canvas(to), canvas::REFERENCE);
+            }
+        }
+    }
+}
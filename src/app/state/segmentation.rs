@@ -0,0 +1,177 @@
+use super::canvas;
+use super::decoder::Job;
+use crate::dicom::{ImageTarget, Roi, Segmentation};
+use dicom_pixeldata::image::{Rgb, RgbImage};
+use std::path::{Path, PathBuf};
+
+/// RT structure set or segmentation reading, which decodes every segment
+/// frame
+pub type SegmentationJob = Job<Segmentation>;
+
+impl SegmentationJob {
+    pub fn spawn(path: PathBuf) -> Self {
+        Self::cancellable(move |cancelled| Segmentation::open(&path, cancelled))
+    }
+}
+
+/// RT structure set or segmentation drawn over the images of its series,
+/// kept over the files shown after it
+#[derive(Default)]
+pub struct SegmentationLayer {
+    pub loaded: Option<Segmentation>,
+    /// File it was read from
+    path: Option<PathBuf>,
+    /// Whether each of its ROIs is drawn
+    pub roi_shown: Vec<bool>,
+    /// ROI that the show/hide key acts on
+    pub roi_cursor: usize,
+    /// The one being read; a newer one replaces and cancels it
+    pending: Option<(PathBuf, SegmentationJob)>,
+}
+
+impl SegmentationLayer {
+    /// Replaces the loaded segmentation, every ROI shown. Returns the
+    /// notice describing it.
+    pub fn load(&mut self, path: &Path, segmentation: Segmentation) -> String {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let notice = format!(
+            "{} {}: {} ROIs ({name})",
+            segmentation.kind.name(),
+            segmentation.label,
+            segmentation.rois.len()
+        );
+        self.roi_shown = vec![true; segmentation.rois.len()];
+        self.roi_cursor = 0;
+        self.loaded = Some(segmentation);
+        self.path = Some(path.to_path_buf());
+        notice
+    }
+
+    /// Starts reading `path` on a worker, replacing the loaded one once
+    /// read
+    pub fn open(&mut self, path: &Path) {
+        self.pending = Some((
+            path.to_path_buf(),
+            SegmentationJob::spawn(path.to_path_buf()),
+        ));
+    }
+
+    /// Loads the segmentation that has been read, if any. Returns the
+    /// notice describing it, or why it could not be read.
+    pub fn poll(&mut self) -> Option<String> {
+        let (path, job) = self.pending.as_ref()?;
+        let result = job.try_take()?;
+        let path = path.clone();
+        self.pending = None;
+        Some(match result {
+            Ok(segmentation) => self.load(&path, segmentation),
+            Err(e) => e,
+        })
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Drops the loaded segmentation and any being read. Returns false when
+    /// none was loaded.
+    pub fn unload(&mut self) -> bool {
+        self.pending = None;
+        self.path = None;
+        self.roi_shown.clear();
+        self.loaded.take().is_some()
+    }
+
+    /// File being read, or else the one loaded
+    pub fn path(&self) -> Option<&Path> {
+        match &self.pending {
+            Some((path, _)) => Some(path),
+            None => self.path.as_deref(),
+        }
+    }
+
+    /// Moves the ROI selection on. Returns false without ROIs.
+    pub fn select_next_roi(&mut self) -> bool {
+        if self.roi_shown.is_empty() {
+            return false;
+        }
+        self.roi_cursor = (self.roi_cursor + 1) % self.roi_shown.len();
+        true
+    }
+
+    /// Shows or hides the selected ROI. Returns false without ROIs.
+    pub fn toggle_roi(&mut self) -> bool {
+        let Some(shown) = self.roi_shown.get_mut(self.roi_cursor) else {
+            return false;
+        };
+        *shown = !*shown;
+        true
+    }
+
+    fn shown_rois(&self) -> impl Iterator<Item = &Roi> {
+        self.loaded
+            .iter()
+            .flat_map(|segmentation| &segmentation.rois)
+            .zip(&self.roi_shown)
+            .filter(|(_, shown)| **shown)
+            .map(|(roi, _)| roi)
+    }
+
+    /// Blends the shown segment masks of the target frame into the image
+    pub fn paint(&self, image: &mut RgbImage, target: &ImageTarget) {
+        for roi in self.shown_rois() {
+            for mask in roi.masks_on(target) {
+                for (x, y, pixel) in image.enumerate_pixels_mut() {
+                    if mask.is_set(y, x) {
+                        for (channel, color) in pixel.0.iter_mut().zip(roi.color) {
+                            *channel = ((*channel as u16 + color as u16) / 2) as u8;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Outlines the shown structure set contours on the target frame
+    pub fn draw(
+        &self,
+        canvas: &mut RgbImage,
+        target: &ImageTarget,
+        to_canvas: impl Fn((f64, f64)) -> (f64, f64),
+    ) {
+        for roi in self.shown_rois() {
+            let color = Rgb(roi.color);
+            for (outline, closed) in roi.outlines_on(target) {
+                let outline: Vec<_> = outline.into_iter().map(&to_canvas).collect();
+                if let [point] = outline[..] {
+                    canvas::draw_crosshair(canvas, point, color);
+                }
+                for pair in outline.windows(2) {
+                    canvas::draw_line(canvas, pair[0], pair[1], color);
+                }
+                if let (true, [first, .., last]) = (closed, &outline[..]) {
+                    canvas::draw_line(canvas, *last, *first, color);
+                }
+            }
+        }
+    }
+
+    /// Status line of the selected ROI, with the count of ROIs on the
+    /// target frame
+    pub fn status(&self, target: &ImageTarget) -> Option<String> {
+        let segmentation = self.loaded.as_ref()?;
+        let roi = segmentation.rois.get(self.roi_cursor)?;
+        let shown = match self.roi_shown.get(self.roi_cursor) {
+            Some(true) => "shown",
+            _ => "hidden",
+        };
+        let on_image = segmentation.rois.iter().filter(|r| r.is_on(target)).count();
+        Some(format!(
+            "{} ROI {}/{}: {} ({shown}) | {on_image} on this image",
+            segmentation.kind.name(),
+            self.roi_cursor + 1,
+            segmentation.rois.len(),
+            roi.name
+        ))
+    }
+}
@@ -7,6 +7,8 @@ pub struct Tags {
     pub visible: Vec<DicomTag>,
     pub all: Vec<DicomTag>,
     pub filtered: Option<Vec<DicomTag>>,
+    /// Restricts the table to top-level rows with these tag labels
+    pub only: Option<Vec<String>>,
     pub table_state: TableState,
     pub area: Rect,
 }
@@ -22,6 +24,7 @@ impl Tags {
             visible,
             all,
             filtered: None,
+            only: None,
             table_state,
             area: Rect::default(),
         }
@@ -39,7 +42,7 @@ impl Tags {
     }
 
     pub fn filter(&mut self, query: &str) {
-        if query.is_empty() {
+        if query.is_empty() && self.only.is_none() {
             self.filtered = None;
             self.rebuild_visible_tags();
         } else {
@@ -51,6 +54,11 @@ impl Tags {
                     tag.tag.to_lowercase().contains(&query)
                         || tag.name.to_lowercase().contains(&query)
                 })
+                .filter(|tag| {
+                    self.only
+                        .as_ref()
+                        .is_none_or(|only| only.contains(&tag.tag))
+                })
                 .cloned()
                 .collect();
             self.filtered = Some(filtered);
@@ -60,9 +68,7 @@ impl Tags {
     }
 
     pub fn clear_filter(&mut self) {
-        self.filtered = None;
-        self.rebuild_visible_tags();
-        self.reset_selection();
+        self.filter("");
     }
 
     pub fn reset_selection(&mut self) {
@@ -127,6 +133,44 @@ impl Tags {
         }
     }
 
+    /// Row labels from the top level down to each visible row
+    pub fn visible_locations(&self) -> Vec<Vec<String>> {
+        let mut stack: Vec<String> = Vec::new();
+        self.visible
+            .iter()
+            .map(|tag| {
+                stack.truncate(tag.depth);
+                stack.push(tag.tag.clone());
+                stack.clone()
+            })
+            .collect()
+    }
+
+    /// Expands the rows along `location` and selects the last one
+    pub fn reveal(&mut self, location: &[String]) {
+        if location.is_empty() {
+            return;
+        }
+
+        let mut tags: &mut Vec<DicomTag> = self.active_tags_mut();
+        for label in location {
+            let Some(tag) = tags.iter_mut().find(|t| &t.tag == label) else {
+                return;
+            };
+            tag.is_expanded = tag.is_expandable;
+            tags = &mut tag.children;
+        }
+        self.rebuild_visible_tags();
+
+        if let Some(idx) = self
+            .visible_locations()
+            .iter()
+            .position(|l| l.as_slice() == location)
+        {
+            self.table_state.select(Some(idx));
+        }
+    }
+
     fn build_visible_tags_from(tags: &[DicomTag]) -> Vec<DicomTag> {
         let mut visible = Vec::new();
         Self::collect_visible_tags(tags, &mut visible);
@@ -152,10 +196,9 @@ impl Tags {
     }
 
     fn active_tags_mut(&mut self) -> &mut Vec<DicomTag> {
-        if self.filtered.is_some() {
-            self.filtered.as_mut().unwrap()
-        } else {
-            &mut self.all
+        match &mut self.filtered {
+            Some(filtered) => filtered,
+            None => &mut self.all,
         }
     }
 
@@ -0,0 +1,91 @@
+use super::decoder::Job;
+use crate::dicom::{load_thumbnail, Thumbnail};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// Thumbnail reading for the explorer
+pub type ThumbnailJob = Job<Thumbnail>;
+
+impl ThumbnailJob {
+    pub fn spawn(path: PathBuf) -> Self {
+        Self::cancellable(move |cancelled| load_thumbnail(&path, cancelled))
+    }
+}
+
+/// Thumbnails kept before the least recently hovered ones are dropped
+const CAPACITY: usize = 64;
+
+/// Thumbnails of the files hovered in the explorer, kept per path
+pub struct Thumbnails {
+    cache: HashMap<PathBuf, Result<Thumbnail, String>>,
+    /// Cached paths, least recently requested first
+    recent: VecDeque<PathBuf>,
+    capacity: usize,
+    /// The one being read; a newer request replaces and cancels it
+    pending: Option<(PathBuf, ThumbnailJob)>,
+}
+
+impl Default for Thumbnails {
+    fn default() -> Self {
+        Self::with_capacity(CAPACITY)
+    }
+}
+
+impl Thumbnails {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            cache: HashMap::new(),
+            recent: VecDeque::new(),
+            capacity: capacity.max(1),
+            pending: None,
+        }
+    }
+
+    /// Starts reading the thumbnail of `path` unless it is known or on its
+    /// way
+    pub fn request(&mut self, path: &Path) {
+        if self.cache.contains_key(path) {
+            self.touch(path);
+            return;
+        }
+        if self.is_pending(path) {
+            return;
+        }
+        self.pending = Some((path.to_path_buf(), ThumbnailJob::spawn(path.to_path_buf())));
+    }
+
+    /// Keeps the thumbnail that has been read, if any
+    pub fn poll(&mut self) {
+        let Some((path, job)) = &self.pending else {
+            return;
+        };
+        if let Some(result) = job.try_take() {
+            let path = path.clone();
+            self.pending = None;
+            if self.cache.len() >= self.capacity {
+                if let Some(oldest) = self.recent.pop_front() {
+                    self.cache.remove(&oldest);
+                }
+            }
+            self.cache.insert(path.clone(), result);
+            self.recent.push_back(path);
+        }
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&Result<Thumbnail, String>> {
+        self.cache.get(path)
+    }
+
+    pub fn is_pending(&self, path: &Path) -> bool {
+        self.pending.as_ref().is_some_and(|(p, _)| p == path)
+    }
+
+    /// Moves a cached path to the most recently requested end
+    fn touch(&mut self, path: &Path) {
+        if let Some(index) = self.recent.iter().position(|p| p == path) {
+            if let Some(path) = self.recent.remove(index) {
+                self.recent.push_back(path);
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use dicom_pixeldata::image::{imageops, RgbImage};
+use ratatui::layout::Rect;
+
+/// Largest zoom, relative to fitting the image in the pane
+pub const MAX_ZOOM: f64 = 32.0;
+
+/// Where the preview image sits on screen. The image is composed at the
+/// pane's pixel size so that screen cells map back to image pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    /// Terminal cells the image is drawn in
+    pub area: Rect,
+    /// Pixel size of one terminal cell
+    pub font_size: (u16, u16),
+    /// Magnification relative to fitting the image in the pane
+    pub zoom: f64,
+    /// Offset of the view's center from the image's center, in image pixels
+    pub pan: (f64, f64),
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            area: Rect::default(),
+            font_size: (0, 0),
+            zoom: 1.0,
+            pan: (0.0, 0.0),
+        }
+    }
+}
+
+impl Viewport {
+    /// Pixel size of the pane, once it has been laid out
+    pub fn canvas_size(&self) -> Option<(u32, u32)> {
+        let width = self.area.width as u32 * self.font_size.0 as u32;
+        let height = self.area.height as u32 * self.font_size.1 as u32;
+        (width > 0 && height > 0).then_some((width, height))
+    }
+
+    /// Canvas pixels per image pixel, and the canvas position of the
+    /// image's top-left corner
+    fn placement(&self, image: (u32, u32)) -> Option<(f64, (f64, f64))> {
+        let (width, height) = self.canvas_size()?;
+        if image.0 == 0 || image.1 == 0 {
+            return None;
+        }
+        let scale = f64::min(
+            width as f64 / image.0 as f64,
+            height as f64 / image.1 as f64,
+        ) * self.zoom;
+        let origin = (
+            width as f64 / 2.0 - (image.0 as f64 / 2.0 + self.pan.0) * scale,
+            height as f64 / 2.0 - (image.1 as f64 / 2.0 + self.pan.1) * scale,
+        );
+        Some((scale, origin))
+    }
+
+    /// Image pixels per terminal cell, horizontally and vertically
+    pub fn cell_extent(&self, image: (u32, u32)) -> Option<(f64, f64)> {
+        let (scale, _) = self.placement(image)?;
+        Some((
+            self.font_size.0 as f64 / scale,
+            self.font_size.1 as f64 / scale,
+        ))
+    }
+
+    /// Sets the zoom, keeping the pan within the image
+    pub fn set_zoom(&mut self, zoom: f64, image: (u32, u32)) {
+        self.zoom = zoom.clamp(1.0, MAX_ZOOM);
+        self.pan_by((0.0, 0.0), image);
+    }
+
+    /// Moves the view by image pixels, as far as the zoomed image reaches
+    /// past the pane
+    pub fn pan_by(&mut self, offset: (f64, f64), image: (u32, u32)) {
+        let (Some((width, height)), Some((scale, _))) = (self.canvas_size(), self.placement(image))
+        else {
+            return;
+        };
+        let limit =
+            |image: u32, canvas: u32| (image as f64 / 2.0 - canvas as f64 / (2.0 * scale)).max(0.0);
+        let (x, y) = (limit(image.0, width), limit(image.1, height));
+        self.pan = (
+            (self.pan.0 + offset.0).clamp(-x, x),
+            (self.pan.1 + offset.1).clamp(-y, y),
+        );
+    }
+
+    /// Canvas position of an image position, given as (x, y)
+    pub fn image_to_canvas(&self, image: (u32, u32), point: (f64, f64)) -> (f64, f64) {
+        match self.placement(image) {
+            Some((scale, origin)) => (origin.0 + point.0 * scale, origin.1 + point.1 * scale),
+            None => point,
+        }
+    }
+
+    /// Image pixel, as (row, column), under the center of a terminal cell
+    pub fn cell_to_image(&self, image: (u32, u32), column: u16, row: u16) -> Option<(u32, u32)> {
+        let area = self.area;
+        if column < area.x
+            || column >= area.x + area.width
+            || row < area.y
+            || row >= area.y + area.height
+        {
+            return None;
+        }
+        let (scale, origin) = self.placement(image)?;
+        let x = ((column - area.x) as f64 + 0.5) * self.font_size.0 as f64;
+        let y = ((row - area.y) as f64 + 0.5) * self.font_size.1 as f64;
+        let image_x = (x - origin.0) / scale;
+        let image_y = (y - origin.1) / scale;
+        let inside = image_x >= 0.0
+            && image_y >= 0.0
+            && image_x < image.0 as f64
+            && image_y < image.1 as f64;
+        inside.then_some((image_y as u32, image_x as u32))
+    }
+
+    /// The visible part of the image, scaled and placed on a black canvas
+    /// the size of the pane, or the image itself before the pane has been
+    /// laid out
+    pub fn compose(&self, image: &RgbImage) -> RgbImage {
+        let (Some((width, height)), Some((scale, origin))) =
+            (self.canvas_size(), self.placement(image.dimensions()))
+        else {
+            return image.clone();
+        };
+        let mut canvas = RgbImage::new(width, height);
+        let visible = |origin: f64, canvas: u32, image: u32| {
+            let first = (-origin / scale).floor().max(0.0) as u32;
+            let last = ((canvas as f64 - origin) / scale).ceil().min(image as f64) as u32;
+            (first, last.saturating_sub(first))
+        };
+        let (x, crop_width) = visible(origin.0, width, image.width());
+        let (y, crop_height) = visible(origin.1, height, image.height());
+        if crop_width == 0 || crop_height == 0 {
+            return canvas;
+        }
+
+        let crop = imageops::crop_imm(image, x, y, crop_width, crop_height).to_image();
+        let scaled_width = ((crop_width as f64 * scale).round() as u32).max(1);
+        let scaled_height = ((crop_height as f64 * scale).round() as u32).max(1);
+        let filter = if scale >= 1.0 {
+            imageops::FilterType::Nearest
+        } else {
+            imageops::FilterType::Triangle
+        };
+        let scaled = imageops::resize(&crop, scaled_width, scaled_height, filter);
+        imageops::overlay(
+            &mut canvas,
+            &scaled,
+            (origin.0 + x as f64 * scale).round() as i64,
+            (origin.1 + y as f64 * scale).round() as i64,
+        );
+        canvas
+    }
+}
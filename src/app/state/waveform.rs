@@ -0,0 +1,119 @@
+use crate::dicom::Waveform;
+
+/// Seconds shown when a waveform is opened, or less for shorter recordings
+const DEFAULT_SPAN: f64 = 10.0;
+
+/// Fewest samples the view zooms in to
+const MIN_SPAN_SAMPLES: f64 = 10.0;
+
+/// Share of the shown span one scroll step moves
+const SCROLL_FRACTION: f64 = 0.25;
+
+/// Time window and channel selection over the multiplex groups of a
+/// waveform object
+pub struct WaveformView {
+    pub waveforms: Vec<Waveform>,
+    /// Multiplex group shown
+    pub group: usize,
+    /// Seconds from the start of the recording
+    pub start: f64,
+    /// Seconds shown
+    pub span: f64,
+    /// Channel that Space toggles
+    pub cursor: usize,
+    /// Whether each channel of the group is plotted
+    pub shown: Vec<bool>,
+}
+
+impl WaveformView {
+    /// `None` without any waveform
+    pub fn new(waveforms: Vec<Waveform>) -> Option<Self> {
+        if waveforms.is_empty() {
+            return None;
+        }
+        let mut view = Self {
+            waveforms,
+            group: 0,
+            start: 0.0,
+            span: DEFAULT_SPAN,
+            cursor: 0,
+            shown: Vec::new(),
+        };
+        view.reset_group();
+        Some(view)
+    }
+
+    pub fn waveform(&self) -> &Waveform {
+        &self.waveforms[self.group]
+    }
+
+    fn reset_group(&mut self) {
+        let waveform = self.waveform();
+        let (span, channels) = (
+            waveform.duration().min(DEFAULT_SPAN),
+            waveform.channels.len(),
+        );
+        self.start = 0.0;
+        self.span = span;
+        self.cursor = 0;
+        self.shown = vec![true; channels];
+    }
+
+    /// Shows the next multiplex group, from its start
+    pub fn next_group(&mut self) {
+        self.group = (self.group + 1) % self.waveforms.len();
+        self.reset_group();
+    }
+
+    /// Moves the time window by quarter spans, staying in the recording
+    pub fn scroll(&mut self, steps: i32) {
+        let last = (self.waveform().duration() - self.span).max(0.0);
+        self.start = (self.start + steps as f64 * self.span * SCROLL_FRACTION).clamp(0.0, last);
+    }
+
+    /// Shows `factor` times more time, between a few samples and the whole
+    /// recording
+    pub fn zoom_by(&mut self, factor: f64) {
+        let waveform = self.waveform();
+        let (duration, shortest) = (
+            waveform.duration(),
+            MIN_SPAN_SAMPLES / waveform.sampling_frequency,
+        );
+        self.span = (self.span * factor).clamp(shortest.min(duration), duration);
+        self.start = self.start.clamp(0.0, (duration - self.span).max(0.0));
+    }
+
+    pub fn select_channel(&mut self, delta: i32) {
+        let count = self.shown.len() as i32;
+        if count > 0 {
+            self.cursor = (self.cursor as i32 + delta).rem_euclid(count) as usize;
+        }
+    }
+
+    pub fn toggle_channel(&mut self) {
+        if let Some(shown) = self.shown.get_mut(self.cursor) {
+            *shown = !*shown;
+        }
+    }
+
+    /// Channels plotted, by index
+    pub fn shown_channels(&self) -> Vec<usize> {
+        (0..self.shown.len()).filter(|&i| self.shown[i]).collect()
+    }
+
+    /// e.g. "Group 1/2 RHYTHM | 500 Hz | 0.00-10.00 s of 10.00 s"
+    pub fn title(&self) -> String {
+        let waveform = self.waveform();
+        let mut title = format!("Group {}/{}", self.group + 1, self.waveforms.len());
+        if let Some(label) = &waveform.label {
+            title.push_str(&format!(" {label}"));
+        }
+        format!(
+            "{title} | {} Hz | {:.2}-{:.2} s of {:.2} s",
+            waveform.sampling_frequency,
+            self.start,
+            self.start + self.span,
+            waveform.duration()
+        )
+    }
+}
@@ -0,0 +1,92 @@
+use dicom::core::Tag;
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// Text for the four corners of the image, as laid out by diagnostic
+/// viewers
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CornerText {
+    /// Patient
+    pub top_left: Vec<String>,
+    /// Study
+    pub top_right: Vec<String>,
+    /// Series and instance
+    pub bottom_left: Vec<String>,
+    /// Display settings, filled in by the viewer
+    pub bottom_right: Vec<String>,
+}
+
+/// YYYYMMDD as YYYY-MM-DD, other values unchanged
+fn format_date(date: &str) -> String {
+    if date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit()) {
+        format!("{}-{}-{}", &date[..4], &date[4..6], &date[6..])
+    } else {
+        date.to_string()
+    }
+}
+
+/// HHMMSS.FFFFFF as HH:MM:SS, other values unchanged
+fn format_time(time: &str) -> String {
+    let time = time.split('.').next().unwrap_or(time);
+    if time.len() == 6 && time.bytes().all(|b| b.is_ascii_digit()) {
+        format!("{}:{}:{}", &time[..2], &time[2..4], &time[4..])
+    } else {
+        time.to_string()
+    }
+}
+
+pub fn corner_text(obj: &InMemDicomObject) -> CornerText {
+    let text = |tag: Tag| {
+        obj.element(tag)
+            .ok()
+            .and_then(|e| e.to_str().ok())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let joined = |parts: Vec<Option<String>>| {
+        let line = parts.into_iter().flatten().collect::<Vec<_>>().join(" ");
+        (!line.is_empty()).then_some(line)
+    };
+
+    let top_left = [
+        text(tags::PATIENT_NAME).map(|name| {
+            name.split('^')
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        }),
+        text(tags::PATIENT_ID).map(|id| format!("ID: {id}")),
+        joined(vec![
+            text(tags::PATIENT_BIRTH_DATE).map(|d| format_date(&d)),
+            text(tags::PATIENT_SEX),
+            text(tags::PATIENT_AGE),
+        ]),
+    ];
+    let top_right = [
+        text(tags::INSTITUTION_NAME),
+        text(tags::STUDY_DESCRIPTION),
+        joined(vec![
+            text(tags::STUDY_DATE).map(|d| format_date(&d)),
+            text(tags::STUDY_TIME).map(|t| format_time(&t)),
+        ]),
+        text(tags::ACCESSION_NUMBER).map(|acc| format!("Acc: {acc}")),
+    ];
+    let bottom_left = [
+        joined(vec![
+            text(tags::MODALITY),
+            text(tags::SERIES_NUMBER).map(|n| format!("Se: {n}")),
+            text(tags::SERIES_DESCRIPTION),
+        ]),
+        text(tags::INSTANCE_NUMBER).map(|n| format!("Im: {n}")),
+        joined(vec![
+            text(tags::SLICE_THICKNESS).map(|t| format!("Thk: {t} mm")),
+            text(tags::SLICE_LOCATION).map(|l| format!("Loc: {l}")),
+        ]),
+    ];
+    CornerText {
+        top_left: top_left.into_iter().flatten().collect(),
+        top_right: top_right.into_iter().flatten().collect(),
+        bottom_left: bottom_left.into_iter().flatten().collect(),
+        bottom_right: Vec::new(),
+    }
+}
@@ -0,0 +1,25 @@
+use dicom::core::Tag;
+use dicom::object::InMemDicomObject;
+
+/// Items of a sequence, none when it is absent
+pub(super) fn items(obj: &InMemDicomObject, tag: Tag) -> &[InMemDicomObject] {
+    obj.element(tag)
+        .ok()
+        .and_then(|e| e.items())
+        .unwrap_or_default()
+}
+
+/// A string element without its padding, `None` when absent or empty
+pub(super) fn text(obj: &InMemDicomObject, tag: Tag) -> Option<String> {
+    let value = obj.element(tag).ok()?.to_str().ok()?;
+    Some(value.trim_end_matches(['\0', ' ']).trim().to_string()).filter(|v| !v.is_empty())
+}
+
+pub(super) fn float(obj: &InMemDicomObject, tag: Tag) -> Option<f64> {
+    obj.element(tag).ok()?.to_float64().ok()
+}
+
+/// A tag as it is shown in the tag table, (GGGG,EEEE)
+pub(crate) fn tag_label(tag: Tag) -> String {
+    format!("({:04X},{:04X})", tag.group(), tag.element())
+}
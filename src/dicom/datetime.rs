@@ -28,7 +28,7 @@ pub fn parse_dicom_datetime_delta_ms(vr: &str, baseline: &str, modified: &str) -
     }
 }
 
-fn parse_tm(s: &str) -> Option<NaiveTime> {
+pub(super) fn parse_tm(s: &str) -> Option<NaiveTime> {
     if s.contains('.') {
         NaiveTime::parse_from_str(s, "%H%M%S%.f").ok()
     } else {
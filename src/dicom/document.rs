@@ -0,0 +1,360 @@
+use super::attr::text;
+use dicom::dictionary_std::tags;
+use dicom::object::{open_file, InMemDicomObject};
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// The EncapsulatedDocument of an Encapsulated PDF, CDA, STL or other
+/// document object
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncapsulatedDocument {
+    pub mime_type: String,
+    pub title: Option<String>,
+    pub data: Vec<u8>,
+}
+
+impl EncapsulatedDocument {
+    /// `None` for objects without an EncapsulatedDocument
+    pub fn from_obj(obj: &InMemDicomObject) -> Option<Self> {
+        let mut data = obj
+            .element(tags::ENCAPSULATED_DOCUMENT)
+            .ok()?
+            .to_bytes()
+            .ok()?
+            .into_owned();
+        // The element is padded to an even length; the length attribute
+        // tells the document's own
+        if let Some(length) = obj
+            .element(tags::ENCAPSULATED_DOCUMENT_LENGTH)
+            .ok()
+            .and_then(|e| e.to_int::<usize>().ok())
+        {
+            data.truncate(length);
+        }
+        Some(Self {
+            mime_type: text(obj, tags::MIME_TYPE_OF_ENCAPSULATED_DOCUMENT).unwrap_or_default(),
+            title: text(obj, tags::DOCUMENT_TITLE),
+            data,
+        })
+    }
+
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let obj = open_file(path).map_err(|e| format!("Failed to open DICOM file: {e}"))?;
+        Self::from_obj(&obj).ok_or_else(|| "No encapsulated document in this file".into())
+    }
+
+    /// File extension for the MIME type
+    pub fn extension(&self) -> &'static str {
+        match self.mime_type.to_ascii_lowercase().as_str() {
+            "application/pdf" => "pdf",
+            "text/xml" | "application/xml" => "xml",
+            "text/plain" => "txt",
+            "model/stl" | "application/sla" => "stl",
+            "model/obj" => "obj",
+            "model/mtl" => "mtl",
+            _ => "bin",
+        }
+    }
+
+    /// `{stem of source}.{extension}` in `dir`
+    pub fn file_name(&self, source: &Path, dir: &Path) -> PathBuf {
+        let stem = source
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "document".into());
+        dir.join(format!("{stem}.{}", self.extension()))
+    }
+
+    pub fn save(&self, output: &Path) -> Result<(), String> {
+        std::fs::write(output, &self.data)
+            .map_err(|e| format!("Failed to write {}: {e}", output.display()))
+    }
+
+    /// Readable text of a PDF, CDA or plain text document, one entry per
+    /// line. PDF text comes from the content streams, so fonts with their
+    /// own encodings may come out garbled.
+    pub fn text(&self) -> Result<Vec<String>, String> {
+        let text = match self.extension() {
+            "pdf" => pdf_text(&self.data),
+            "xml" => xml_text(&String::from_utf8_lossy(&self.data)),
+            "txt" => String::from_utf8_lossy(&self.data).into_owned(),
+            _ => return Err(format!("No text view for {}", self.mime_type)),
+        };
+        Ok(text
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|line| !line.is_empty())
+            .collect())
+    }
+}
+
+/// Short description for the tag table in place of the document bytes
+pub(super) fn describe(obj: &InMemDicomObject) -> String {
+    match EncapsulatedDocument::from_obj(obj) {
+        Some(document) if !document.mime_type.is_empty() => {
+            format!(
+                "<{} document, {} bytes>",
+                document.mime_type,
+                document.data.len()
+            )
+        }
+        Some(document) => format!("<Document, {} bytes>", document.data.len()),
+        None => "<Document>".into(),
+    }
+}
+
+fn find(data: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    data.get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| from + position)
+}
+
+/// Content of every stream of a PDF, inflated when Flate encoded
+fn pdf_streams(data: &[u8]) -> Vec<Vec<u8>> {
+    let mut streams = Vec::new();
+    let mut from = 0;
+    while let Some(keyword) = find(data, b"stream", from) {
+        from = keyword + b"stream".len();
+        // Skip the "stream" of "endstream"
+        if keyword >= 3 && &data[keyword - 3..keyword] == b"end" {
+            continue;
+        }
+        let mut start = from;
+        if data.get(start) == Some(&b'\r') {
+            start += 1;
+        }
+        if data.get(start) == Some(&b'\n') {
+            start += 1;
+        }
+        let Some(end) = find(data, b"endstream", start) else {
+            break;
+        };
+        let dictionary_start = data[..keyword]
+            .windows(3)
+            .rposition(|window| window == b"obj")
+            .unwrap_or(0);
+        let dictionary = &data[dictionary_start..keyword];
+        let content = &data[start..end];
+        if find(dictionary, b"/FlateDecode", 0).is_some() {
+            let mut inflated = Vec::new();
+            if ZlibDecoder::new(content).read_to_end(&mut inflated).is_ok() {
+                streams.push(inflated);
+            }
+        } else if find(dictionary, b"/Filter", 0).is_none() {
+            streams.push(content.to_vec());
+        }
+        from = end + b"endstream".len();
+    }
+    streams
+}
+
+/// A literal string from just after its opening parenthesis, and the
+/// position after its closing one
+fn literal(data: &[u8], mut at: usize) -> (Vec<u8>, usize) {
+    let mut bytes = Vec::new();
+    let mut depth = 1;
+    while let Some(&byte) = data.get(at) {
+        at += 1;
+        match byte {
+            b'\\' => {
+                let Some(&escaped) = data.get(at) else {
+                    break;
+                };
+                at += 1;
+                match escaped {
+                    b'n' => bytes.push(b'\n'),
+                    b'r' => bytes.push(b'\r'),
+                    b't' => bytes.push(b'\t'),
+                    b'0'..=b'7' => {
+                        let mut code = (escaped - b'0') as u32;
+                        for _ in 0..2 {
+                            match data.get(at) {
+                                Some(&digit @ b'0'..=b'7') => {
+                                    code = code * 8 + (digit - b'0') as u32;
+                                    at += 1;
+                                }
+                                _ => break,
+                            }
+                        }
+                        bytes.push(code as u8);
+                    }
+                    b'\r' | b'\n' => {}
+                    other => bytes.push(other),
+                }
+            }
+            b'(' => {
+                depth += 1;
+                bytes.push(byte);
+            }
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+                bytes.push(byte);
+            }
+            _ => bytes.push(byte),
+        }
+    }
+    (bytes, at)
+}
+
+/// A hex string from just after its opening bracket
+fn hex(data: &[u8], at: usize) -> (Vec<u8>, usize) {
+    let end = find(data, b">", at).unwrap_or(data.len());
+    let digits: Vec<u8> = data[at..end]
+        .iter()
+        .filter_map(|&b| (b as char).to_digit(16).map(|d| d as u8))
+        .collect();
+    let bytes = digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+        .collect();
+    (bytes, end + 1)
+}
+
+/// Text shown by the text operators of a content stream, with a line
+/// break for each line move
+fn content_text(data: &[u8], out: &mut String) {
+    let mut strings: Vec<Vec<u8>> = Vec::new();
+    let mut line = String::new();
+    let mut at = 0;
+    let mut in_array = false;
+    while let Some(&byte) = data.get(at) {
+        match byte {
+            b'(' => {
+                let (bytes, next) = literal(data, at + 1);
+                strings.push(bytes);
+                at = next;
+            }
+            b'<' if data.get(at + 1) == Some(&b'<') => at += 2,
+            b'<' => {
+                let (bytes, next) = hex(data, at + 1);
+                strings.push(bytes);
+                at = next;
+            }
+            b'[' => {
+                in_array = true;
+                at += 1;
+            }
+            b']' => {
+                in_array = false;
+                at += 1;
+            }
+            b'%' => {
+                at = find(data, b"\n", at).unwrap_or(data.len());
+            }
+            b'-' | b'0'..=b'9' | b'.' if in_array => {
+                let end = data[at..]
+                    .iter()
+                    .position(|b| !matches!(b, b'-' | b'0'..=b'9' | b'.'))
+                    .map_or(data.len(), |p| at + p);
+                // Large negative kerning inside TJ separates words
+                let kerning: f64 = std::str::from_utf8(&data[at..end])
+                    .ok()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or(0.0);
+                if kerning < -200.0 {
+                    strings.push(b" ".to_vec());
+                }
+                at = end;
+            }
+            b'a'..=b'z' | b'A'..=b'Z' | b'\'' | b'"' | b'*' => {
+                let end = data[at..]
+                    .iter()
+                    .position(|b| !(b.is_ascii_alphabetic() || matches!(b, b'\'' | b'"' | b'*')))
+                    .map_or(data.len(), |p| at + p);
+                let operator = &data[at..end];
+                if matches!(operator, b"'" | b"\"" | b"Td" | b"TD" | b"T*" | b"ET")
+                    && !line.trim().is_empty()
+                {
+                    out.push_str(&line);
+                    out.push('\n');
+                    line.clear();
+                }
+                if matches!(operator, b"Tj" | b"TJ" | b"'" | b"\"") {
+                    for bytes in &strings {
+                        line.extend(
+                            bytes
+                                .iter()
+                                .map(|&b| b as char)
+                                .filter(|c| !c.is_control() || *c == ' '),
+                        );
+                    }
+                }
+                strings.clear();
+                at = end;
+            }
+            _ => at += 1,
+        }
+    }
+    if !line.trim().is_empty() {
+        out.push_str(&line);
+        out.push('\n');
+    }
+}
+
+fn pdf_text(data: &[u8]) -> String {
+    let mut text = String::new();
+    for stream in pdf_streams(data) {
+        if find(&stream, b"BT", 0).is_some() {
+            content_text(&stream, &mut text);
+        }
+    }
+    text
+}
+
+/// Elements that end a line of CDA narrative
+const XML_BREAKS: [&str; 7] = [
+    "title",
+    "paragraph",
+    "item",
+    "tr",
+    "br",
+    "caption",
+    "section",
+];
+
+/// Character data of an XML document, with line breaks after block
+/// elements of CDA narrative
+fn xml_text(xml: &str) -> String {
+    let mut text = String::new();
+    let mut rest = xml;
+    let push = |text: &mut String, data: &str| {
+        let mut words = data.split_whitespace().peekable();
+        if data.starts_with(char::is_whitespace) && words.peek().is_some() {
+            text.push(' ');
+        }
+        text.push_str(&words.collect::<Vec<_>>().join(" "));
+        if data.ends_with(char::is_whitespace) {
+            text.push(' ');
+        }
+    };
+    while let Some(open) = rest.find('<') {
+        push(&mut text, &rest[..open]);
+        let Some(close) = rest[open..].find('>') else {
+            break;
+        };
+        let tag = &rest[open + 1..open + close];
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        let name = name.rsplit(':').next().unwrap_or(name);
+        if XML_BREAKS.contains(&name) && (tag.starts_with('/') || tag.ends_with('/')) {
+            text.push('\n');
+        } else if name == "td" || name == "th" {
+            text.push(' ');
+        }
+        rest = &rest[open + close + 1..];
+    }
+    push(&mut text, rest);
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
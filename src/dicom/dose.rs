@@ -0,0 +1,363 @@
+use super::attr::{float, items, text};
+use super::folder::FolderHeaders;
+use super::geometry::{dot, frame_group, frame_planes, uid, unit};
+use dicom::dictionary_std::tags;
+use dicom::object::{InMemDicomObject, OpenFileOptions};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+/// DCM codes of the CT Radiation Dose SR template (TID 10011) that the
+/// summary reads
+mod code {
+    pub const CT_ACQUISITION: &str = "113819";
+    pub const CT_DOSE: &str = "113829";
+    pub const MEAN_CTDIVOL: &str = "113830";
+    pub const DLP: &str = "113838";
+    pub const PHANTOM_TYPE: &str = "113835";
+    pub const IRRADIATION_EVENT_UID: &str = "113769";
+    pub const ACQUISITION_PROTOCOL: &str = "125203";
+    pub const TARGET_REGION: &str = "123014";
+    pub const ACCUMULATED_DOSE: &str = "113811";
+    pub const DLP_TOTAL: &str = "113813";
+}
+
+/// Where the dose values come from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DoseSource {
+    /// An X-Ray Radiation Dose SR
+    Report,
+    /// CTDIvol of CT images, with the DLP estimated from the scan length
+    Images,
+}
+
+/// One CT irradiation event
+#[derive(Clone, Debug, PartialEq)]
+pub struct DoseEvent {
+    pub uid: Option<String>,
+    /// Acquisition protocol or target region of a report, protocol name
+    /// or series description of images
+    pub protocol: Option<String>,
+    /// Mean CTDIvol in mGy
+    pub ctdi_vol: Option<f64>,
+    /// Dose length product in mGy·cm
+    pub dlp: Option<f64>,
+    pub phantom: Option<String>,
+}
+
+/// CT dose of a study, per irradiation event
+#[derive(Clone, Debug, PartialEq)]
+pub struct DoseSummary {
+    pub source: DoseSource,
+    pub study_uid: Option<String>,
+    pub events: Vec<DoseEvent>,
+    /// Study DLP total stated in the report
+    pub reported_total_dlp: Option<f64>,
+}
+
+fn number(value: Option<f64>, decimals: usize) -> String {
+    value.map_or_else(String::new, |v| format!("{v:.decimals$}"))
+}
+
+/// A CSV field, quoted when it holds a separator or a quote
+fn field(value: &str) -> String {
+    match value.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+impl DoseSummary {
+    /// The report's total, or the sum of the events' DLP
+    pub fn total_dlp(&self) -> Option<f64> {
+        self.reported_total_dlp.or_else(|| {
+            let dlps: Vec<f64> = self.events.iter().filter_map(|e| e.dlp).collect();
+            (!dlps.is_empty()).then(|| dlps.iter().sum())
+        })
+    }
+
+    /// One line per event, then the study total
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .events
+            .iter()
+            .enumerate()
+            .map(|(index, event)| {
+                let mut parts = vec![format!(
+                    "{}. {}",
+                    index + 1,
+                    event.protocol.as_deref().unwrap_or("Unnamed event")
+                )];
+                if let Some(ctdi_vol) = event.ctdi_vol {
+                    parts.push(format!("CTDIvol {ctdi_vol:.2} mGy"));
+                }
+                if let Some(dlp) = event.dlp {
+                    parts.push(format!("DLP {dlp:.1} mGy·cm"));
+                }
+                if let Some(phantom) = &event.phantom {
+                    parts.push(phantom.clone());
+                }
+                parts.join(" | ")
+            })
+            .collect();
+        let total = match self.total_dlp() {
+            Some(total) => format!("Study total DLP: {total:.1} mGy·cm"),
+            None => "Study total DLP: unknown".to_string(),
+        };
+        lines.push(format!("{total} over {} event(s)", self.events.len()));
+        if self.source == DoseSource::Images {
+            lines.push("DLP estimated as CTDIvol × scanned length".into());
+        }
+        lines
+    }
+
+    /// One row per event and a study total row, for dose registries
+    pub fn csv(&self) -> String {
+        let source = match self.source {
+            DoseSource::Report => "report",
+            DoseSource::Images => "images",
+        };
+        let study = field(self.study_uid.as_deref().unwrap_or_default());
+        let mut csv = String::from(
+            "study_instance_uid,irradiation_event_uid,protocol,ctdivol_mgy,dlp_mgy_cm,phantom,source\n",
+        );
+        for event in &self.events {
+            csv.push_str(&format!(
+                "{study},{},{},{},{},{},{source}\n",
+                field(event.uid.as_deref().unwrap_or_default()),
+                field(event.protocol.as_deref().unwrap_or_default()),
+                number(event.ctdi_vol, 2),
+                number(event.dlp, 2),
+                field(event.phantom.as_deref().unwrap_or_default()),
+            ));
+        }
+        csv.push_str(&format!(
+            "{study},TOTAL,,,{},,{source}\n",
+            number(self.total_dlp(), 2)
+        ));
+        csv
+    }
+}
+
+/// Code value of a content item's concept name
+fn concept(item: &InMemDicomObject) -> Option<String> {
+    text(
+        items(item, tags::CONCEPT_NAME_CODE_SEQUENCE).first()?,
+        tags::CODE_VALUE,
+    )
+}
+
+/// Content items below `item` with the given concept, depth first
+fn find<'a>(item: &'a InMemDicomObject, code: &str, found: &mut Vec<&'a InMemDicomObject>) {
+    for child in items(item, tags::CONTENT_SEQUENCE) {
+        if concept(child).as_deref() == Some(code) {
+            found.push(child);
+        }
+        find(child, code, found);
+    }
+}
+
+fn first<'a>(item: &'a InMemDicomObject, code: &str) -> Option<&'a InMemDicomObject> {
+    let mut found = Vec::new();
+    find(item, code, &mut found);
+    found.first().copied()
+}
+
+fn numeric(item: &InMemDicomObject) -> Option<f64> {
+    float(
+        items(item, tags::MEASURED_VALUE_SEQUENCE).first()?,
+        tags::NUMERIC_VALUE,
+    )
+}
+
+fn code_meaning(item: &InMemDicomObject) -> Option<String> {
+    text(
+        items(item, tags::CONCEPT_CODE_SEQUENCE).first()?,
+        tags::CODE_MEANING,
+    )
+}
+
+/// CT irradiation events and the accumulated DLP of an X-Ray Radiation
+/// Dose SR. `None` when it holds neither.
+pub fn report_dose(obj: &InMemDicomObject) -> Option<DoseSummary> {
+    let mut acquisitions = Vec::new();
+    find(obj, code::CT_ACQUISITION, &mut acquisitions);
+    let events: Vec<DoseEvent> = acquisitions
+        .into_iter()
+        .map(|acquisition| {
+            let dose = first(acquisition, code::CT_DOSE).unwrap_or(acquisition);
+            DoseEvent {
+                uid: first(acquisition, code::IRRADIATION_EVENT_UID)
+                    .and_then(|item| text(item, tags::UID)),
+                protocol: first(acquisition, code::ACQUISITION_PROTOCOL)
+                    .and_then(|item| text(item, tags::TEXT_VALUE))
+                    .or_else(|| first(acquisition, code::TARGET_REGION).and_then(code_meaning)),
+                ctdi_vol: first(dose, code::MEAN_CTDIVOL).and_then(numeric),
+                dlp: first(dose, code::DLP).and_then(numeric),
+                phantom: first(dose, code::PHANTOM_TYPE).and_then(code_meaning),
+            }
+        })
+        .collect();
+    let reported_total_dlp = first(obj, code::ACCUMULATED_DOSE)
+        .and_then(|accumulated| first(accumulated, code::DLP_TOTAL))
+        .and_then(numeric);
+    if events.is_empty() && reported_total_dlp.is_none() {
+        return None;
+    }
+    Some(DoseSummary {
+        source: DoseSource::Report,
+        study_uid: uid(obj, tags::STUDY_INSTANCE_UID),
+        events,
+        reported_total_dlp,
+    })
+}
+
+/// CTDIvol of a CT image, from the CT Exposure functional group for
+/// enhanced objects
+fn image_ctdi_vol(obj: &InMemDicomObject) -> Option<f64> {
+    float(obj, tags::CTD_IVOL).or_else(|| {
+        float(
+            frame_group(obj, 0, tags::CT_EXPOSURE_SEQUENCE)?,
+            tags::CTD_IVOL,
+        )
+    })
+}
+
+/// Images of one irradiation event
+#[derive(Default)]
+struct EventImages {
+    protocol: Option<String>,
+    phantom: Option<String>,
+    ctdi_vols: Vec<f64>,
+    /// Slice positions along the normal, in mm
+    positions: Vec<f64>,
+    thickness: f64,
+}
+
+/// CT irradiation events of a study's images, grouped by
+/// IrradiationEventUID, or by series and acquisition number without one
+pub fn image_dose(images: &[InMemDicomObject]) -> Option<DoseSummary> {
+    let mut events: Vec<(String, Option<String>, EventImages)> = Vec::new();
+    for obj in images {
+        let event_uid = uid(obj, tags::IRRADIATION_EVENT_UID);
+        let key = event_uid.clone().unwrap_or_else(|| {
+            format!(
+                "{} {}",
+                uid(obj, tags::SERIES_INSTANCE_UID).unwrap_or_default(),
+                text(obj, tags::ACQUISITION_NUMBER).unwrap_or_default()
+            )
+        });
+        let index = match events.iter().position(|(k, _, _)| *k == key) {
+            Some(index) => index,
+            None => {
+                events.push((key, event_uid, EventImages::default()));
+                events.len() - 1
+            }
+        };
+        let event = &mut events[index].2;
+        event.protocol = event.protocol.take().or_else(|| {
+            text(obj, tags::PROTOCOL_NAME).or_else(|| text(obj, tags::SERIES_DESCRIPTION))
+        });
+        event.phantom = event.phantom.take().or_else(|| {
+            text(
+                items(obj, tags::CTDI_PHANTOM_TYPE_CODE_SEQUENCE).first()?,
+                tags::CODE_MEANING,
+            )
+        });
+        event.ctdi_vols.extend(image_ctdi_vol(obj));
+        event.thickness = event
+            .thickness
+            .max(float(obj, tags::SLICE_THICKNESS).unwrap_or(0.0));
+        for plane in frame_planes(obj) {
+            event
+                .positions
+                .push(dot(plane.position, unit(plane.normal())));
+        }
+    }
+
+    let events: Vec<DoseEvent> = events
+        .into_iter()
+        .filter(|(_, _, images)| !images.ctdi_vols.is_empty())
+        .map(|(_, uid, images)| {
+            let ctdi_vol = images.ctdi_vols.iter().sum::<f64>() / images.ctdi_vols.len() as f64;
+            let low = images.positions.iter().copied().reduce(f64::min);
+            let high = images.positions.iter().copied().reduce(f64::max);
+            let dlp = low
+                .zip(high)
+                .map(|(low, high)| ctdi_vol * (high - low + images.thickness) / 10.0);
+            DoseEvent {
+                uid,
+                protocol: images.protocol,
+                ctdi_vol: Some(ctdi_vol),
+                dlp,
+                phantom: images.phantom,
+            }
+        })
+        .collect();
+    if events.is_empty() {
+        return None;
+    }
+    Some(DoseSummary {
+        source: DoseSource::Images,
+        study_uid: images
+            .first()
+            .and_then(|obj| uid(obj, tags::STUDY_INSTANCE_UID)),
+        events,
+        reported_total_dlp: None,
+    })
+}
+
+/// What the dose summary of a file is read from, so that files sharing it
+/// share one summary
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DoseScope {
+    /// The file alone: a dose report, or a file without a study dose
+    File(PathBuf),
+    /// The CT images of a study found in a folder
+    Study {
+        folder: PathBuf,
+        study_uid: Option<String>,
+    },
+}
+
+impl DoseScope {
+    /// Follows [`dose_summary`] in telling reports and CT images apart
+    pub fn of(path: &Path, obj: &InMemDicomObject) -> Self {
+        if obj.element(tags::CONTENT_SEQUENCE).is_ok()
+            || text(obj, tags::MODALITY).as_deref() != Some("CT")
+        {
+            return Self::File(path.to_path_buf());
+        }
+        Self::Study {
+            folder: path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+            study_uid: uid(obj, tags::STUDY_INSTANCE_UID),
+        }
+    }
+}
+
+/// Dose of a file: a dose report's own values, or those of the CT images
+/// of the study found in the file's folder. Stops reading the folder once
+/// `cancelled` is set.
+pub fn dose_summary(path: &Path, cancelled: &AtomicBool) -> Result<DoseSummary, String> {
+    let obj = OpenFileOptions::new()
+        .read_until(tags::PIXEL_DATA)
+        .open_file(path)
+        .map_err(|e| format!("Failed to open DICOM file: {e}"))?;
+    if obj.element(tags::CONTENT_SEQUENCE).is_ok() {
+        return report_dose(&obj).ok_or_else(|| "No CT dose information in the report".into());
+    }
+    if text(&obj, tags::MODALITY).as_deref() != Some("CT") {
+        return Err("Dose summaries need a CT image or a radiation dose report".into());
+    }
+    let study = uid(&obj, tags::STUDY_INSTANCE_UID);
+    let headers = FolderHeaders::read(FolderHeaders::folder_of(path), cancelled)?;
+    let images: Vec<InMemDicomObject> = headers
+        .iter()
+        .map(|(_, other)| other)
+        .filter(|other| {
+            text(other, tags::MODALITY).as_deref() == Some("CT")
+                && uid(other, tags::STUDY_INSTANCE_UID) == study
+        })
+        .cloned()
+        .collect();
+    image_dose(&images).ok_or_else(|| "No CTDIvol in the CT images of this study".into())
+}
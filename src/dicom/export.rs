@@ -0,0 +1,135 @@
+use super::overlay::{overlay_planes, OverlayPlane};
+use super::palette::PaletteLut;
+use super::pixels::{frame_interval, FramePixels};
+use super::voi::{file_vois, Voi};
+use dicom_pixeldata::image::codecs::gif::{GifEncoder, Repeat};
+use dicom_pixeldata::image::{self, Delay, DynamicImage, ImageFormat, Rgb, RgbImage};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::time::Duration;
+
+/// Frame delay of GIFs when the file gives no frame timing
+const DEFAULT_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How `export_image` renders a file
+#[derive(Clone, Debug, Default)]
+pub struct ExportOptions {
+    /// 1-based frame or range, such as "3" or "1-10"; every frame if unset
+    pub frames: Option<String>,
+    /// Window or VOI LUT to apply; the file's first one if unset
+    pub voi: Option<Voi>,
+    pub hide_overlays: bool,
+}
+
+/// Color overlay planes are burned in with
+pub const OVERLAY_COLOR: Rgb<u8> = Rgb([0, 255, 0]);
+
+/// A frame as the preview shows it: the VOI applied to grayscale values
+/// and the overlay bits painted over
+pub fn rendered_frame(
+    pixels: &FramePixels,
+    voi: Option<&Voi>,
+    overlays: &[OverlayPlane],
+    frame: u32,
+) -> RgbImage {
+    let mut image = pixels.render(voi).to_rgb8();
+    for overlay in overlays {
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            if overlay.is_set(frame, y, x) {
+                *pixel = OVERLAY_COLOR;
+            }
+        }
+    }
+    image
+}
+
+/// 0-based frames from a 1-based "N" or "FIRST-LAST", or every frame
+pub fn parse_frame_range(range: Option<&str>, frame_count: u32) -> Result<Vec<u32>, String> {
+    let Some(range) = range else {
+        return Ok((0..frame_count).collect());
+    };
+    let number = |s: &str| {
+        s.trim()
+            .parse::<u32>()
+            .map_err(|_| format!("Invalid frame range: {range}"))
+    };
+    let (first, last) = match range.split_once('-') {
+        Some((first, last)) => (number(first)?, number(last)?),
+        None => (number(range)?, number(range)?),
+    };
+    if first == 0 || first > last || last > frame_count {
+        return Err(format!(
+            "Frame range {range} is outside frames 1-{frame_count}"
+        ));
+    }
+    Ok((first - 1..last).collect())
+}
+
+/// Writes one frame as PNG or JPEG, or several as an animated GIF, picking
+/// the format from the file extension
+pub fn save_frames(frames: &[RgbImage], interval: Duration, path: &Path) -> Result<(), String> {
+    let format = ImageFormat::from_path(path)
+        .map_err(|_| format!("Unknown image format: {}", path.display()))?;
+    match (format, frames) {
+        (_, []) => Err("No frames to export".into()),
+        (ImageFormat::Gif, _) => save_gif(frames, interval, path),
+        (ImageFormat::Png | ImageFormat::Jpeg, [frame]) => frame
+            .save_with_format(path, format)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display())),
+        (ImageFormat::Png | ImageFormat::Jpeg, _) => {
+            Err("Several frames can only be exported as a GIF".into())
+        }
+        _ => Err(format!("Export as PNG, JPEG or GIF: {}", path.display())),
+    }
+}
+
+fn save_gif(frames: &[RgbImage], interval: Duration, path: &Path) -> Result<(), String> {
+    let write_error = |e: image::ImageError| format!("Failed to write {}: {e}", path.display());
+    let file =
+        File::create(path).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    let mut encoder = GifEncoder::new_with_speed(BufWriter::new(file), 10);
+    encoder.set_repeat(Repeat::Infinite).map_err(write_error)?;
+    let delay = Delay::from_saturating_duration(interval);
+    encoder
+        .encode_frames(frames.iter().map(|frame| {
+            let rgba = DynamicImage::ImageRgb8(frame.clone()).to_rgba8();
+            image::Frame::from_parts(rgba, 0, 0, delay)
+        }))
+        .map_err(write_error)
+}
+
+/// Renders frames of a DICOM file to `output`, returning how many were
+/// written
+pub fn export_image(input: &Path, output: &Path, options: &ExportOptions) -> Result<usize, String> {
+    use dicom_pixeldata::PixelDecoder;
+
+    let obj =
+        dicom::object::open_file(input).map_err(|e| format!("Failed to open DICOM file: {e}"))?;
+    let data = obj
+        .decode_pixel_data()
+        .map_err(|e| format!("Failed to decode pixel data: {e}"))?;
+    let frames = parse_frame_range(options.frames.as_deref(), data.number_of_frames())?;
+    let voi = options
+        .voi
+        .clone()
+        .or_else(|| file_vois(&obj).into_iter().next());
+    let palette = PaletteLut::from_obj(&obj);
+    let overlays = if options.hide_overlays {
+        Vec::new()
+    } else {
+        overlay_planes(&obj)
+    };
+
+    let images = frames
+        .iter()
+        .map(|&frame| {
+            let pixels = FramePixels::from_decoded(&data, frame, palette.as_ref())
+                .map_err(|e| format!("Failed to convert to image: {e}"))?;
+            Ok(rendered_frame(&pixels, voi.as_ref(), &overlays, frame))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let interval = frame_interval(&obj).unwrap_or(DEFAULT_FRAME_INTERVAL);
+    save_frames(&images, interval, output)?;
+    Ok(images.len())
+}
@@ -0,0 +1,71 @@
+use dicom::dictionary_std::tags;
+use dicom::object::{InMemDicomObject, OpenFileOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Attributes of every DICOM file in a folder, read without their pixel
+/// data, so the folder is searched once for presentation states,
+/// segmentations and series slices
+#[derive(Debug, Default)]
+pub struct FolderHeaders {
+    /// Files by name, with what precedes their pixel data
+    files: Vec<(PathBuf, InMemDicomObject)>,
+}
+
+impl FolderHeaders {
+    /// Reads the folder; files that are not DICOM are left out. Stops once
+    /// `cancelled` is set.
+    pub fn read(folder: &Path, cancelled: &AtomicBool) -> Result<Self, String> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(folder)
+            .map_err(|e| format!("Failed to read {}: {e}", folder.display()))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect();
+        paths.sort();
+        let mut files = Vec::new();
+        for path in paths {
+            if cancelled.load(Ordering::Relaxed) {
+                return Err("Cancelled".into());
+            }
+            if let Ok(obj) = OpenFileOptions::new()
+                .read_until(tags::PIXEL_DATA)
+                .open_file(&path)
+            {
+                files.push((path, obj.into_inner()));
+            }
+        }
+        Ok(Self { files })
+    }
+
+    /// Folder of a file, the working directory for a bare file name
+    pub fn folder_of(path: &Path) -> &Path {
+        match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        }
+    }
+
+    /// Files by name
+    pub fn iter(&self) -> impl Iterator<Item = (&Path, &InMemDicomObject)> {
+        self.files.iter().map(|(path, obj)| (path.as_path(), obj))
+    }
+
+    /// Files by name, other than the one named like `except`
+    pub fn others<'a>(
+        &'a self,
+        except: &'a Path,
+    ) -> impl Iterator<Item = (&'a Path, &'a InMemDicomObject)> {
+        self.iter()
+            .filter(move |(path, _)| path.file_name() != except.file_name())
+    }
+
+    /// Header of the file named like `path`
+    pub fn get(&self, path: &Path) -> Option<&InMemDicomObject> {
+        self.files
+            .iter()
+            .find(|(file, _)| file.file_name() == path.file_name())
+            .map(|(_, obj)| obj)
+    }
+}
@@ -0,0 +1,120 @@
+use super::attr::{tag_label, text};
+use super::loader::extract_tags_from_inmem_object;
+use super::tag::DicomTag;
+use dicom::core::dictionary::DataDictionary;
+use dicom::core::header::Header;
+use dicom::core::Tag;
+use dicom::dictionary_std::{tags, StandardDataDictionary};
+use dicom::object::InMemDicomObject;
+use std::collections::BTreeMap;
+
+/// Shared and Per-frame Functional Groups of an enhanced multi-frame object
+#[derive(Clone, Debug)]
+pub struct FunctionalGroups {
+    shared: Option<InMemDicomObject>,
+    per_frame: Vec<InMemDicomObject>,
+    /// Label of each dimension index, in DimensionIndexSequence order
+    dimensions: Vec<String>,
+}
+
+/// DimensionDescriptionLabel of a dimension, or the keyword of the
+/// attribute it points to
+fn dimension_label(item: &InMemDicomObject) -> String {
+    text(item, tags::DIMENSION_DESCRIPTION_LABEL)
+        .or_else(|| {
+            let pointer = item.element(tags::DIMENSION_INDEX_POINTER).ok()?;
+            let tag = pointer.value().to_tag().ok()?;
+            StandardDataDictionary
+                .by_tag(tag)
+                .map(|entry| entry.alias.to_string())
+        })
+        .unwrap_or_else(|| "?".into())
+}
+
+impl FunctionalGroups {
+    /// `None` for objects without a Per-frame Functional Groups Sequence
+    pub fn from_obj(obj: &InMemDicomObject) -> Option<Self> {
+        let per_frame = obj
+            .element(tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE)
+            .ok()?
+            .items()?
+            .to_vec();
+        let shared = obj
+            .element(tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE)
+            .ok()
+            .and_then(|e| e.items()?.first().cloned());
+        let dimensions = obj
+            .element(tags::DIMENSION_INDEX_SEQUENCE)
+            .ok()
+            .and_then(|e| e.items())
+            .unwrap_or_default()
+            .iter()
+            .map(dimension_label)
+            .collect();
+        Some(Self {
+            shared,
+            per_frame,
+            dimensions,
+        })
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        self.per_frame.len() as u32
+    }
+
+    /// The attributes of every functional group macro of a frame as one
+    /// flat list, in macro order. A per-frame macro replaces the shared
+    /// one; attributes of shared macros have "(shared)" after their name.
+    pub fn rows(&self, frame: u32) -> Vec<DicomTag> {
+        let mut macros: BTreeMap<Tag, (&InMemDicomObject, bool)> = BTreeMap::new();
+        let sources = [
+            (self.shared.as_ref(), true),
+            (self.per_frame.get(frame as usize), false),
+        ];
+        for (groups, shared) in sources {
+            for element in groups.into_iter().flatten() {
+                if let Some(item) = element.items().and_then(|items| items.first()) {
+                    macros.insert(element.tag(), (item, shared));
+                }
+            }
+        }
+
+        let index_values = tag_label(tags::DIMENSION_INDEX_VALUES);
+        let mut rows = Vec::new();
+        for (item, shared) in macros.into_values() {
+            for mut row in extract_tags_from_inmem_object(item, 0) {
+                if shared {
+                    row.name.push_str(" (shared)");
+                }
+                if row.tag == index_values {
+                    if let Some(dimensions) = self.describe_indices(item) {
+                        row.value = format!("{} ({dimensions})", row.value);
+                    }
+                }
+                rows.push(row);
+            }
+        }
+        rows
+    }
+
+    /// e.g. "StackID=1, InStackPositionNumber=3"
+    fn describe_indices(&self, item: &InMemDicomObject) -> Option<String> {
+        let values = item
+            .element(tags::DIMENSION_INDEX_VALUES)
+            .ok()?
+            .to_multi_int::<u32>()
+            .ok()?;
+        if self.dimensions.is_empty() {
+            return None;
+        }
+        let pairs: Vec<String> = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let label = self.dimensions.get(i).map_or("?", String::as_str);
+                format!("{label}={value}")
+            })
+            .collect();
+        Some(pairs.join(", "))
+    }
+}
@@ -0,0 +1,144 @@
+use dicom::core::Tag;
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// Position, orientation and pixel spacing of a frame in the patient
+/// coordinate system
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImagePlane {
+    /// Center of the first transmitted pixel, in mm
+    pub position: [f64; 3],
+    /// Direction of increasing column index
+    pub row_direction: [f64; 3],
+    /// Direction of increasing row index
+    pub column_direction: [f64; 3],
+    /// Distance between rows, then between columns, in mm
+    pub spacing: [f64; 2],
+}
+
+impl ImagePlane {
+    /// Patient coordinate of a (possibly fractional) pixel position,
+    /// following PS3.3 C.7.6.2.1.1
+    pub fn patient_point(&self, row: f64, column: f64) -> [f64; 3] {
+        std::array::from_fn(|axis| {
+            self.position[axis]
+                + self.row_direction[axis] * self.spacing[1] * column
+                + self.column_direction[axis] * self.spacing[0] * row
+        })
+    }
+
+    /// Fractional (row, column) of a patient coordinate projected onto the
+    /// plane, the inverse of `patient_point`
+    pub fn pixel_position(&self, point: [f64; 3]) -> (f64, f64) {
+        let offset = sub(point, self.position);
+        (
+            dot(offset, unit(self.column_direction)) / self.spacing[0],
+            dot(offset, unit(self.row_direction)) / self.spacing[1],
+        )
+    }
+
+    pub fn normal(&self) -> [f64; 3] {
+        let (r, c) = (self.row_direction, self.column_direction);
+        [
+            r[1] * c[2] - r[2] * c[1],
+            r[2] * c[0] - r[0] * c[2],
+            r[0] * c[1] - r[1] * c[0],
+        ]
+    }
+}
+
+/// The item of a functional group macro that applies to a frame: the
+/// per-frame item when present, otherwise the shared one
+pub fn frame_group(obj: &InMemDicomObject, frame: u32, sequence: Tag) -> Option<&InMemDicomObject> {
+    let group_item = |groups: Tag, index: usize| {
+        obj.element(groups)
+            .ok()?
+            .items()?
+            .get(index)?
+            .element(sequence)
+            .ok()?
+            .items()?
+            .first()
+    };
+    group_item(tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE, frame as usize)
+        .or_else(|| group_item(tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE, 0))
+}
+
+pub(super) fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(super) fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub(super) fn unit(v: [f64; 3]) -> [f64; 3] {
+    let length = dot(v, v).sqrt();
+    if length == 0.0 {
+        return v;
+    }
+    v.map(|c| c / length)
+}
+
+/// A UID element without its padding
+pub(super) fn uid(obj: &InMemDicomObject, tag: Tag) -> Option<String> {
+    let uid = obj.element(tag).ok()?.to_str().ok()?;
+    Some(uid.trim_end_matches(['\0', ' ']).to_string())
+}
+
+/// The FrameOfReferenceUID that the image's geometry is expressed in
+pub fn frame_of_reference(obj: &InMemDicomObject) -> Option<String> {
+    uid(obj, tags::FRAME_OF_REFERENCE_UID)
+}
+
+fn floats<const N: usize>(obj: &InMemDicomObject, tag: Tag) -> Option<[f64; N]> {
+    let values = obj.element(tag).ok()?.to_multi_float64().ok()?;
+    values.get(..N)?.try_into().ok()
+}
+
+/// One plane per frame. Enhanced objects take them from the functional
+/// groups, other objects have a single plane for every frame. Empty when
+/// the geometry is incomplete.
+pub fn frame_planes(obj: &InMemDicomObject) -> Vec<ImagePlane> {
+    let frames = obj
+        .element(tags::NUMBER_OF_FRAMES)
+        .ok()
+        .and_then(|e| e.to_int::<u32>().ok())
+        .unwrap_or(1)
+        .max(1);
+
+    let enhanced = obj.element(tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE).is_ok()
+        || obj
+            .element(tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE)
+            .is_ok();
+    if !enhanced {
+        return plane_from(obj, obj, obj).into_iter().collect();
+    }
+
+    (0..frames)
+        .map(|frame| {
+            plane_from(
+                frame_group(obj, frame, tags::PLANE_POSITION_SEQUENCE)?,
+                frame_group(obj, frame, tags::PLANE_ORIENTATION_SEQUENCE)?,
+                frame_group(obj, frame, tags::PIXEL_MEASURES_SEQUENCE)?,
+            )
+        })
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default()
+}
+
+fn plane_from(
+    position: &InMemDicomObject,
+    orientation: &InMemDicomObject,
+    measures: &InMemDicomObject,
+) -> Option<ImagePlane> {
+    let position = floats::<3>(position, tags::IMAGE_POSITION_PATIENT)?;
+    let [rx, ry, rz, cx, cy, cz] = floats::<6>(orientation, tags::IMAGE_ORIENTATION_PATIENT)?;
+    let spacing = floats::<2>(measures, tags::PIXEL_SPACING)?;
+    Some(ImagePlane {
+        position,
+        row_direction: [rx, ry, rz],
+        column_direction: [cx, cy, cz],
+        spacing,
+    })
+}
@@ -0,0 +1,98 @@
+use super::pixels::MonochromeFrame;
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// Distribution of the modality values of a grayscale frame
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    /// Lowest value, the left edge of the first bin
+    pub min: f64,
+    /// Highest value, the right edge of the last bin
+    pub max: f64,
+    pub mean: f64,
+    pub counts: Vec<u64>,
+    /// Pixels holding a padding value, left out of the counts
+    pub padding: usize,
+    pub pixels: usize,
+}
+
+impl Histogram {
+    /// Bins `bins` equal ranges of the values that are not padding. `None`
+    /// when every pixel is padding.
+    pub fn from_frame(
+        frame: &MonochromeFrame,
+        padding: Option<(f64, f64)>,
+        bins: usize,
+    ) -> Option<Self> {
+        let is_padding = |value: f32| {
+            padding.is_some_and(|(lo, hi)| {
+                let stored = ((value as f64 - frame.intercept) / frame.slope).round();
+                stored >= lo && stored <= hi
+            })
+        };
+        let values: Vec<f64> = frame
+            .values
+            .iter()
+            .filter(|&&v| !is_padding(v))
+            .map(|&v| v as f64)
+            .collect();
+        if values.is_empty() || bins == 0 {
+            return None;
+        }
+
+        let min = values.iter().copied().fold(f64::MAX, f64::min);
+        let max = values.iter().copied().fold(f64::MIN, f64::max);
+        let width = (max - min) / bins as f64;
+        let mut counts = vec![0; bins];
+        for &value in &values {
+            let bin = if width > 0.0 {
+                (((value - min) / width) as usize).min(bins - 1)
+            } else {
+                0
+            };
+            counts[bin] += 1;
+        }
+        Some(Self {
+            min,
+            max,
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            counts,
+            padding: frame.values.len() - values.len(),
+            pixels: frame.values.len(),
+        })
+    }
+
+    pub fn bin_width(&self) -> f64 {
+        (self.max - self.min) / self.counts.len() as f64
+    }
+
+    /// Center value and count of every bin
+    pub fn points(&self) -> Vec<(f64, f64)> {
+        let width = self.bin_width();
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| (self.min + (i as f64 + 0.5) * width, count as f64))
+            .collect()
+    }
+
+    pub fn padding_percent(&self) -> f64 {
+        self.padding as f64 * 100.0 / self.pixels.max(1) as f64
+    }
+}
+
+/// Stored values marked as padding by PixelPaddingValue, up to
+/// PixelPaddingRangeLimit when present
+pub fn pixel_padding(obj: &InMemDicomObject) -> Option<(f64, f64)> {
+    let int = |tag| {
+        obj.element(tag)
+            .ok()
+            .and_then(|e| e.to_int::<i32>().ok())
+            .map(f64::from)
+    };
+    let value = int(tags::PIXEL_PADDING_VALUE)?;
+    Some(match int(tags::PIXEL_PADDING_RANGE_LIMIT) {
+        Some(limit) => (value.min(limit), value.max(limit)),
+        None => (value, value),
+    })
+}
@@ -2,7 +2,7 @@ use super::tag::DicomTag;
 use dicom::core::dictionary::DataDictionary;
 use dicom::core::header::HasLength;
 use dicom::core::header::Header;
-use dicom::dictionary_std::StandardDataDictionary;
+use dicom::dictionary_std::{tags, StandardDataDictionary};
 use dicom::object::{open_file, FileDicomObject, InMemDicomObject};
 use std::path::Path;
 
@@ -58,6 +58,8 @@ pub fn extract_tags(obj: &FileDicomObject<InMemDicomObject>) -> Vec<DicomTag> {
             let is_expandable = !children.is_empty();
             let value = format!("<Sequence with {} item(s)>", items.len());
             (value, children, is_expandable)
+        } else if tag == tags::ENCAPSULATED_DOCUMENT {
+            (super::document::describe(obj), Vec::new(), false)
         } else {
             (format_value(element.value()), Vec::new(), false)
         };
@@ -102,7 +104,7 @@ fn extract_sequence_items(items: &[InMemDicomObject], depth: usize) -> Vec<Dicom
     children
 }
 
-fn extract_tags_from_inmem_object(obj: &InMemDicomObject, depth: usize) -> Vec<DicomTag> {
+pub(super) fn extract_tags_from_inmem_object(obj: &InMemDicomObject, depth: usize) -> Vec<DicomTag> {
     let mut tags = Vec::new();
 
     for element in obj {
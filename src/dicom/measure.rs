@@ -0,0 +1,231 @@
+use super::geometry::frame_group;
+use super::pixels::MonochromeFrame;
+use dicom::core::Tag;
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+use serde::Serialize;
+
+/// Outline of a region of interest, spanned by two opposite corners
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Shape {
+    Rectangle,
+    Ellipse,
+}
+
+/// Which attribute the pixel spacing comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum SpacingSource {
+    PixelSpacing,
+    ImagerPixelSpacing,
+}
+
+/// Physical size of a pixel, as distance between rows then columns in mm
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Calibration {
+    pub spacing: [f64; 2],
+    pub source: SpacingSource,
+}
+
+impl Calibration {
+    /// PixelSpacing, from the dataset or the frame's Pixel Measures, or else
+    /// ImagerPixelSpacing
+    pub fn from_obj(obj: &InMemDicomObject, frame: u32) -> Option<Self> {
+        let spacing = |obj: &InMemDicomObject, tag: Tag| -> Option<[f64; 2]> {
+            let values = obj.element(tag).ok()?.to_multi_float64().ok()?;
+            let spacing: [f64; 2] = values.get(..2)?.try_into().ok()?;
+            spacing.iter().all(|v| *v > 0.0).then_some(spacing)
+        };
+        let pixel_spacing = spacing(obj, tags::PIXEL_SPACING).or_else(|| {
+            let measures = frame_group(obj, frame, tags::PIXEL_MEASURES_SEQUENCE)?;
+            spacing(measures, tags::PIXEL_SPACING)
+        });
+        match pixel_spacing {
+            Some(spacing) => Some(Self {
+                spacing,
+                source: SpacingSource::PixelSpacing,
+            }),
+            None => spacing(obj, tags::IMAGER_PIXEL_SPACING).map(|spacing| Self {
+                spacing,
+                source: SpacingSource::ImagerPixelSpacing,
+            }),
+        }
+    }
+
+    /// Caveat for sizes measured with this calibration
+    pub fn warning(&self) -> Option<&'static str> {
+        match self.source {
+            SpacingSource::PixelSpacing => None,
+            SpacingSource::ImagerPixelSpacing => {
+                Some("ImagerPixelSpacing: sizes are at the detector, not in the patient")
+            }
+        }
+    }
+}
+
+/// Statistics of the modality values inside a region
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct RoiStats {
+    pub pixels: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub area_mm2: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MeasurementKind {
+    Roi { shape: Shape, stats: RoiStats },
+    Distance { pixels: f64, mm: Option<f64> },
+}
+
+/// A measurement taken between two pixels, given as [row, column]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Measurement {
+    pub frame: u32,
+    pub start: [u32; 2],
+    pub end: [u32; 2],
+    #[serde(flatten)]
+    pub kind: MeasurementKind,
+}
+
+impl Measurement {
+    pub fn roi(
+        frame: &MonochromeFrame,
+        index: u32,
+        shape: Shape,
+        start: [u32; 2],
+        end: [u32; 2],
+        calibration: Option<&Calibration>,
+    ) -> Option<Self> {
+        Some(Self {
+            frame: index,
+            start,
+            end,
+            kind: MeasurementKind::Roi {
+                shape,
+                stats: roi_stats(frame, shape, start, end, calibration)?,
+            },
+        })
+    }
+
+    pub fn distance(
+        index: u32,
+        start: [u32; 2],
+        end: [u32; 2],
+        calibration: Option<&Calibration>,
+    ) -> Self {
+        let rows = end[0] as f64 - start[0] as f64;
+        let columns = end[1] as f64 - start[1] as f64;
+        Self {
+            frame: index,
+            start,
+            end,
+            kind: MeasurementKind::Distance {
+                pixels: rows.hypot(columns),
+                mm: calibration.map(|c| (rows * c.spacing[0]).hypot(columns * c.spacing[1])),
+            },
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            MeasurementKind::Roi { shape, stats } => {
+                let name = match shape {
+                    Shape::Rectangle => "Rect",
+                    Shape::Ellipse => "Ellipse",
+                };
+                let area = match stats.area_mm2 {
+                    Some(mm2) => format!("{mm2:.1} mm²"),
+                    None => format!("{} px", stats.pixels),
+                };
+                format!(
+                    "{name} min {:.1} max {:.1} mean {:.1} sd {:.1} area {area}",
+                    stats.min, stats.max, stats.mean, stats.std_dev
+                )
+            }
+            MeasurementKind::Distance { pixels, mm } => match mm {
+                Some(mm) => format!("Distance {mm:.2} mm"),
+                None => format!("Distance {pixels:.1} px"),
+            },
+        }
+    }
+}
+
+/// Whether a pixel lies in the region spanned by two corners, inclusive
+fn contains(shape: Shape, start: [u32; 2], end: [u32; 2], pixel: [u32; 2]) -> bool {
+    let (top, bottom) = (start[0].min(end[0]), start[0].max(end[0]));
+    let (left, right) = (start[1].min(end[1]), start[1].max(end[1]));
+    let in_box = (top..=bottom).contains(&pixel[0]) && (left..=right).contains(&pixel[1]);
+    match shape {
+        Shape::Rectangle => in_box,
+        Shape::Ellipse => {
+            let center = ((top + bottom) as f64 / 2.0, (left + right) as f64 / 2.0);
+            let radii = (
+                (bottom - top) as f64 / 2.0 + 0.5,
+                (right - left) as f64 / 2.0 + 0.5,
+            );
+            let dy = (pixel[0] as f64 - center.0) / radii.0;
+            let dx = (pixel[1] as f64 - center.1) / radii.1;
+            in_box && dy * dy + dx * dx <= 1.0
+        }
+    }
+}
+
+pub fn roi_stats(
+    frame: &MonochromeFrame,
+    shape: Shape,
+    start: [u32; 2],
+    end: [u32; 2],
+    calibration: Option<&Calibration>,
+) -> Option<RoiStats> {
+    let rows = start[0].min(end[0])..=start[0].max(end[0]).min(frame.rows.saturating_sub(1));
+    let values: Vec<f64> = rows
+        .flat_map(|row| {
+            let columns =
+                start[1].min(end[1])..=start[1].max(end[1]).min(frame.columns.saturating_sub(1));
+            columns.map(move |column| [row, column])
+        })
+        .filter(|&pixel| contains(shape, start, end, pixel))
+        .filter_map(|[row, column]| frame.value(row, column))
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count;
+    Some(RoiStats {
+        pixels: values.len(),
+        min: values.iter().copied().fold(f64::MAX, f64::min),
+        max: values.iter().copied().fold(f64::MIN, f64::max),
+        mean,
+        std_dev: variance.sqrt(),
+        area_mm2: calibration.map(|c| count * c.spacing[0] * c.spacing[1]),
+    })
+}
+
+#[derive(Serialize)]
+struct MeasurementExport<'a> {
+    file: &'a str,
+    calibration: Option<&'a Calibration>,
+    warning: Option<&'static str>,
+    measurements: &'a [Measurement],
+}
+
+/// The measurements of a file as a JSON document
+pub fn measurements_json(
+    file: &str,
+    calibration: Option<&Calibration>,
+    measurements: &[Measurement],
+) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&MeasurementExport {
+        file,
+        calibration,
+        warning: calibration.and_then(Calibration::warning),
+        measurements,
+    })
+}
@@ -1,9 +1,74 @@
+mod annotation;
+mod attr;
 mod datetime;
 mod diff;
+mod document;
+mod dose;
+mod export;
+mod folder;
+mod functional;
+mod geometry;
+mod histogram;
 mod loader;
+mod measure;
+mod overlay;
+mod palette;
+mod pixels;
+mod presentation;
+mod reference;
+mod report;
+mod segmentation;
 mod tag;
+mod thumbnail;
+mod units;
+mod voi;
+mod volume;
+mod waveform;
 
+pub(crate) use attr::tag_label;
+pub use annotation::{corner_text, CornerText};
 pub use datetime::parse_dicom_datetime_delta_ms;
 pub use diff::compare_dicom_files;
-pub use loader::{extract_tags, load_dicom_file};
-pub use tag::{DiffStatus, DicomTag};
+pub use document::EncapsulatedDocument;
+pub use dose::{dose_summary, DoseScope, DoseSource, DoseSummary};
+pub use export::{export_image, rendered_frame, save_frames, ExportOptions};
+pub use folder::FolderHeaders;
+pub use functional::FunctionalGroups;
+pub use geometry::{frame_of_reference, frame_planes, ImagePlane};
+pub use histogram::{pixel_padding, Histogram};
+pub use loader::extract_tags;
+pub use measure::{measurements_json, Calibration, Measurement, MeasurementKind, Shape};
+pub use overlay::{overlay_planes, OverlayPlane};
+pub use palette::PaletteLut;
+pub use pixels::{frame_interval, FramePixels};
+pub use presentation::{
+    presentation_states_for, sop_instance_uid, DisplayTransform, GraphicType, PresentationState,
+};
+pub use reference::{series_reference_planes, ReferencePlane};
+pub use report::content_tree;
+pub use segmentation::{segmentations_for, ImageTarget, Roi, Segmentation};
+pub use tag::{is_private_group, DicomTag, DiffStatus};
+pub use thumbnail::{load_thumbnail, Thumbnail, ThumbnailSource};
+pub use units::ValueUnits;
+pub use voi::{file_vois, Voi, VoiTransform, Window, WINDOW_PRESETS};
+pub use volume::{load_volume, Axis, Volume};
+pub use waveform::{waveforms, Waveform};
+
+// Used by the tests but not by the binary, which builds this module again
+#[allow(unused_imports)]
+pub use {
+    dose::{image_dose, report_dose, DoseEvent},
+    export::{parse_frame_range, OVERLAY_COLOR},
+    geometry::frame_group,
+    loader::load_dicom_file,
+    measure::{roi_stats, RoiStats, SpacingSource},
+    palette::expand_segments,
+    pixels::MonochromeFrame,
+    presentation::{references_image, Graphic, TextAnnotation},
+    reference::reference_planes,
+    segmentation::{lab_to_rgb, refers_to, Contour, SegmentMask, SegmentationKind},
+    thumbnail::{icon_image, THUMBNAIL_SIZE},
+    units::suv_factor,
+    voi::{VoiFunction, VoiLut},
+    waveform::WaveformChannel,
+};
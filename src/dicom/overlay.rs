@@ -0,0 +1,89 @@
+use dicom::core::Tag;
+use dicom::object::InMemDicomObject;
+
+/// A bitmap overlay from one of the repeating groups 6000-601E
+#[derive(Clone, Debug, PartialEq)]
+pub struct OverlayPlane {
+    pub group: u16,
+    pub rows: u32,
+    pub columns: u32,
+    /// Image pixel under the overlay's first pixel, as 1-based (row, column)
+    pub origin: (i32, i32),
+    /// Overlay frames, each applying to one image frame from `first_frame`,
+    /// or `None` when a single overlay applies to every frame
+    pub frames: Option<(u32, u32)>,
+    /// "G" for graphics or "R" for a region of interest
+    pub kind: String,
+    pub label: Option<String>,
+    /// One bit per pixel, row by row, least significant bit first
+    pub data: Vec<u8>,
+}
+
+impl OverlayPlane {
+    /// Whether the overlay marks an image pixel of a 0-based frame
+    pub fn is_set(&self, frame: u32, row: u32, column: u32) -> bool {
+        let overlay_frame = match self.frames {
+            Some((first_frame, count)) => match frame.checked_sub(first_frame) {
+                Some(offset) if offset < count => offset,
+                _ => return false,
+            },
+            None => 0,
+        };
+        let row = row as i64 - (self.origin.0 as i64 - 1);
+        let column = column as i64 - (self.origin.1 as i64 - 1);
+        if row < 0 || column < 0 || row >= self.rows as i64 || column >= self.columns as i64 {
+            return false;
+        }
+        let bit = (overlay_frame as usize * self.rows as usize + row as usize)
+            * self.columns as usize
+            + column as usize;
+        self.data
+            .get(bit / 8)
+            .is_some_and(|byte| byte & (1 << (bit % 8)) != 0)
+    }
+}
+
+/// Overlay planes stored in OverlayData. Overlays embedded in the unused
+/// bits of the pixel data were retired and are not read.
+pub fn overlay_planes(obj: &InMemDicomObject) -> Vec<OverlayPlane> {
+    (0x6000..=0x601E)
+        .step_by(2)
+        .filter_map(|group| overlay_plane(obj, group))
+        .collect()
+}
+
+fn overlay_plane(obj: &InMemDicomObject, group: u16) -> Option<OverlayPlane> {
+    let element = |element| obj.element(Tag(group, element)).ok();
+    let int = |tag| element(tag).and_then(|e| e.to_int::<u32>().ok());
+    let text = |tag| {
+        element(tag)
+            .and_then(|e| e.to_str().ok())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    let data = element(0x3000)?.to_bytes().ok()?.into_owned();
+    let rows = int(0x0010)?;
+    let columns = int(0x0011)?;
+    if int(0x0100).is_some_and(|bits| bits != 1) {
+        return None;
+    }
+    let origin = element(0x0050)
+        .and_then(|e| e.to_multi_int::<i32>().ok())
+        .and_then(|values| Some((*values.first()?, *values.get(1)?)))
+        .unwrap_or((1, 1));
+    let frames = int(0x0015).map(|count| {
+        let first_frame = int(0x0051).unwrap_or(1).saturating_sub(1);
+        (first_frame, count)
+    });
+    Some(OverlayPlane {
+        group,
+        rows,
+        columns,
+        origin,
+        frames,
+        kind: text(0x0040).unwrap_or_else(|| "G".into()),
+        label: text(0x1500).or_else(|| text(0x0022)),
+        data,
+    })
+}
@@ -0,0 +1,161 @@
+use dicom::core::Tag;
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// One color channel of a palette: the LUT entries and the stored value
+/// mapped to the first one
+#[derive(Clone, Debug, PartialEq)]
+struct PaletteChannel {
+    first_value: i32,
+    bits: u16,
+    data: Vec<u16>,
+}
+
+impl PaletteChannel {
+    fn lookup(&self, value: i32) -> u8 {
+        let index = (value - self.first_value).clamp(0, self.data.len() as i32 - 1) as usize;
+        let entry = self.data[index];
+        if self.bits > 8 {
+            (entry >> 8) as u8
+        } else {
+            entry as u8
+        }
+    }
+}
+
+/// Red, green and blue palette LUTs of a PALETTE COLOR image, from the
+/// normal or the segmented LUT data (PS3.3 C.7.6.3.1.5-6)
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaletteLut {
+    channels: [PaletteChannel; 3],
+}
+
+impl PaletteLut {
+    pub fn from_obj(obj: &InMemDicomObject) -> Option<Self> {
+        let channel =
+            |descriptor, data, segmented| palette_channel(obj, descriptor, data, segmented);
+        Some(Self {
+            channels: [
+                channel(
+                    tags::RED_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR,
+                    tags::RED_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+                    tags::SEGMENTED_RED_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+                )?,
+                channel(
+                    tags::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR,
+                    tags::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+                    tags::SEGMENTED_GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+                )?,
+                channel(
+                    tags::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR,
+                    tags::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+                    tags::SEGMENTED_BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+                )?,
+            ],
+        })
+    }
+
+    /// Color of a stored value
+    pub fn lookup(&self, value: i32) -> [u8; 3] {
+        self.channels
+            .each_ref()
+            .map(|channel| channel.lookup(value))
+    }
+}
+
+/// Little endian 16-bit words of an OW or US value
+fn words(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect()
+}
+
+fn palette_channel(
+    obj: &InMemDicomObject,
+    descriptor: Tag,
+    data: Tag,
+    segmented: Tag,
+) -> Option<PaletteChannel> {
+    let descriptor = obj.element(descriptor).ok()?.to_multi_int::<i32>().ok()?;
+    let &[entries, first_value, bits] = descriptor.get(..3)? else {
+        return None;
+    };
+    // 0 entries stands for 2^16
+    let entries = if entries == 0 {
+        65536
+    } else {
+        entries as usize
+    };
+    let bits = bits as u16;
+
+    let data = match obj.element(data) {
+        Ok(element) => {
+            let bytes = element.to_bytes().ok()?;
+            // 8-bit entries are either packed one per byte, or padded to a
+            // word each
+            if bits <= 8 && bytes.len() < entries * 2 {
+                bytes.iter().map(|&b| b as u16).collect()
+            } else {
+                words(&bytes)
+            }
+        }
+        Err(_) => expand_segments(&words(&obj.element(segmented).ok()?.to_bytes().ok()?))?,
+    };
+    (!data.is_empty()).then_some(PaletteChannel {
+        first_value,
+        bits,
+        data,
+    })
+}
+
+/// Expands segmented LUT data made of discrete, linear and indirect
+/// segments. `None` when the data is malformed.
+pub fn expand_segments(segments: &[u16]) -> Option<Vec<u16>> {
+    fn expand(segments: &[u16], out: &mut Vec<u16>, depth: u8) -> Option<()> {
+        let mut i = 0;
+        while i < segments.len() {
+            let opcode = segments[i];
+            let length = *segments.get(i + 1)? as usize;
+            match opcode {
+                // Discrete: the next `length` words are entries
+                0 => {
+                    out.extend(segments.get(i + 2..i + 2 + length)?);
+                    i += 2 + length;
+                }
+                // Linear: a ramp from the previous entry to the given one
+                1 => {
+                    let start = *out.last()? as f64;
+                    let end = *segments.get(i + 2)? as f64;
+                    out.extend((1..=length).map(|step| {
+                        (start + (end - start) * step as f64 / length as f64).round() as u16
+                    }));
+                    i += 3;
+                }
+                // Indirect: repeats `length` segments found at a byte offset
+                2 if depth == 0 => {
+                    let low = *segments.get(i + 2)? as usize;
+                    let high = *segments.get(i + 3)? as usize;
+                    let offset = (high << 16 | low) / 2;
+                    let mut position = offset;
+                    for _ in 0..length {
+                        let segment_len = match *segments.get(position)? {
+                            0 => 2 + *segments.get(position + 1)? as usize,
+                            1 => 3,
+                            _ => return None,
+                        };
+                        expand(segments.get(position..position + segment_len)?, out, 1)?;
+                        position += segment_len;
+                    }
+                    i += 4;
+                }
+                _ => return None,
+            }
+        }
+        Some(())
+    }
+
+    let mut out = Vec::new();
+    expand(segments, &mut out, 0)?;
+    Some(out)
+}
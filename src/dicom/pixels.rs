@@ -0,0 +1,261 @@
+use super::palette::PaletteLut;
+use super::voi::{Voi, VoiTransform, Window};
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+use dicom_pixeldata::image::{DynamicImage, GrayImage, RgbImage};
+use dicom_pixeldata::{
+    ConvertOptions, DecodedPixelData, ModalityLutOption, PhotometricInterpretation,
+    PixelRepresentation, PlanarConfiguration,
+};
+use std::time::Duration;
+
+/// Pixel values of one decoded frame, kept so that the display can be
+/// recomputed without decoding again
+#[derive(Clone, Debug)]
+pub enum FramePixels {
+    Monochrome(MonochromeFrame),
+    Color(RgbImage),
+}
+
+/// A grayscale frame in modality units, after RescaleSlope/Intercept
+#[derive(Clone, Debug)]
+pub struct MonochromeFrame {
+    pub rows: u32,
+    pub columns: u32,
+    pub values: Vec<f32>,
+    pub slope: f64,
+    pub intercept: f64,
+    /// MONOCHROME1: the lowest value is displayed white
+    pub invert: bool,
+}
+
+impl FramePixels {
+    /// Converts a frame. PALETTE COLOR frames need the file's palette.
+    pub fn from_decoded(
+        data: &DecodedPixelData<'_>,
+        frame: u32,
+        palette: Option<&PaletteLut>,
+    ) -> Result<Self, dicom_pixeldata::Error> {
+        let photometric = data.photometric_interpretation();
+        if !photometric.is_monochrome() {
+            let converted = match photometric {
+                PhotometricInterpretation::PaletteColor => {
+                    palette.and_then(|palette| palette_frame(data, frame, palette))
+                }
+                _ => color_frame(data, frame),
+            };
+            return match converted {
+                Some(image) => Ok(Self::Color(image)),
+                None => Ok(Self::Color(data.to_dynamic_image(frame)?.to_rgb8())),
+            };
+        }
+
+        let rescale = data.rescale()?;
+        let mut rescale = rescale
+            .get(frame as usize)
+            .or_else(|| rescale.first())
+            .copied()
+            .unwrap_or(dicom_pixeldata::Rescale {
+                slope: 1.0,
+                intercept: 0.0,
+            });
+        // A RescaleSlope of 0 would flatten every value and make stored
+        // values undefined, so it is read as 1
+        let values = if rescale.slope == 0.0 {
+            rescale.slope = 1.0;
+            let options =
+                ConvertOptions::new().with_modality_lut(ModalityLutOption::Override(rescale));
+            data.to_vec_frame_with_options(frame, &options)?
+        } else {
+            data.to_vec_frame(frame)?
+        };
+        Ok(Self::Monochrome(MonochromeFrame {
+            rows: data.rows(),
+            columns: data.columns(),
+            values,
+            slope: rescale.slope,
+            intercept: rescale.intercept,
+            invert: *photometric == PhotometricInterpretation::Monochrome1,
+        }))
+    }
+
+    /// Width and height in pixels
+    pub fn dimensions(&self) -> (u32, u32) {
+        match self {
+            Self::Monochrome(frame) => (frame.columns, frame.rows),
+            Self::Color(image) => image.dimensions(),
+        }
+    }
+
+    /// The frame as an 8-bit image, with `voi` applied to grayscale frames
+    pub fn render(&self, voi: Option<&Voi>) -> DynamicImage {
+        match self {
+            Self::Monochrome(frame) => {
+                let transform = voi
+                    .map(|v| v.transform.clone())
+                    .unwrap_or_else(|| VoiTransform::Window(frame.full_range_window()));
+                DynamicImage::ImageLuma8(frame.render(&transform))
+            }
+            Self::Color(image) => DynamicImage::ImageRgb8(image.clone()),
+        }
+    }
+}
+
+impl MonochromeFrame {
+    /// Lowest and highest modality values of the frame
+    pub fn min_max(&self) -> (f64, f64) {
+        self.values
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(lo, hi), &v| {
+                (lo.min(v as f64), hi.max(v as f64))
+            })
+    }
+
+    /// Modality value at a pixel
+    pub fn value(&self, row: u32, column: u32) -> Option<f64> {
+        if row >= self.rows || column >= self.columns {
+            return None;
+        }
+        let index = row as usize * self.columns as usize + column as usize;
+        self.values.get(index).map(|&v| v as f64)
+    }
+
+    /// Stored value at a pixel, before RescaleSlope/Intercept
+    pub fn stored_value(&self, row: u32, column: u32) -> Option<f64> {
+        let value = self.value(row, column)?;
+        Some(((value - self.intercept) / self.slope).round())
+    }
+
+    /// A window spanning every value in the frame
+    pub fn full_range_window(&self) -> Window {
+        let (min, max) = self.min_max();
+        if min > max {
+            return Window::new(0.0, 1.0);
+        }
+        Window::new((min + max + 1.0) / 2.0, max - min + 1.0)
+    }
+
+    pub fn render(&self, transform: &VoiTransform) -> GrayImage {
+        let pixels = self
+            .values
+            .iter()
+            .map(|&v| {
+                let level = transform.apply(v as f64);
+                let level = if self.invert { 1.0 - level } else { level };
+                (level * 255.0).round() as u8
+            })
+            .collect();
+        GrayImage::from_raw(self.columns, self.rows, pixels).unwrap_or_default()
+    }
+}
+
+/// Raw bytes of one frame holding `samples` samples
+fn frame_bytes<'a>(data: &'a DecodedPixelData<'_>, frame: u32, samples: usize) -> Option<&'a [u8]> {
+    let bytes_per_sample = data.bits_allocated().div_ceil(8) as usize;
+    if bytes_per_sample > 2 {
+        return None;
+    }
+    let length = samples * bytes_per_sample;
+    let start = frame as usize * length;
+    data.data().get(start..start + length)
+}
+
+/// Sample `index` of 8 or 16-bit data, little endian
+fn sample(bytes: &[u8], bits_allocated: u16, index: usize) -> u16 {
+    if bits_allocated <= 8 {
+        bytes[index] as u16
+    } else {
+        u16::from_le_bytes([bytes[2 * index], bytes[2 * index + 1]])
+    }
+}
+
+/// Maps the stored values of a PALETTE COLOR frame through its palette
+fn palette_frame(
+    data: &DecodedPixelData<'_>,
+    frame: u32,
+    palette: &PaletteLut,
+) -> Option<RgbImage> {
+    let (columns, rows) = (data.columns(), data.rows());
+    let pixels = columns as usize * rows as usize;
+    let bytes = frame_bytes(data, frame, pixels)?;
+    let bits = data.bits_allocated();
+    let signed = data.pixel_representation() == PixelRepresentation::Signed;
+    let rgb = (0..pixels)
+        .flat_map(|i| {
+            let stored = sample(bytes, bits, i);
+            let value = match (signed, bits <= 8) {
+                (true, true) => stored as u8 as i8 as i32,
+                (true, false) => stored as i16 as i32,
+                (false, _) => stored as i32,
+            };
+            palette.lookup(value)
+        })
+        .collect();
+    RgbImage::from_raw(columns, rows, rgb)
+}
+
+/// Converts an uncompressed RGB, YBR_FULL or YBR_FULL_422 frame in either
+/// planar configuration. Compressed color frames come out of the decoder
+/// as interleaved RGB already.
+fn color_frame(data: &DecodedPixelData<'_>, frame: u32) -> Option<RgbImage> {
+    if data.samples_per_pixel() != 3 {
+        return None;
+    }
+    let photometric = data.photometric_interpretation();
+    let subsampled = *photometric == PhotometricInterpretation::YbrFull422;
+    let ybr = subsampled || *photometric == PhotometricInterpretation::YbrFull;
+    if !ybr && *photometric != PhotometricInterpretation::Rgb {
+        return None;
+    }
+
+    let (columns, rows) = (data.columns(), data.rows());
+    let pixels = columns as usize * rows as usize;
+    let samples = if subsampled { pixels * 2 } else { pixels * 3 };
+    let bytes = frame_bytes(data, frame, samples)?;
+    let bits = data.bits_allocated();
+    let scale = 255.0 / ((1u32 << data.bits_stored().min(16)) - 1) as f64;
+    let value = |index: usize| sample(bytes, bits, index) as f64 * scale;
+    let planar = data.planar_configuration() == PlanarConfiguration::PixelFirst;
+
+    let rgb = (0..pixels)
+        .flat_map(|i| {
+            let [a, b, c] = if subsampled {
+                // Y1 Y2 Cb Cr for each pair of pixels in a row
+                let pair = i / 2 * 4;
+                [value(pair + i % 2), value(pair + 2), value(pair + 3)]
+            } else if planar {
+                [value(i), value(pixels + i), value(2 * pixels + i)]
+            } else {
+                [value(3 * i), value(3 * i + 1), value(3 * i + 2)]
+            };
+            let [r, g, b] = if ybr {
+                // PS3.3 C.7.6.3.1.2
+                let (y, cb, cr) = (a, b - 128.0, c - 128.0);
+                [
+                    y + 1.402 * cr,
+                    y - 0.344136 * cb - 0.714136 * cr,
+                    y + 1.772 * cb,
+                ]
+            } else {
+                [a, b, c]
+            };
+            [r, g, b].map(|v| v.round().clamp(0.0, 255.0) as u8)
+        })
+        .collect();
+    RgbImage::from_raw(columns, rows, rgb)
+}
+
+/// Time between frames for cine playback, from FrameTime,
+/// RecommendedDisplayFrameRate or CineRate
+pub fn frame_interval(obj: &InMemDicomObject) -> Option<Duration> {
+    let number = |tag| {
+        obj.element(tag)
+            .ok()
+            .and_then(|e| e.to_float64().ok())
+            .filter(|v| v.is_finite() && *v > 0.0)
+    };
+    let millis = number(tags::FRAME_TIME)
+        .or_else(|| number(tags::RECOMMENDED_DISPLAY_FRAME_RATE).map(|fps| 1000.0 / fps))
+        .or_else(|| number(tags::CINE_RATE).map(|fps| 1000.0 / fps))?;
+    Some(Duration::from_secs_f64(millis / 1000.0))
+}
@@ -0,0 +1,393 @@
+use super::attr::{items, text};
+use super::folder::FolderHeaders;
+use super::geometry::uid;
+use super::voi::{file_vois, Voi};
+use dicom::core::Tag;
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::{open_file, InMemDicomObject};
+use dicom_pixeldata::image::{imageops, RgbImage};
+use std::f64::consts::TAU;
+use std::path::{Path, PathBuf};
+
+/// Segments used to draw circles and ellipses
+const CURVE_SEGMENTS: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphicType {
+    Point,
+    Polyline,
+    Interpolated,
+    Circle,
+    Ellipse,
+}
+
+impl GraphicType {
+    fn from_code(code: &str) -> Option<Self> {
+        match code.trim() {
+            "POINT" => Some(Self::Point),
+            "POLYLINE" => Some(Self::Polyline),
+            "INTERPOLATED" => Some(Self::Interpolated),
+            "CIRCLE" => Some(Self::Circle),
+            "ELLIPSE" => Some(Self::Ellipse),
+            _ => None,
+        }
+    }
+}
+
+/// A graphic object of a presentation state
+#[derive(Clone, Debug, PartialEq)]
+pub struct Graphic {
+    pub kind: GraphicType,
+    /// Positions as (x, y): image pixels from the image's top-left corner,
+    /// or fractions of the displayed area for display units
+    pub points: Vec<(f64, f64)>,
+    pub display_units: bool,
+    /// Frames it is drawn on, 0-based; empty for every frame
+    pub frames: Vec<u32>,
+}
+
+/// A text object of a presentation state
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextAnnotation {
+    pub text: String,
+    /// Top-left corner of the bounding box, or the anchor point
+    pub position: Option<(f64, f64)>,
+    pub display_units: bool,
+    /// Frames it is shown on, 0-based; empty for every frame
+    pub frames: Vec<u32>,
+}
+
+/// Displayed area, flip and rotation of a presentation state, mapping
+/// image positions to the displayed image and back
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisplayTransform {
+    /// Image region shown, as (x0, y0, x1, y1) in image pixels
+    pub area: [f64; 4],
+    pub flip: bool,
+    /// Clockwise rotation in degrees, applied after the flip
+    pub rotation: u16,
+}
+
+impl DisplayTransform {
+    /// Shows the whole image unchanged
+    pub fn identity(image: (u32, u32)) -> Self {
+        Self {
+            area: [0.0, 0.0, image.0 as f64, image.1 as f64],
+            flip: false,
+            rotation: 0,
+        }
+    }
+
+    fn area_size(&self) -> (f64, f64) {
+        let [x0, y0, x1, y1] = self.area;
+        ((x1 - x0).max(1.0), (y1 - y0).max(1.0))
+    }
+
+    /// Width and height of the displayed image
+    pub fn size(&self) -> (u32, u32) {
+        let (width, height) = self.area_size();
+        let (width, height) = (width.round() as u32, height.round() as u32);
+        match self.rotation {
+            90 | 270 => (height, width),
+            _ => (width, height),
+        }
+    }
+
+    /// Displayed position of an image position
+    pub fn to_display(self, point: (f64, f64)) -> (f64, f64) {
+        let (width, height) = self.area_size();
+        let (mut u, v) = (point.0 - self.area[0], point.1 - self.area[1]);
+        if self.flip {
+            u = width - u;
+        }
+        match self.rotation {
+            90 => (height - v, u),
+            180 => (width - u, height - v),
+            270 => (v, width - u),
+            _ => (u, v),
+        }
+    }
+
+    /// Image position under a displayed position
+    pub fn to_image(self, point: (f64, f64)) -> (f64, f64) {
+        let (width, height) = self.area_size();
+        let (a, b) = point;
+        let (mut u, v) = match self.rotation {
+            90 => (b, height - a),
+            180 => (width - a, height - b),
+            270 => (width - b, a),
+            _ => (a, b),
+        };
+        if self.flip {
+            u = width - u;
+        }
+        (u + self.area[0], v + self.area[1])
+    }
+
+    /// The displayed area of a rendered image, flipped and rotated. Parts of
+    /// the area outside the image are black.
+    pub fn apply(&self, image: &RgbImage) -> RgbImage {
+        if *self == Self::identity(image.dimensions()) {
+            return image.clone();
+        }
+        let (width, height) = self.area_size();
+        let mut area = RgbImage::new(width.round() as u32, height.round() as u32);
+        imageops::overlay(
+            &mut area,
+            image,
+            -self.area[0].round() as i64,
+            -self.area[1].round() as i64,
+        );
+        if self.flip {
+            imageops::flip_horizontal_in_place(&mut area);
+        }
+        match self.rotation {
+            90 => imageops::rotate90(&area),
+            180 => imageops::rotate180(&area),
+            270 => imageops::rotate270(&area),
+            _ => area,
+        }
+    }
+
+    /// Image position of a point given in image pixels or, for display
+    /// units, as fractions of the displayed area
+    fn resolve(&self, point: (f64, f64), display_units: bool) -> (f64, f64) {
+        if !display_units {
+            return point;
+        }
+        let (width, height) = self.size();
+        self.to_image((point.0 * width as f64, point.1 * height as f64))
+    }
+}
+
+fn applies_on(frames: &[u32], frame: u32) -> bool {
+    frames.is_empty() || frames.contains(&frame)
+}
+
+impl Graphic {
+    pub fn applies_on(&self, frame: u32) -> bool {
+        applies_on(&self.frames, frame)
+    }
+
+    /// Image positions to join with lines; a single position for a point
+    pub fn outline(&self, transform: &DisplayTransform) -> Vec<(f64, f64)> {
+        let points: Vec<(f64, f64)> = self
+            .points
+            .iter()
+            .map(|&p| transform.resolve(p, self.display_units))
+            .collect();
+        let curve = |center: (f64, f64), a: (f64, f64), b: (f64, f64)| {
+            (0..=CURVE_SEGMENTS)
+                .map(|step| {
+                    let (sin, cos) = (step as f64 / CURVE_SEGMENTS as f64 * TAU).sin_cos();
+                    (
+                        center.0 + a.0 * cos + b.0 * sin,
+                        center.1 + a.1 * cos + b.1 * sin,
+                    )
+                })
+                .collect()
+        };
+        match (self.kind, &points[..]) {
+            (GraphicType::Circle, [center, edge, ..]) => {
+                let radius = (edge.0 - center.0).hypot(edge.1 - center.1);
+                curve(*center, (radius, 0.0), (0.0, radius))
+            }
+            (GraphicType::Ellipse, [major0, major1, minor0, minor1, ..]) => {
+                let center = ((major0.0 + major1.0) / 2.0, (major0.1 + major1.1) / 2.0);
+                let half = |p: &(f64, f64), q: &(f64, f64)| ((q.0 - p.0) / 2.0, (q.1 - p.1) / 2.0);
+                curve(center, half(major0, major1), half(minor0, minor1))
+            }
+            _ => points,
+        }
+    }
+}
+
+impl TextAnnotation {
+    pub fn applies_on(&self, frame: u32) -> bool {
+        applies_on(&self.frames, frame)
+    }
+
+    /// Image position the text is attached to
+    pub fn anchor(&self, transform: &DisplayTransform) -> Option<(f64, f64)> {
+        self.position
+            .map(|p| transform.resolve(p, self.display_units))
+    }
+}
+
+/// Display settings of a Grayscale Softcopy Presentation State for one
+/// image
+#[derive(Clone, Debug, PartialEq)]
+pub struct PresentationState {
+    pub label: String,
+    pub voi: Option<Voi>,
+    /// Image region shown, from the Displayed Area Selection
+    pub displayed_area: Option<[f64; 4]>,
+    pub flip: bool,
+    pub rotation: u16,
+    pub graphics: Vec<Graphic>,
+    pub texts: Vec<TextAnnotation>,
+}
+
+fn floats(obj: &InMemDicomObject, tag: Tag) -> Vec<f64> {
+    obj.element(tag)
+        .ok()
+        .and_then(|e| e.to_multi_float64().ok())
+        .unwrap_or_default()
+}
+
+fn pairs(values: &[f64]) -> Vec<(f64, f64)> {
+    values.chunks_exact(2).map(|p| (p[0], p[1])).collect()
+}
+
+/// Frames of the image that an item applies to, 0-based and empty for
+/// every frame, or `None` when its Referenced Image Sequence leaves the
+/// image out
+fn referenced_frames(item: &InMemDicomObject, sop_instance_uid: &str) -> Option<Vec<u32>> {
+    let references = items(item, tags::REFERENCED_IMAGE_SEQUENCE);
+    if references.is_empty() {
+        return Some(Vec::new());
+    }
+    let reference = references.iter().find(|reference| {
+        uid(reference, tags::REFERENCED_SOP_INSTANCE_UID).as_deref() == Some(sop_instance_uid)
+    })?;
+    Some(
+        reference
+            .element(tags::REFERENCED_FRAME_NUMBER)
+            .ok()
+            .and_then(|e| e.to_multi_int::<u32>().ok())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|frame| frame.saturating_sub(1))
+            .collect(),
+    )
+}
+
+/// SOPInstanceUID that presentation states reference the image by
+pub fn sop_instance_uid(obj: &InMemDicomObject) -> Option<String> {
+    uid(obj, tags::SOP_INSTANCE_UID)
+}
+
+/// Whether the presentation state lists the image among those it applies to
+pub fn references_image(obj: &InMemDicomObject, sop_instance_uid: &str) -> bool {
+    items(obj, tags::REFERENCED_SERIES_SEQUENCE)
+        .iter()
+        .flat_map(|series| items(series, tags::REFERENCED_IMAGE_SEQUENCE))
+        .any(|image| {
+            uid(image, tags::REFERENCED_SOP_INSTANCE_UID).as_deref() == Some(sop_instance_uid)
+        })
+}
+
+impl PresentationState {
+    /// The settings of a presentation state that apply to an image
+    pub fn from_obj(obj: &InMemDicomObject, sop_instance_uid: &str) -> Result<Self, String> {
+        if !references_image(obj, sop_instance_uid) {
+            return Err("The presentation state does not reference this image".into());
+        }
+        let applying = |tag| {
+            items(obj, tag)
+                .iter()
+                .find(|item| referenced_frames(item, sop_instance_uid).is_some())
+        };
+
+        let displayed_area = applying(tags::DISPLAYED_AREA_SELECTION_SEQUENCE).and_then(|area| {
+            let top_left = floats(area, tags::DISPLAYED_AREA_TOP_LEFT_HAND_CORNER);
+            let bottom_right = floats(area, tags::DISPLAYED_AREA_BOTTOM_RIGHT_HAND_CORNER);
+            match (&top_left[..], &bottom_right[..]) {
+                ([left, top], [right, bottom]) => Some([left - 1.0, top - 1.0, *right, *bottom]),
+                _ => None,
+            }
+        });
+        let voi = applying(tags::SOFTCOPY_VOILUT_SEQUENCE)
+            .and_then(|item| file_vois(item).into_iter().next());
+        let rotation = obj
+            .element(tags::IMAGE_ROTATION)
+            .ok()
+            .and_then(|e| e.to_int::<u16>().ok())
+            .filter(|r| [90, 180, 270].contains(r))
+            .unwrap_or(0);
+        let flip = text(obj, tags::IMAGE_HORIZONTAL_FLIP).as_deref() == Some("Y");
+
+        let mut graphics = Vec::new();
+        let mut texts = Vec::new();
+        for annotation in items(obj, tags::GRAPHIC_ANNOTATION_SEQUENCE) {
+            let Some(frames) = referenced_frames(annotation, sop_instance_uid) else {
+                continue;
+            };
+            for object in items(annotation, tags::GRAPHIC_OBJECT_SEQUENCE) {
+                let Some(kind) =
+                    text(object, tags::GRAPHIC_TYPE).and_then(|t| GraphicType::from_code(&t))
+                else {
+                    continue;
+                };
+                graphics.push(Graphic {
+                    kind,
+                    points: pairs(&floats(object, tags::GRAPHIC_DATA)),
+                    display_units: text(object, tags::GRAPHIC_ANNOTATION_UNITS).as_deref()
+                        == Some("DISPLAY"),
+                    frames: frames.clone(),
+                });
+            }
+            for object in items(annotation, tags::TEXT_OBJECT_SEQUENCE) {
+                let corner = pairs(&floats(object, tags::BOUNDING_BOX_TOP_LEFT_HAND_CORNER));
+                let (position, units) = match corner.first() {
+                    Some(&corner) => (Some(corner), tags::BOUNDING_BOX_ANNOTATION_UNITS),
+                    None => (
+                        pairs(&floats(object, tags::ANCHOR_POINT)).first().copied(),
+                        tags::ANCHOR_POINT_ANNOTATION_UNITS,
+                    ),
+                };
+                texts.push(TextAnnotation {
+                    text: text(object, tags::UNFORMATTED_TEXT_VALUE).unwrap_or_default(),
+                    position,
+                    display_units: text(object, units).as_deref() == Some("DISPLAY"),
+                    frames: frames.clone(),
+                });
+            }
+        }
+
+        Ok(Self {
+            label: text(obj, tags::CONTENT_LABEL).unwrap_or_else(|| "GSPS".into()),
+            voi,
+            displayed_area,
+            flip,
+            rotation,
+            graphics,
+            texts,
+        })
+    }
+
+    /// Reads a presentation state file for an image
+    pub fn open(path: &Path, sop_instance_uid: &str) -> Result<Self, String> {
+        let obj = open_file(path).map_err(|e| format!("Failed to open presentation state: {e}"))?;
+        Self::from_obj(&obj, sop_instance_uid)
+    }
+
+    /// Spatial transform for an image of the given size
+    pub fn transform(&self, image: (u32, u32)) -> DisplayTransform {
+        DisplayTransform {
+            area: self
+                .displayed_area
+                .unwrap_or(DisplayTransform::identity(image).area),
+            flip: self.flip,
+            rotation: self.rotation,
+        }
+    }
+}
+
+/// Grayscale Softcopy Presentation States among the headers of the image's
+/// folder that reference it, by file name
+pub fn presentation_states_for(
+    headers: &FolderHeaders,
+    image: &Path,
+    sop_instance_uid: &str,
+) -> Vec<PathBuf> {
+    headers
+        .others(image)
+        .filter(|(_, obj)| {
+            uid(obj, tags::SOP_CLASS_UID).as_deref()
+                == Some(uids::GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE)
+                && references_image(obj, sop_instance_uid)
+        })
+        .map(|(path, _)| path.to_path_buf())
+        .collect()
+}
@@ -0,0 +1,109 @@
+use super::folder::FolderHeaders;
+use super::geometry::{dot, frame_of_reference, frame_planes, sub, uid, unit, ImagePlane};
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+use std::path::Path;
+
+/// Plane and extent of an image whose reference line is drawn on others
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReferencePlane {
+    /// File name, with the frame number for multi-frame files
+    pub label: String,
+    pub frame_of_reference: Option<String>,
+    pub plane: ImagePlane,
+    /// Columns and rows
+    pub size: (u32, u32),
+}
+
+impl ReferencePlane {
+    /// Where this image cuts the `target` plane, as two (x, y) positions in
+    /// target pixels. `None` when the planes are parallel or meet outside
+    /// this image.
+    pub fn line_on(&self, target: &ImagePlane) -> Option<[(f64, f64); 2]> {
+        let normal = unit(target.normal());
+        let (columns, rows) = (self.size.0 as f64, self.size.1 as f64);
+        let corners = [
+            (-0.5, -0.5),
+            (-0.5, columns - 0.5),
+            (rows - 0.5, columns - 0.5),
+            (rows - 0.5, -0.5),
+        ]
+        .map(|(row, column)| self.plane.patient_point(row, column));
+        let distances = corners.map(|corner| dot(sub(corner, target.position), normal));
+
+        let mut points: Vec<[f64; 3]> = Vec::new();
+        for edge in 0..4 {
+            let (a, b) = (edge, (edge + 1) % 4);
+            let (da, db) = (distances[a], distances[b]);
+            if da == db || (da.signum() == db.signum() && da != 0.0 && db != 0.0) {
+                continue;
+            }
+            let t = da / (da - db);
+            let point = std::array::from_fn(|axis| {
+                corners[a][axis] + (corners[b][axis] - corners[a][axis]) * t
+            });
+            let known = points
+                .iter()
+                .any(|p| dot(sub(*p, point), sub(*p, point)) < 1e-6);
+            if !known {
+                points.push(point);
+            }
+        }
+        let [from, to] = points[..] else {
+            return None;
+        };
+        let to_target = |point: [f64; 3]| {
+            let (row, column) = target.pixel_position(point);
+            (column + 0.5, row + 0.5)
+        };
+        Some([to_target(from), to_target(to)])
+    }
+}
+
+fn size(obj: &InMemDicomObject) -> Option<(u32, u32)> {
+    let read = |tag| obj.element(tag).ok()?.to_int::<u32>().ok();
+    Some((read(tags::COLUMNS)?, read(tags::ROWS)?))
+}
+
+/// One reference plane per frame with geometry
+pub fn reference_planes(obj: &InMemDicomObject, name: &str) -> Vec<ReferencePlane> {
+    let Some(size) = size(obj) else {
+        return Vec::new();
+    };
+    let planes = frame_planes(obj);
+    let multi_frame = planes.len() > 1;
+    planes
+        .into_iter()
+        .enumerate()
+        .map(|(frame, plane)| ReferencePlane {
+            label: match multi_frame {
+                true => format!("{name} frame {}", frame + 1),
+                false => name.to_string(),
+            },
+            frame_of_reference: frame_of_reference(obj),
+            plane,
+            size,
+        })
+        .collect()
+}
+
+/// Reference planes of every image of a file's series among the headers of
+/// its folder
+pub fn series_reference_planes(
+    headers: &FolderHeaders,
+    path: &Path,
+) -> Result<Vec<ReferencePlane>, String> {
+    let obj = headers
+        .get(path)
+        .ok_or_else(|| format!("{} is not a DICOM file", path.display()))?;
+    let series = uid(obj, tags::SERIES_INSTANCE_UID)
+        .ok_or("No SeriesInstanceUID to find the other slices by")?;
+    let mut planes = Vec::new();
+    for (file, other) in headers.iter() {
+        if uid(other, tags::SERIES_INSTANCE_UID).as_deref() == Some(series.as_str()) {
+            let name = file.file_name().unwrap_or_default().to_string_lossy();
+            planes.extend(reference_planes(other, &name));
+        }
+    }
+    Ok(planes)
+}
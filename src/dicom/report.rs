@@ -0,0 +1,134 @@
+use super::attr::{items, text};
+use super::tag::DicomTag;
+use dicom::core::Tag;
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// Meaning of the first item of a code sequence
+fn code_meaning(obj: &InMemDicomObject, sequence: Tag) -> Option<String> {
+    text(items(obj, sequence).first()?, tags::CODE_MEANING)
+}
+
+/// Numeric value with its units: the UCUM code when there is one, as it
+/// is shorter than the meaning
+fn numeric(item: &InMemDicomObject) -> String {
+    let Some(measured) = items(item, tags::MEASURED_VALUE_SEQUENCE).first() else {
+        return code_meaning(item, tags::NUMERIC_VALUE_QUALIFIER_CODE_SEQUENCE)
+            .unwrap_or_else(|| "(no value)".into());
+    };
+    let value = text(measured, tags::NUMERIC_VALUE).unwrap_or_default();
+    let units = items(measured, tags::MEASUREMENT_UNITS_CODE_SEQUENCE)
+        .first()
+        .and_then(
+            |code| match text(code, tags::CODING_SCHEME_DESIGNATOR).as_deref() {
+                Some("UCUM") => text(code, tags::CODE_VALUE).filter(|unit| unit != "1"),
+                _ => text(code, tags::CODE_MEANING),
+            },
+        );
+    match units {
+        Some(units) => format!("{value} {units}"),
+        None => value,
+    }
+}
+
+/// Referenced instance of an IMAGE, COMPOSITE or WAVEFORM item, with its
+/// frames or channels
+fn reference(item: &InMemDicomObject, kind: &str) -> String {
+    let Some(sop) = items(item, tags::REFERENCED_SOP_SEQUENCE).first() else {
+        return format!("{kind} (no reference)");
+    };
+    let mut value = format!(
+        "{kind} {}",
+        text(sop, tags::REFERENCED_SOP_INSTANCE_UID).unwrap_or_default()
+    );
+    if let Some(frames) = text(sop, tags::REFERENCED_FRAME_NUMBER) {
+        value.push_str(&format!(" frame {}", frames.replace('\\', ",")));
+    }
+    if let Some(presentation) = items(sop, tags::REFERENCED_SOP_SEQUENCE)
+        .first()
+        .and_then(|ps| text(ps, tags::REFERENCED_SOP_INSTANCE_UID))
+    {
+        value.push_str(&format!(" with GSPS {presentation}"));
+    }
+    value
+}
+
+/// Spatial coordinates, summarized by their shape and point count
+fn coordinates(item: &InMemDicomObject, dimensions: usize) -> String {
+    let values = item
+        .element(tags::GRAPHIC_DATA)
+        .ok()
+        .and_then(|e| e.to_multi_float64().ok())
+        .map_or(0, |values| values.len());
+    format!(
+        "{} of {} point(s)",
+        text(item, tags::GRAPHIC_TYPE).unwrap_or_default(),
+        values / dimensions
+    )
+}
+
+/// The value column of a content item, by its ValueType
+fn item_value(item: &InMemDicomObject, value_type: &str) -> String {
+    if let Some(target) = text(item, tags::REFERENCED_CONTENT_ITEM_IDENTIFIER) {
+        return format!("See item {}", target.replace('\\', "."));
+    }
+    let value = match value_type {
+        "TEXT" => text(item, tags::TEXT_VALUE),
+        "CODE" => code_meaning(item, tags::CONCEPT_CODE_SEQUENCE),
+        "NUM" => Some(numeric(item)),
+        "DATETIME" => text(item, tags::DATE_TIME),
+        "DATE" => text(item, tags::DATE),
+        "TIME" => text(item, tags::TIME),
+        "UIDREF" => text(item, tags::UID),
+        "PNAME" => text(item, tags::PERSON_NAME).map(|name| name.replace('^', " ")),
+        "IMAGE" => Some(reference(item, "Image")),
+        "COMPOSITE" => Some(reference(item, "Object")),
+        "WAVEFORM" => Some(reference(item, "Waveform")),
+        "SCOORD" => Some(coordinates(item, 2)),
+        "SCOORD3D" => Some(coordinates(item, 3)),
+        "TCOORD" => text(item, tags::TEMPORAL_RANGE_TYPE),
+        "CONTAINER" => {
+            let children = items(item, tags::CONTENT_SEQUENCE).len();
+            Some(format!("<{children} item(s)>"))
+        }
+        _ => None,
+    };
+    value.unwrap_or_default()
+}
+
+fn content_item(item: &InMemDicomObject, relationship: String, depth: usize) -> DicomTag {
+    let value_type = text(item, tags::VALUE_TYPE).unwrap_or_default();
+    let children: Vec<DicomTag> = items(item, tags::CONTENT_SEQUENCE)
+        .iter()
+        .map(|child| {
+            let relationship = text(child, tags::RELATIONSHIP_TYPE).unwrap_or_default();
+            content_item(child, relationship, depth + 1)
+        })
+        .collect();
+    DicomTag {
+        tag: relationship,
+        name: code_meaning(item, tags::CONCEPT_NAME_CODE_SEQUENCE).unwrap_or_default(),
+        value: item_value(item, &value_type),
+        vr: value_type,
+        baseline_value: None,
+        depth,
+        is_expandable: !children.is_empty(),
+        is_expanded: false,
+        children,
+        diff_status: None,
+    }
+}
+
+/// Content tree of a Structured Report as table rows: the relationship in
+/// the tag column, the concept name, the value type in the VR column and
+/// the value. `None` for objects without SR content.
+pub fn content_tree(obj: &InMemDicomObject) -> Option<Vec<DicomTag>> {
+    if text(obj, tags::VALUE_TYPE).as_deref() != Some("CONTAINER")
+        || obj.element(tags::CONTENT_SEQUENCE).is_err()
+    {
+        return None;
+    }
+    let mut root = content_item(obj, "ROOT".into(), 0);
+    root.is_expanded = root.is_expandable;
+    Some(vec![root])
+}
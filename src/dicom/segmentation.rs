@@ -0,0 +1,433 @@
+use super::attr::{items, text};
+use super::folder::FolderHeaders;
+use super::geometry::{
+    dot, frame_group, frame_of_reference, frame_planes, sub, uid, unit, ImagePlane,
+};
+use dicom::core::Tag;
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::{open_file, InMemDicomObject};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Distance in mm within which a contour or a segment frame without an
+/// image reference lies on an image
+const PLANE_TOLERANCE: f64 = 0.5;
+
+/// Colors of ROIs that recommend none, in turn
+const DEFAULT_COLORS: [[u8; 3]; 6] = [
+    [255, 64, 64],
+    [64, 255, 64],
+    [64, 128, 255],
+    [255, 255, 64],
+    [255, 64, 255],
+    [64, 255, 255],
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SegmentationKind {
+    StructureSet,
+    Segmentation,
+}
+
+impl SegmentationKind {
+    fn of(obj: &InMemDicomObject) -> Option<Self> {
+        match uid(obj, tags::SOP_CLASS_UID)?.as_str() {
+            uids::RT_STRUCTURE_SET_STORAGE => Some(Self::StructureSet),
+            uids::SEGMENTATION_STORAGE => Some(Self::Segmentation),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::StructureSet => "RTSTRUCT",
+            Self::Segmentation => "SEG",
+        }
+    }
+}
+
+/// A planar contour of a structure set ROI
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    /// Image the contour was drawn on, from the Contour Image Sequence
+    pub image_uid: Option<String>,
+    /// Patient coordinates in mm
+    pub points: Vec<[f64; 3]>,
+    /// Whether the last point joins the first
+    pub closed: bool,
+}
+
+/// One frame of a segment, covering one source image
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentMask {
+    pub image_uid: Option<String>,
+    /// Frame of the source image, 0-based, for multi-frame sources
+    pub image_frame: Option<u32>,
+    pub plane: Option<ImagePlane>,
+    pub rows: u32,
+    pub columns: u32,
+    /// One bit per pixel, row by row, least significant bit first
+    pub data: Vec<u8>,
+}
+
+impl SegmentMask {
+    pub fn is_set(&self, row: u32, column: u32) -> bool {
+        if row >= self.rows || column >= self.columns {
+            return false;
+        }
+        let bit = row as usize * self.columns as usize + column as usize;
+        self.data
+            .get(bit / 8)
+            .is_some_and(|byte| byte & (1 << (bit % 8)) != 0)
+    }
+}
+
+/// The displayed image that ROIs are matched against
+#[derive(Clone, Copy, Debug)]
+pub struct ImageTarget<'a> {
+    pub sop_instance_uid: Option<&'a str>,
+    /// Displayed frame, 0-based
+    pub frame: u32,
+    /// Geometry of the frame, when it shares the segmentation's
+    /// FrameOfReferenceUID
+    pub plane: Option<&'a ImagePlane>,
+    /// Columns and rows
+    pub size: (u32, u32),
+}
+
+fn on_plane(point: [f64; 3], plane: &ImagePlane) -> bool {
+    dot(sub(point, plane.position), unit(plane.normal())).abs() <= PLANE_TOLERANCE
+}
+
+/// A structure set ROI or a segment
+#[derive(Clone, Debug, PartialEq)]
+pub struct Roi {
+    /// ROINumber or SegmentNumber
+    pub number: u32,
+    pub name: String,
+    pub color: [u8; 3],
+    pub contours: Vec<Contour>,
+    pub masks: Vec<SegmentMask>,
+}
+
+impl Roi {
+    /// Contours on the image as (x, y) image positions, with whether each
+    /// is closed. Contours that name an image are matched by its UID,
+    /// others by lying on its plane.
+    pub fn outlines_on(&self, target: &ImageTarget) -> Vec<(Vec<(f64, f64)>, bool)> {
+        self.contours
+            .iter()
+            .filter(
+                |contour| match (&contour.image_uid, target.sop_instance_uid) {
+                    (Some(image), Some(displayed)) => image == displayed,
+                    _ => target.plane.is_some_and(|plane| {
+                        contour.points.iter().all(|&point| on_plane(point, plane))
+                    }),
+                },
+            )
+            .filter_map(|contour| {
+                let plane = target.plane?;
+                let points = contour
+                    .points
+                    .iter()
+                    .map(|&point| {
+                        let (row, column) = plane.pixel_position(point);
+                        (column + 0.5, row + 0.5)
+                    })
+                    .collect();
+                Some((points, contour.closed))
+            })
+            .collect()
+    }
+
+    /// Segment frames covering the image, matched by UID and frame, or by
+    /// position for frames that name no image
+    pub fn masks_on<'a>(
+        &'a self,
+        target: &ImageTarget<'a>,
+    ) -> impl Iterator<Item = &'a SegmentMask> + 'a {
+        let target = *target;
+        self.masks.iter().filter(move |mask| {
+            if (mask.columns, mask.rows) != target.size {
+                return false;
+            }
+            match (&mask.image_uid, target.sop_instance_uid) {
+                (Some(image), Some(displayed)) => {
+                    image == displayed && mask.image_frame.is_none_or(|f| f == target.frame)
+                }
+                _ => match (&mask.plane, target.plane) {
+                    (Some(mask_plane), Some(plane)) => {
+                        let offset = sub(mask_plane.position, plane.position);
+                        dot(offset, offset).sqrt() <= PLANE_TOLERANCE
+                            && dot(unit(mask_plane.normal()), unit(plane.normal())).abs() > 0.999
+                    }
+                    _ => false,
+                },
+            }
+        })
+    }
+
+    /// Whether anything of the ROI is drawn on the image
+    pub fn is_on(&self, target: &ImageTarget) -> bool {
+        !self.outlines_on(target).is_empty() || self.masks_on(target).next().is_some()
+    }
+}
+
+/// ROIs of an RT Structure Set, or segments of a Segmentation
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segmentation {
+    pub kind: SegmentationKind,
+    pub label: String,
+    pub frame_of_reference: Option<String>,
+    pub rois: Vec<Roi>,
+}
+
+fn int(obj: &InMemDicomObject, tag: Tag) -> Option<u32> {
+    obj.element(tag).ok()?.to_int::<u32>().ok()
+}
+
+/// sRGB of a CIELab color encoded as in PS3.3 C.10.7.1.1, converted from
+/// the D50 white point
+pub fn lab_to_rgb(lab: [u16; 3]) -> [u8; 3] {
+    let l = lab[0] as f64 * 100.0 / 65535.0;
+    let a = lab[1] as f64 * 255.0 / 65535.0 - 128.0;
+    let b = lab[2] as f64 * 255.0 / 65535.0 - 128.0;
+    let fy = (l + 16.0) / 116.0;
+    let inverse = |t: f64| {
+        const DELTA: f64 = 6.0 / 29.0;
+        match t > DELTA {
+            true => t.powi(3),
+            false => 3.0 * DELTA * DELTA * (t - 4.0 / 29.0),
+        }
+    };
+    let x = 0.96422 * inverse(fy + a / 500.0);
+    let y = inverse(fy);
+    let z = 0.82521 * inverse(fy - b / 200.0);
+    [
+        3.1338561 * x - 1.6168667 * y - 0.4906146 * z,
+        -0.9787684 * x + 1.9161415 * y + 0.0334540 * z,
+        0.0719453 * x - 0.2289914 * y + 1.4052427 * z,
+    ]
+    .map(|linear| {
+        let linear = linear.clamp(0.0, 1.0);
+        let encoded = match linear <= 0.0031308 {
+            true => 12.92 * linear,
+            false => 1.055 * linear.powf(1.0 / 2.4) - 0.055,
+        };
+        (encoded * 255.0).round() as u8
+    })
+}
+
+impl Segmentation {
+    pub fn from_obj(obj: &InMemDicomObject) -> Result<Self, String> {
+        let kind = SegmentationKind::of(obj).ok_or("Not an RT structure set or segmentation")?;
+        let (label, frame_of_reference, rois) = match kind {
+            SegmentationKind::StructureSet => (
+                text(obj, tags::STRUCTURE_SET_LABEL),
+                items(obj, tags::REFERENCED_FRAME_OF_REFERENCE_SEQUENCE)
+                    .first()
+                    .and_then(|item| uid(item, tags::FRAME_OF_REFERENCE_UID)),
+                structure_set_rois(obj),
+            ),
+            SegmentationKind::Segmentation => (
+                text(obj, tags::SERIES_DESCRIPTION).or_else(|| text(obj, tags::CONTENT_LABEL)),
+                frame_of_reference(obj),
+                segments(obj)?,
+            ),
+        };
+        Ok(Self {
+            kind,
+            label: label.unwrap_or_else(|| kind.name().to_string()),
+            frame_of_reference,
+            rois,
+        })
+    }
+
+    /// Reads a file, unless `cancelled` is set by the time it is read
+    pub fn open(path: &Path, cancelled: &AtomicBool) -> Result<Self, String> {
+        let obj = open_file(path).map_err(|e| format!("Failed to open segmentation: {e}"))?;
+        if cancelled.load(Ordering::Relaxed) {
+            return Err("Cancelled".into());
+        }
+        Self::from_obj(&obj)
+    }
+}
+
+fn structure_set_rois(obj: &InMemDicomObject) -> Vec<Roi> {
+    let contours = items(obj, tags::ROI_CONTOUR_SEQUENCE);
+    items(obj, tags::STRUCTURE_SET_ROI_SEQUENCE)
+        .iter()
+        .enumerate()
+        .filter_map(|(index, roi)| {
+            let number = int(roi, tags::ROI_NUMBER)?;
+            let contour = contours
+                .iter()
+                .find(|item| int(item, tags::REFERENCED_ROI_NUMBER) == Some(number));
+            let color = contour
+                .and_then(|item| item.element(tags::ROI_DISPLAY_COLOR).ok())
+                .and_then(|e| e.to_multi_int::<u8>().ok())
+                .and_then(|rgb| rgb.get(..3)?.try_into().ok())
+                .unwrap_or(DEFAULT_COLORS[index % DEFAULT_COLORS.len()]);
+            let contours = contour
+                .map(|item| items(item, tags::CONTOUR_SEQUENCE))
+                .unwrap_or_default()
+                .iter()
+                .map(|contour| Contour {
+                    image_uid: items(contour, tags::CONTOUR_IMAGE_SEQUENCE)
+                        .first()
+                        .and_then(|image| uid(image, tags::REFERENCED_SOP_INSTANCE_UID)),
+                    points: contour
+                        .element(tags::CONTOUR_DATA)
+                        .ok()
+                        .and_then(|e| e.to_multi_float64().ok())
+                        .unwrap_or_default()
+                        .chunks_exact(3)
+                        .map(|p| [p[0], p[1], p[2]])
+                        .collect(),
+                    closed: text(contour, tags::CONTOUR_GEOMETRIC_TYPE).as_deref()
+                        == Some("CLOSED_PLANAR"),
+                })
+                .filter(|contour| !contour.points.is_empty())
+                .collect();
+            Some(Roi {
+                number,
+                name: text(roi, tags::ROI_NAME).unwrap_or_else(|| format!("ROI {number}")),
+                color,
+                contours,
+                masks: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+fn segments(obj: &InMemDicomObject) -> Result<Vec<Roi>, String> {
+    let mut rois: Vec<Roi> = items(obj, tags::SEGMENT_SEQUENCE)
+        .iter()
+        .enumerate()
+        .filter_map(|(index, segment)| {
+            let number = int(segment, tags::SEGMENT_NUMBER)?;
+            let color = segment
+                .element(tags::RECOMMENDED_DISPLAY_CIE_LAB_VALUE)
+                .ok()
+                .and_then(|e| e.to_multi_int::<u16>().ok())
+                .and_then(|lab| Some(lab_to_rgb(lab.get(..3)?.try_into().ok()?)))
+                .unwrap_or(DEFAULT_COLORS[index % DEFAULT_COLORS.len()]);
+            Some(Roi {
+                number,
+                name: text(segment, tags::SEGMENT_LABEL)
+                    .unwrap_or_else(|| format!("Segment {number}")),
+                color,
+                contours: Vec::new(),
+                masks: Vec::new(),
+            })
+        })
+        .collect();
+
+    let (Some(rows), Some(columns)) = (int(obj, tags::ROWS), int(obj, tags::COLUMNS)) else {
+        return Ok(rois);
+    };
+    let Ok(element) = obj.element(tags::PIXEL_DATA) else {
+        return Ok(rois);
+    };
+    if element.value().fragments().is_some() {
+        return Err("Compressed segmentation pixel data is not supported".into());
+    }
+    let data = element
+        .to_bytes()
+        .map_err(|e| format!("Failed to read segmentation pixel data: {e}"))?;
+    let frames = int(obj, tags::NUMBER_OF_FRAMES).unwrap_or(1);
+    let bits = int(obj, tags::BITS_ALLOCATED).unwrap_or(1);
+    let threshold = match text(obj, tags::SEGMENTATION_TYPE).as_deref() {
+        Some("FRACTIONAL") => int(obj, tags::MAXIMUM_FRACTIONAL_VALUE)
+            .unwrap_or(255)
+            .div_ceil(2),
+        _ => 1,
+    };
+    let planes = frame_planes(obj);
+    let pixels = rows as usize * columns as usize;
+
+    for frame in 0..frames {
+        let segment = frame_group(obj, frame, tags::SEGMENT_IDENTIFICATION_SEQUENCE)
+            .and_then(|item| int(item, tags::REFERENCED_SEGMENT_NUMBER));
+        let Some(roi) = rois.iter_mut().find(|roi| Some(roi.number) == segment) else {
+            continue;
+        };
+        let source = frame_group(obj, frame, tags::DERIVATION_IMAGE_SEQUENCE)
+            .and_then(|item| items(item, tags::SOURCE_IMAGE_SEQUENCE).first());
+        let start = frame as usize * pixels;
+        let is_set = |pixel: usize| match bits {
+            1 => data
+                .get((start + pixel) / 8)
+                .is_some_and(|byte| byte & (1 << ((start + pixel) % 8)) != 0),
+            _ => data
+                .get(start + pixel)
+                .is_some_and(|&value| value as u32 >= threshold),
+        };
+        let mut mask = vec![0u8; pixels.div_ceil(8)];
+        for pixel in (0..pixels).filter(|&pixel| is_set(pixel)) {
+            mask[pixel / 8] |= 1 << (pixel % 8);
+        }
+        roi.masks.push(SegmentMask {
+            image_uid: source.and_then(|item| uid(item, tags::REFERENCED_SOP_INSTANCE_UID)),
+            image_frame: source
+                .and_then(|item| int(item, tags::REFERENCED_FRAME_NUMBER))
+                .map(|frame| frame.saturating_sub(1)),
+            plane: planes.get(frame as usize).copied(),
+            rows,
+            columns,
+            data: mask,
+        });
+    }
+    Ok(rois)
+}
+
+/// Whether an item or any item nested in it references the instance
+fn mentions(obj: &InMemDicomObject, sop_instance_uid: &str) -> bool {
+    obj.iter().any(|element| match element.items() {
+        Some(items) => items.iter().any(|item| mentions(item, sop_instance_uid)),
+        None => {
+            element.header().tag == tags::REFERENCED_SOP_INSTANCE_UID
+                && element
+                    .to_str()
+                    .is_ok_and(|uid| uid.trim_end_matches(['\0', ' ']) == sop_instance_uid)
+        }
+    })
+}
+
+/// Whether an RT structure set or segmentation references the image, or
+/// shares its FrameOfReferenceUID
+pub fn refers_to(
+    obj: &InMemDicomObject,
+    sop_instance_uid: Option<&str>,
+    frame_of_reference: Option<&str>,
+) -> bool {
+    let Some(kind) = SegmentationKind::of(obj) else {
+        return false;
+    };
+    let shared_frame = match kind {
+        SegmentationKind::StructureSet => items(obj, tags::REFERENCED_FRAME_OF_REFERENCE_SEQUENCE)
+            .iter()
+            .filter_map(|item| uid(item, tags::FRAME_OF_REFERENCE_UID))
+            .any(|uid| Some(uid.as_str()) == frame_of_reference),
+        SegmentationKind::Segmentation => {
+            frame_of_reference.is_some()
+                && uid(obj, tags::FRAME_OF_REFERENCE_UID).as_deref() == frame_of_reference
+        }
+    };
+    shared_frame || sop_instance_uid.is_some_and(|uid| mentions(obj, uid))
+}
+
+/// RT structure sets and segmentations among the headers of the image's
+/// folder that refer to it, by file name
+pub fn segmentations_for(
+    headers: &FolderHeaders,
+    image: &Path,
+    sop_instance_uid: Option<&str>,
+    frame_of_reference: Option<&str>,
+) -> Vec<PathBuf> {
+    headers
+        .others(image)
+        .filter(|(_, obj)| refers_to(obj, sop_instance_uid, frame_of_reference))
+        .map(|(path, _)| path.to_path_buf())
+        .collect()
+}
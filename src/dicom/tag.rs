@@ -22,12 +22,17 @@ pub struct DicomTag {
     pub diff_status: Option<DiffStatus>,
 }
 
+/// Private data elements are in odd groups
+pub fn is_private_group(group: u16) -> bool {
+    group % 2 == 1
+}
+
 impl DicomTag {
     pub fn is_private(&self) -> bool {
         self.tag
             .get(1..5)
             .and_then(|s| u16::from_str_radix(s, 16).ok())
-            .map(|group| group % 2 == 1)
+            .map(is_private_group)
             .unwrap_or(false)
     }
 }
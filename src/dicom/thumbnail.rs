@@ -0,0 +1,141 @@
+use super::palette::PaletteLut;
+use super::pixels::FramePixels;
+use super::voi::file_vois;
+use dicom::core::Tag;
+use dicom::dictionary_std::tags;
+use dicom::object::{open_file, InMemDicomObject};
+use dicom_pixeldata::image::{imageops, RgbImage};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Longest side of a thumbnail, in pixels
+pub const THUMBNAIL_SIZE: u32 = 64;
+
+/// Where a thumbnail was taken from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThumbnailSource {
+    /// The IconImageSequence
+    Icon,
+    /// The first frame, downsampled
+    Frame,
+}
+
+#[derive(Clone, Debug)]
+pub struct Thumbnail {
+    pub image: RgbImage,
+    pub source: ThumbnailSource,
+}
+
+fn number(obj: &InMemDicomObject, tag: Tag) -> Option<u32> {
+    obj.element(tag).ok()?.to_int::<u32>().ok()
+}
+
+/// Shrinks an image to fit in THUMBNAIL_SIZE pixels, keeping its aspect
+fn shrink(image: RgbImage) -> RgbImage {
+    let (width, height) = image.dimensions();
+    let scale = THUMBNAIL_SIZE as f64 / width.max(height).max(1) as f64;
+    if scale >= 1.0 {
+        return image;
+    }
+    let size = |side: u32| ((side as f64 * scale).round() as u32).max(1);
+    imageops::thumbnail(&image, size(width), size(height))
+}
+
+/// The uncompressed 8 or 16-bit image of an IconImageSequence item, in
+/// MONOCHROME1/2, PALETTE COLOR or RGB
+pub fn icon_image(obj: &InMemDicomObject) -> Option<RgbImage> {
+    let icon = obj
+        .element(tags::ICON_IMAGE_SEQUENCE)
+        .ok()?
+        .items()?
+        .first()?;
+    let rows = number(icon, tags::ROWS)?;
+    let columns = number(icon, tags::COLUMNS)?;
+    let bits = number(icon, tags::BITS_ALLOCATED).unwrap_or(8);
+    let samples = number(icon, tags::SAMPLES_PER_PIXEL).unwrap_or(1);
+    let photometric = icon
+        .element(tags::PHOTOMETRIC_INTERPRETATION)
+        .ok()?
+        .to_str()
+        .ok()?
+        .trim()
+        .to_string();
+    let data = icon.element(tags::PIXEL_DATA).ok()?.to_bytes().ok()?;
+    let pixels = (rows * columns) as usize;
+
+    let values: Vec<u32> = match bits {
+        8 => data.iter().map(|&b| b as u32).collect(),
+        16 => data
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]) as u32)
+            .collect(),
+        _ => return None,
+    };
+    if values.len() < pixels * samples as usize {
+        return None;
+    }
+
+    let rgb: Vec<u8> = match (photometric.as_str(), samples) {
+        ("MONOCHROME1" | "MONOCHROME2", 1) => {
+            let values = &values[..pixels];
+            let low = *values.iter().min()?;
+            let high = (*values.iter().max()?).max(low + 1);
+            values
+                .iter()
+                .flat_map(|&value| {
+                    let mut gray = ((value - low) * 255 / (high - low)) as u8;
+                    if photometric == "MONOCHROME1" {
+                        gray = 255 - gray;
+                    }
+                    [gray; 3]
+                })
+                .collect()
+        }
+        ("PALETTE COLOR", 1) => {
+            let palette = PaletteLut::from_obj(icon)?;
+            values[..pixels]
+                .iter()
+                .flat_map(|&value| palette.lookup(value as i32))
+                .collect()
+        }
+        ("RGB", 3) if bits == 8 => {
+            let planar = number(icon, tags::PLANAR_CONFIGURATION).unwrap_or(0) == 1;
+            (0..pixels * 3)
+                .map(|i| match planar {
+                    true => data[(i % 3) * pixels + i / 3],
+                    false => data[i],
+                })
+                .collect()
+        }
+        _ => return None,
+    };
+    RgbImage::from_raw(columns, rows, rgb)
+}
+
+/// Thumbnail of a file: its icon when it has a usable one, or else its
+/// first frame in the first stored window. The frame is not decoded once
+/// `cancelled` is set.
+pub fn load_thumbnail(path: &Path, cancelled: &AtomicBool) -> Result<Thumbnail, String> {
+    let obj = open_file(path).map_err(|e| format!("Failed to open DICOM file: {e}"))?;
+    if let Some(icon) = icon_image(&obj) {
+        return Ok(Thumbnail {
+            image: shrink(icon),
+            source: ThumbnailSource::Icon,
+        });
+    }
+    if cancelled.load(Ordering::Relaxed) {
+        return Err("Cancelled".into());
+    }
+
+    use dicom_pixeldata::PixelDecoder;
+    let data = obj
+        .decode_pixel_data_frame(0)
+        .map_err(|e| format!("Failed to decode pixel data: {e}"))?;
+    let pixels = FramePixels::from_decoded(&data, 0, PaletteLut::from_obj(&obj).as_ref())
+        .map_err(|e| format!("Failed to convert to image: {e}"))?;
+    let image = pixels.render(file_vois(&obj).first()).to_rgb8();
+    Ok(Thumbnail {
+        image: shrink(image),
+        source: ThumbnailSource::Frame,
+    })
+}
@@ -0,0 +1,81 @@
+use super::datetime::parse_tm;
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// What rescaled pixel values measure
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValueUnits {
+    /// Unit of the modality values, such as "HU"
+    pub unit: Option<String>,
+    /// Multiplier from modality values to body-weight SUV
+    pub suv_factor: Option<f64>,
+}
+
+impl ValueUnits {
+    pub fn from_obj(obj: &InMemDicomObject) -> Self {
+        let text = |tag| {
+            obj.element(tag)
+                .ok()
+                .and_then(|e| e.to_str().ok())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+        let unit = match text(tags::MODALITY).as_deref() {
+            Some("CT") => Some("HU".to_string()),
+            Some("PT") => text(tags::UNITS).map(|u| match u.as_str() {
+                "BQML" => "Bq/ml".to_string(),
+                _ => u,
+            }),
+            _ => text(tags::RESCALE_TYPE).filter(|t| t != "US"),
+        };
+        Self {
+            unit,
+            suv_factor: suv_factor(obj),
+        }
+    }
+}
+
+/// Body-weight SUV factor for PET images in Bq/ml that are decay corrected
+/// to the series start, from the patient weight and the injected dose
+/// decayed to that time
+pub fn suv_factor(obj: &InMemDicomObject) -> Option<f64> {
+    let text = |obj: &InMemDicomObject, tag| {
+        obj.element(tag)
+            .ok()
+            .and_then(|e| e.to_str().ok())
+            .map(|s| s.trim().to_string())
+    };
+    let number = |obj: &InMemDicomObject, tag| {
+        obj.element(tag)
+            .ok()
+            .and_then(|e| e.to_float64().ok())
+            .filter(|v| v.is_finite() && *v > 0.0)
+    };
+
+    if text(obj, tags::UNITS)? != "BQML" || text(obj, tags::DECAY_CORRECTION)? != "START" {
+        return None;
+    }
+    let weight_kg = number(obj, tags::PATIENT_WEIGHT)?;
+    let radiopharmaceutical = obj
+        .element(tags::RADIOPHARMACEUTICAL_INFORMATION_SEQUENCE)
+        .ok()?
+        .items()?
+        .first()?;
+    let dose = number(radiopharmaceutical, tags::RADIONUCLIDE_TOTAL_DOSE)?;
+    let half_life = number(radiopharmaceutical, tags::RADIONUCLIDE_HALF_LIFE)?;
+
+    let injection = parse_tm(&text(
+        radiopharmaceutical,
+        tags::RADIOPHARMACEUTICAL_START_TIME,
+    )?)?;
+    let start =
+        parse_tm(&text(obj, tags::SERIES_TIME).or_else(|| text(obj, tags::ACQUISITION_TIME))?)?;
+    let mut elapsed = (start - injection).num_milliseconds() as f64 / 1000.0;
+    if elapsed < 0.0 {
+        // The scan started after midnight
+        elapsed += 24.0 * 3600.0;
+    }
+
+    let decayed_dose = dose * (-std::f64::consts::LN_2 * elapsed / half_life).exp();
+    Some(weight_kg * 1000.0 / decayed_dose)
+}
@@ -0,0 +1,264 @@
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// Window presets for CT, as (name, center, width) in Hounsfield units
+pub const WINDOW_PRESETS: &[(&str, f64, f64)] = &[
+    ("Brain", 40.0, 80.0),
+    ("Lung", -600.0, 1500.0),
+    ("Bone", 400.0, 1800.0),
+    ("Abdomen", 40.0, 400.0),
+];
+
+/// VOI LUT Function (0028,1056) applied with a window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VoiFunction {
+    #[default]
+    Linear,
+    LinearExact,
+    Sigmoid,
+}
+
+impl VoiFunction {
+    fn from_code(code: &str) -> Self {
+        match code.trim() {
+            "LINEAR_EXACT" => Self::LinearExact,
+            "SIGMOID" => Self::Sigmoid,
+            _ => Self::Linear,
+        }
+    }
+}
+
+/// A window center and width over modality values
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Window {
+    pub center: f64,
+    pub width: f64,
+    pub function: VoiFunction,
+}
+
+impl Window {
+    pub fn new(center: f64, width: f64) -> Self {
+        Self {
+            center,
+            width,
+            function: VoiFunction::Linear,
+        }
+    }
+
+    /// Maps a modality value to the 0.0–1.0 display range, following
+    /// PS3.3 C.11.2.1.2
+    pub fn apply(&self, value: f64) -> f64 {
+        let (c, w) = (self.center, self.width);
+        match self.function {
+            VoiFunction::Linear => {
+                let span = (w - 1.0).max(f64::EPSILON);
+                if value <= c - 0.5 - span / 2.0 {
+                    0.0
+                } else if value > c - 0.5 + span / 2.0 {
+                    1.0
+                } else {
+                    (value - (c - 0.5)) / span + 0.5
+                }
+            }
+            VoiFunction::LinearExact => {
+                let w = w.max(f64::EPSILON);
+                ((value - c) / w + 0.5).clamp(0.0, 1.0)
+            }
+            VoiFunction::Sigmoid => 1.0 / (1.0 + (-4.0 * (value - c) / w.max(f64::EPSILON)).exp()),
+        }
+    }
+}
+
+/// An item of the VOI LUT Sequence (0028,3010)
+#[derive(Clone, Debug, PartialEq)]
+pub struct VoiLut {
+    /// Modality value mapped to the first entry
+    pub first_value: i32,
+    pub bits: u8,
+    pub data: Vec<u16>,
+}
+
+impl VoiLut {
+    pub fn apply(&self, value: f64) -> f64 {
+        let last = self.data.len().saturating_sub(1) as i64;
+        let index = (value.round() as i64 - self.first_value as i64).clamp(0, last) as usize;
+        let max = ((1u32 << self.bits.clamp(1, 16)) - 1) as f64;
+        (self.data.get(index).copied().unwrap_or(0) as f64 / max).clamp(0.0, 1.0)
+    }
+
+    /// The window spanning the table's input range
+    pub fn as_window(&self) -> Window {
+        let width = self.data.len() as f64;
+        Window::new(self.first_value as f64 + width / 2.0, width)
+    }
+}
+
+/// How modality values become display values
+#[derive(Clone, Debug, PartialEq)]
+pub enum VoiTransform {
+    Window(Window),
+    Lut(VoiLut),
+}
+
+impl VoiTransform {
+    pub fn apply(&self, value: f64) -> f64 {
+        match self {
+            Self::Window(window) => window.apply(value),
+            Self::Lut(lut) => lut.apply(value),
+        }
+    }
+
+    /// A window equivalent to this transform, used as the starting point
+    /// for interactive adjustment
+    pub fn as_window(&self) -> Window {
+        match self {
+            Self::Window(window) => *window,
+            Self::Lut(lut) => lut.as_window(),
+        }
+    }
+}
+
+/// A named VOI transform, as listed in the file or chosen by the user
+#[derive(Clone, Debug, PartialEq)]
+pub struct Voi {
+    pub label: String,
+    pub transform: VoiTransform,
+}
+
+impl Voi {
+    pub fn window(label: impl Into<String>, center: f64, width: f64) -> Self {
+        Self {
+            label: label.into(),
+            transform: VoiTransform::Window(Window::new(center, width)),
+        }
+    }
+
+    /// Short description for the preview title
+    pub fn describe(&self) -> String {
+        match &self.transform {
+            VoiTransform::Window(window) => format!(
+                "W:{} L:{} {}",
+                format_number(window.width),
+                format_number(window.center),
+                self.label
+            ),
+            VoiTransform::Lut(_) => format!("VOI LUT {}", self.label),
+        }
+    }
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.1}")
+    }
+}
+
+/// VOI LUTs and windows stored in the object, in that order. Enhanced
+/// objects keep their windows in the Frame VOI LUT Sequence of the shared
+/// functional groups.
+pub fn file_vois(obj: &InMemDicomObject) -> Vec<Voi> {
+    let mut vois = voi_luts(obj);
+    let mut windows = stored_windows(obj);
+    if windows.is_empty() {
+        if let Some(frame_voi) = obj
+            .element(tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE)
+            .ok()
+            .and_then(|e| e.items())
+            .and_then(|items| items.first())
+            .and_then(|shared| shared.element(tags::FRAME_VOILUT_SEQUENCE).ok())
+            .and_then(|e| e.items())
+            .and_then(|items| items.first())
+        {
+            windows = stored_windows(frame_voi);
+        }
+    }
+    vois.append(&mut windows);
+    vois
+}
+
+fn stored_windows(obj: &InMemDicomObject) -> Vec<Voi> {
+    let values = |tag| -> Vec<f64> {
+        obj.element(tag)
+            .ok()
+            .and_then(|e| e.to_multi_float64().ok())
+            .unwrap_or_default()
+    };
+    let centers = values(tags::WINDOW_CENTER);
+    let widths = values(tags::WINDOW_WIDTH);
+    let explanations: Vec<String> = obj
+        .element(tags::WINDOW_CENTER_WIDTH_EXPLANATION)
+        .ok()
+        .and_then(|e| e.to_str().ok())
+        .map(|s| s.split('\\').map(|t| t.trim().to_string()).collect())
+        .unwrap_or_default();
+    let function = obj
+        .element(tags::VOILUT_FUNCTION)
+        .ok()
+        .and_then(|e| e.to_str().ok())
+        .map(|s| VoiFunction::from_code(&s))
+        .unwrap_or_default();
+
+    centers
+        .iter()
+        .zip(&widths)
+        .enumerate()
+        .map(|(idx, (&center, &width))| Voi {
+            label: explanations
+                .get(idx)
+                .filter(|e| !e.is_empty())
+                .cloned()
+                .unwrap_or_else(|| format!("Window {}", idx + 1)),
+            transform: VoiTransform::Window(Window {
+                center,
+                width,
+                function,
+            }),
+        })
+        .collect()
+}
+
+fn voi_luts(obj: &InMemDicomObject) -> Vec<Voi> {
+    let Some(items) = obj
+        .element(tags::VOILUT_SEQUENCE)
+        .ok()
+        .and_then(|e| e.items())
+    else {
+        return Vec::new();
+    };
+
+    items
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, item)| {
+            let descriptor: Vec<i32> = item
+                .element(tags::LUT_DESCRIPTOR)
+                .ok()?
+                .to_multi_int()
+                .ok()?;
+            let [_, first_value, bits] = descriptor[..] else {
+                return None;
+            };
+            let data: Vec<u16> = item.element(tags::LUT_DATA).ok()?.to_multi_int().ok()?;
+            if data.is_empty() {
+                return None;
+            }
+            let label = item
+                .element(tags::LUT_EXPLANATION)
+                .ok()
+                .and_then(|e| e.to_str().ok())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| format!("#{}", idx + 1));
+            Some(Voi {
+                label,
+                transform: VoiTransform::Lut(VoiLut {
+                    first_value,
+                    bits: bits.clamp(1, 16) as u8,
+                    data,
+                }),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,312 @@
+use super::folder::FolderHeaders;
+use super::geometry::{dot, frame_planes, sub, uid, unit, ImagePlane};
+use super::pixels::{FramePixels, MonochromeFrame};
+use dicom::dictionary_std::tags;
+use dicom::object::open_file;
+use dicom_pixeldata::{DecodedPixelData, PixelDecoder};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Largest angle, in degrees, between the slice normal and the direction
+/// the slices are stacked in
+const MAX_TILT_DEGREES: f64 = 0.5;
+
+/// Spread of the slice spacing, relative to its mean, tolerated without a
+/// warning
+const SPACING_TOLERANCE: f64 = 0.01;
+
+/// Volume index held fixed by a reformatted view
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    Column,
+    Row,
+    Slice,
+}
+
+impl Axis {
+    /// Views in the order they are laid out: the acquired plane first
+    pub const ALL: [Axis; 3] = [Axis::Slice, Axis::Row, Axis::Column];
+
+    /// Position of the axis in a [column, row, slice] point
+    pub fn index(self) -> usize {
+        match self {
+            Self::Column => 0,
+            Self::Row => 1,
+            Self::Slice => 2,
+        }
+    }
+}
+
+/// Grayscale slices of a series stacked along their normal
+#[derive(Clone, Debug)]
+pub struct Volume {
+    pub columns: usize,
+    pub rows: usize,
+    pub slices: usize,
+    /// Modality values, column fastest, then row, then slice
+    pub values: Vec<f32>,
+    /// Distance between columns, rows and slices, in mm
+    pub spacing: [f64; 3],
+    /// Geometry of the first slice
+    pub plane: ImagePlane,
+    /// MONOCHROME1: the lowest value is displayed white
+    pub invert: bool,
+    /// Problems that leave the reformats usable but approximate
+    pub warnings: Vec<String>,
+}
+
+fn same_direction(a: [f64; 3], b: [f64; 3]) -> bool {
+    dot(unit(a), unit(b)) > 0.9999
+}
+
+impl Volume {
+    /// Sorts the slices along their normal and stacks them. Refuses slices
+    /// that differ in size or orientation, share a position or were
+    /// acquired with a gantry tilt; irregular spacing only warns.
+    pub fn from_slices(mut slices: Vec<(ImagePlane, MonochromeFrame)>) -> Result<Self, String> {
+        if slices.len() < 3 {
+            return Err(format!(
+                "MPR needs at least 3 slices, found {}",
+                slices.len()
+            ));
+        }
+        let (first_plane, first) = &slices[0];
+        let (rows, columns) = (first.rows, first.columns);
+        let orientation = (first_plane.row_direction, first_plane.column_direction);
+        if slices
+            .iter()
+            .any(|(_, f)| f.rows != rows || f.columns != columns)
+        {
+            return Err("Slices differ in size".into());
+        }
+        if slices.iter().any(|(p, _)| {
+            !same_direction(p.row_direction, orientation.0)
+                || !same_direction(p.column_direction, orientation.1)
+        }) {
+            return Err("Slices differ in orientation".into());
+        }
+
+        let normal = unit(first_plane.normal());
+        slices.sort_by(|a, b| dot(a.0.position, normal).total_cmp(&dot(b.0.position, normal)));
+        let steps: Vec<f64> = slices
+            .windows(2)
+            .map(|pair| dot(sub(pair[1].0.position, pair[0].0.position), normal))
+            .collect();
+        if steps.iter().any(|&step| step < 1e-3) {
+            return Err("Several slices share a position".into());
+        }
+
+        let (first_plane, last_plane) = (slices[0].0, slices[slices.len() - 1].0);
+        let span = sub(last_plane.position, first_plane.position);
+        let depth = dot(span, normal);
+        let tilt = (depth / dot(span, span).sqrt())
+            .clamp(-1.0, 1.0)
+            .acos()
+            .to_degrees();
+        if tilt > MAX_TILT_DEGREES {
+            return Err(format!(
+                "Gantry tilt of {tilt:.1}°: reformats would be sheared"
+            ));
+        }
+
+        let mean = depth / steps.len() as f64;
+        let (min, max) = steps
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(lo, hi), &s| (lo.min(s), hi.max(s)));
+        let mut warnings = Vec::new();
+        if max - min > SPACING_TOLERANCE * mean {
+            warnings.push(format!(
+                "Irregular slice spacing ({min:.2}–{max:.2} mm): reformats assume {mean:.2} mm"
+            ));
+        }
+
+        let invert = slices[0].1.invert;
+        let count = slices.len();
+        let values = slices
+            .into_iter()
+            .flat_map(|(_, frame)| frame.values)
+            .collect();
+        Ok(Self {
+            columns: columns as usize,
+            rows: rows as usize,
+            slices: count,
+            values,
+            spacing: [first_plane.spacing[1], first_plane.spacing[0], mean],
+            plane: first_plane,
+            invert,
+            warnings,
+        })
+    }
+
+    /// Number of views along an axis
+    pub fn count(&self, axis: Axis) -> usize {
+        [self.columns, self.rows, self.slices][axis.index()]
+    }
+
+    /// Anatomical name of the view, from the patient axis closest to the
+    /// direction it is held fixed along
+    pub fn name(&self, axis: Axis) -> &'static str {
+        let direction = match axis {
+            Axis::Column => self.plane.row_direction,
+            Axis::Row => self.plane.column_direction,
+            Axis::Slice => self.plane.normal(),
+        };
+        let dominant = (0..3)
+            .max_by(|&a, &b| direction[a].abs().total_cmp(&direction[b].abs()))
+            .unwrap_or(2);
+        ["Sagittal", "Coronal", "Axial"][dominant]
+    }
+
+    /// Modality value of a voxel, given as [column, row, slice]
+    pub fn value(&self, point: [usize; 3]) -> Option<f64> {
+        let [x, y, z] = point;
+        if x >= self.columns || y >= self.rows || z >= self.slices {
+            return None;
+        }
+        let index = (z * self.rows + y) * self.columns + x;
+        self.values.get(index).map(|&v| v as f64)
+    }
+
+    /// Patient coordinate of a voxel center, in mm
+    pub fn patient_point(&self, point: [usize; 3]) -> [f64; 3] {
+        let [x, y, z] = point;
+        let normal = unit(self.plane.normal());
+        let in_plane = self.plane.patient_point(y as f64, x as f64);
+        std::array::from_fn(|axis| in_plane[axis] + normal[axis] * self.spacing[2] * z as f64)
+    }
+
+    /// Slice closest to a patient position
+    pub fn nearest_slice(&self, position: [f64; 3]) -> usize {
+        let normal = unit(self.plane.normal());
+        let depth = dot(sub(position, self.plane.position), normal) / self.spacing[2];
+        depth.round().clamp(0.0, (self.slices - 1) as f64) as usize
+    }
+
+    /// Image rows of a reformat across the slices, stretched so that its
+    /// pixels are square
+    fn stacked_rows(&self, axis: Axis) -> usize {
+        let in_plane = match axis {
+            Axis::Row => self.spacing[0],
+            _ => self.spacing[1],
+        };
+        ((self.slices as f64 * self.spacing[2] / in_plane).round() as usize).max(1)
+    }
+
+    /// Slice shown on a row of a reformat across the slices. The last slice
+    /// is drawn at the top.
+    fn row_slice(&self, axis: Axis, row: usize) -> usize {
+        let slice = row * self.slices / self.stacked_rows(axis);
+        self.slices - 1 - slice.min(self.slices - 1)
+    }
+
+    /// Size of a view as (width, height)
+    pub fn view_size(&self, axis: Axis) -> (usize, usize) {
+        match axis {
+            Axis::Slice => (self.columns, self.rows),
+            Axis::Row => (self.columns, self.stacked_rows(axis)),
+            Axis::Column => (self.rows, self.stacked_rows(axis)),
+        }
+    }
+
+    /// The plane through `point` that holds `axis` fixed
+    pub fn reformat(&self, axis: Axis, point: [usize; 3]) -> MonochromeFrame {
+        let (width, height) = self.view_size(axis);
+        let values = (0..height)
+            .flat_map(|row| (0..width).map(move |column| (row, column)))
+            .map(|(row, column)| {
+                let voxel = self.view_voxel(axis, point, (row, column));
+                self.value(voxel).unwrap_or_default() as f32
+            })
+            .collect();
+        MonochromeFrame {
+            rows: height as u32,
+            columns: width as u32,
+            values,
+            slope: 1.0,
+            intercept: 0.0,
+            invert: self.invert,
+        }
+    }
+
+    /// Voxel under a view pixel given as (row, column), keeping `point`'s
+    /// index along `axis`
+    pub fn view_voxel(&self, axis: Axis, point: [usize; 3], pixel: (usize, usize)) -> [usize; 3] {
+        let (row, column) = pixel;
+        match axis {
+            Axis::Slice => [column, row, point[2]],
+            Axis::Row => [column, point[1], self.row_slice(axis, row)],
+            Axis::Column => [point[0], column, self.row_slice(axis, row)],
+        }
+    }
+
+    /// Position of a voxel's center in a view, as (x, y) in view pixels
+    pub fn view_position(&self, axis: Axis, point: [usize; 3]) -> (f64, f64) {
+        let [x, y, z] = point;
+        let stacked = |axis| {
+            let scale = self.stacked_rows(axis) as f64 / self.slices as f64;
+            (self.slices - 1 - z) as f64 * scale + scale / 2.0
+        };
+        match axis {
+            Axis::Slice => (x as f64 + 0.5, y as f64 + 0.5),
+            Axis::Row => (x as f64 + 0.5, stacked(axis)),
+            Axis::Column => (y as f64 + 0.5, stacked(axis)),
+        }
+    }
+}
+
+fn monochrome(data: &DecodedPixelData<'_>, frame: u32) -> Result<MonochromeFrame, String> {
+    match FramePixels::from_decoded(data, frame, None) {
+        Ok(FramePixels::Monochrome(frame)) => Ok(frame),
+        Ok(FramePixels::Color(_)) => Err("MPR needs grayscale images".into()),
+        Err(e) => Err(format!("Failed to decode pixel data: {e}")),
+    }
+}
+
+/// Builds the volume a file belongs to: the frames of a multi-frame file
+/// with one plane per frame, otherwise the slices of its series picked from
+/// the headers of its folder. Only the slices are read in full, and the
+/// reading stops once `cancelled` is set.
+pub fn load_volume(
+    path: &Path,
+    headers: &FolderHeaders,
+    cancelled: &AtomicBool,
+) -> Result<Volume, String> {
+    let header = headers
+        .get(path)
+        .ok_or_else(|| format!("{} is not a DICOM file", path.display()))?;
+    let planes = frame_planes(header);
+    if planes.len() > 1 {
+        let obj = open_file(path).map_err(|e| format!("Failed to open DICOM file: {e}"))?;
+        let data = obj
+            .decode_pixel_data()
+            .map_err(|e| format!("Failed to decode pixel data: {e}"))?;
+        let slices = planes
+            .iter()
+            .zip(0..)
+            .map(|(plane, frame)| Ok((*plane, monochrome(&data, frame)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        return Volume::from_slices(slices);
+    }
+
+    let series = uid(header, tags::SERIES_INSTANCE_UID)
+        .ok_or("No SeriesInstanceUID to find the other slices by")?;
+    let mut slices = Vec::new();
+    for (file, other) in headers.iter() {
+        if uid(other, tags::SERIES_INSTANCE_UID).as_deref() != Some(series.as_str()) {
+            continue;
+        }
+        if cancelled.load(Ordering::Relaxed) {
+            return Err("Cancelled".into());
+        }
+        let name = file.file_name().unwrap_or_default().to_string_lossy();
+        let plane = *frame_planes(other)
+            .first()
+            .ok_or_else(|| format!("{name} has no image position or orientation"))?;
+        let slice = open_file(file).map_err(|e| format!("Failed to open {name}: {e}"))?;
+        let data = slice
+            .decode_pixel_data()
+            .map_err(|e| format!("Failed to decode {name}: {e}"))?;
+        slices.push((plane, monochrome(&data, 0)?));
+    }
+    Volume::from_slices(slices)
+}
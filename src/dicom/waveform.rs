@@ -0,0 +1,213 @@
+use super::attr::{float, items, text};
+use dicom::core::Tag;
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// One channel of a multiplex group, in its sensitivity units
+#[derive(Clone, Debug, PartialEq)]
+pub struct WaveformChannel {
+    pub label: String,
+    pub units: Option<String>,
+    pub samples: Vec<f64>,
+}
+
+/// One item of the WaveformSequence: channels sampled together
+#[derive(Clone, Debug, PartialEq)]
+pub struct Waveform {
+    pub label: Option<String>,
+    /// Samples per second
+    pub sampling_frequency: f64,
+    pub channels: Vec<WaveformChannel>,
+}
+
+impl Waveform {
+    pub fn sample_count(&self) -> usize {
+        self.channels.first().map_or(0, |c| c.samples.len())
+    }
+
+    /// Length of the recording in seconds
+    pub fn duration(&self) -> f64 {
+        self.sample_count() as f64 / self.sampling_frequency
+    }
+
+    /// (seconds, value) points of a channel between `start` and `end`
+    /// seconds. Long stretches keep the lowest and highest sample of each
+    /// of `width` buckets so that peaks survive.
+    pub fn points(&self, channel: usize, start: f64, end: f64, width: usize) -> Vec<(f64, f64)> {
+        let Some(channel) = self.channels.get(channel) else {
+            return Vec::new();
+        };
+        let frequency = self.sampling_frequency;
+        let first = ((start * frequency).floor().max(0.0) as usize).min(channel.samples.len());
+        let last = ((end * frequency).ceil().max(0.0) as usize + 1).min(channel.samples.len());
+        let samples = &channel.samples[first..last.max(first)];
+        let time = |index: usize| (first + index) as f64 / frequency;
+        let width = width.max(1);
+        if samples.len() <= width * 2 {
+            return samples
+                .iter()
+                .enumerate()
+                .map(|(index, &value)| (time(index), value))
+                .collect();
+        }
+        let bucket = samples.len().div_ceil(width);
+        let mut points = Vec::with_capacity(width * 2);
+        for (index, chunk) in samples.chunks(bucket).enumerate() {
+            let offset = index * bucket;
+            let (mut low, mut high) = (0, 0);
+            for (i, &value) in chunk.iter().enumerate() {
+                if value < chunk[low] {
+                    low = i;
+                }
+                if value > chunk[high] {
+                    high = i;
+                }
+            }
+            let (a, b) = (low.min(high), low.max(high));
+            points.push((time(offset + a), chunk[a]));
+            if b != a {
+                points.push((time(offset + b), chunk[b]));
+            }
+        }
+        points
+    }
+}
+
+fn count(obj: &InMemDicomObject, tag: Tag) -> Result<usize, String> {
+    obj.element(tag)
+        .ok()
+        .and_then(|e| e.to_int::<usize>().ok())
+        .ok_or_else(|| format!("Waveform is missing {tag}"))
+}
+
+/// G.711 µ-law sample to linear
+fn mu_law(byte: u8) -> f64 {
+    let byte = !byte;
+    let magnitude = ((((byte & 0x0F) as i32) << 3) + 0x84) << ((byte >> 4) & 0x07);
+    let value = magnitude - 0x84;
+    match byte & 0x80 {
+        0 => value as f64,
+        _ => -value as f64,
+    }
+}
+
+/// G.711 A-law sample to linear
+fn a_law(byte: u8) -> f64 {
+    let byte = byte ^ 0x55;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = ((byte & 0x0F) as i32) << 4;
+    let magnitude = match exponent {
+        0 => mantissa + 8,
+        _ => (mantissa + 0x108) << (exponent - 1),
+    };
+    match byte & 0x80 {
+        0 => -magnitude as f64,
+        _ => magnitude as f64,
+    }
+}
+
+/// Raw samples of WaveformData, channels interleaved
+fn raw_samples(data: &[u8], bits: usize, interpretation: &str) -> Result<Vec<f64>, String> {
+    let samples = match (bits, interpretation) {
+        (8, "SB") => data.iter().map(|&b| b as i8 as f64).collect(),
+        (8, "UB") => data.iter().map(|&b| b as f64).collect(),
+        (8, "MB") => data.iter().map(|&b| mu_law(b)).collect(),
+        (8, "AB") => data.iter().map(|&b| a_law(b)).collect(),
+        (16, "SS") => data
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f64)
+            .collect(),
+        (16, "US") => data
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]) as f64)
+            .collect(),
+        (32, "SL") => data
+            .chunks_exact(4)
+            .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)
+            .collect(),
+        (32, "UL") => data
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)
+            .collect(),
+        _ => {
+            return Err(format!(
+                "Unsupported waveform samples: {bits} bits {interpretation}"
+            ))
+        }
+    };
+    Ok(samples)
+}
+
+/// Name of a channel: its label, or the meaning of its source code
+fn channel_label(definition: &InMemDicomObject, index: usize) -> String {
+    text(definition, tags::CHANNEL_LABEL)
+        .or_else(|| {
+            text(
+                items(definition, tags::CHANNEL_SOURCE_SEQUENCE).first()?,
+                tags::CODE_MEANING,
+            )
+        })
+        .unwrap_or_else(|| format!("Channel {}", index + 1))
+}
+
+fn waveform(group: &InMemDicomObject) -> Result<Waveform, String> {
+    let channel_count = count(group, tags::NUMBER_OF_WAVEFORM_CHANNELS)?;
+    let sample_count = count(group, tags::NUMBER_OF_WAVEFORM_SAMPLES)?;
+    let sampling_frequency = float(group, tags::SAMPLING_FREQUENCY)
+        .filter(|f| *f > 0.0)
+        .ok_or("Waveform is missing its sampling frequency")?;
+    let bits = count(group, tags::WAVEFORM_BITS_ALLOCATED)?;
+    let interpretation = text(group, tags::WAVEFORM_SAMPLE_INTERPRETATION).unwrap_or_default();
+    let data = group
+        .element(tags::WAVEFORM_DATA)
+        .map_err(|_| "Waveform has no WaveformData")?
+        .to_bytes()
+        .map_err(|e| format!("Failed to read WaveformData: {e}"))?;
+    let raw = raw_samples(&data, bits, &interpretation)?;
+    if raw.len() < channel_count * sample_count {
+        return Err(format!(
+            "WaveformData holds {} samples, {channel_count} channels of {sample_count} expected",
+            raw.len()
+        ));
+    }
+
+    let definitions = items(group, tags::CHANNEL_DEFINITION_SEQUENCE);
+    let channels = (0..channel_count)
+        .map(|index| {
+            let definition = definitions.get(index);
+            let value = |tag| definition.and_then(|d| float(d, tag));
+            let scale = value(tags::CHANNEL_SENSITIVITY).unwrap_or(1.0)
+                * value(tags::CHANNEL_SENSITIVITY_CORRECTION_FACTOR).unwrap_or(1.0);
+            let baseline = value(tags::CHANNEL_BASELINE).unwrap_or(0.0);
+            WaveformChannel {
+                label: definition.map_or_else(
+                    || format!("Channel {}", index + 1),
+                    |d| channel_label(d, index),
+                ),
+                units: definition.and_then(|d| {
+                    text(
+                        items(d, tags::CHANNEL_SENSITIVITY_UNITS_SEQUENCE).first()?,
+                        tags::CODE_VALUE,
+                    )
+                }),
+                samples: (0..sample_count)
+                    .map(|sample| raw[sample * channel_count + index] * scale + baseline)
+                    .collect(),
+            }
+        })
+        .collect();
+    Ok(Waveform {
+        label: text(group, tags::MULTIPLEX_GROUP_LABEL),
+        sampling_frequency,
+        channels,
+    })
+}
+
+/// Multiplex groups of the WaveformSequence, decoded with their channel
+/// definitions. Empty for objects without waveforms.
+pub fn waveforms(obj: &InMemDicomObject) -> Result<Vec<Waveform>, String> {
+    items(obj, tags::WAVEFORM_SEQUENCE)
+        .iter()
+        .map(waveform)
+        .collect()
+}
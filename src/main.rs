@@ -4,20 +4,24 @@ mod ui;
 mod validation;
 
 use app::App;
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
-use ratatui_image::picker::Picker;
-use std::{io, path::Path, path::PathBuf};
+use ratatui_image::picker::{Picker, ProtocolType};
+use std::{io, path::Path, path::PathBuf, sync::atomic::AtomicBool};
 
 /// DICOM TUI Viewer - View DICOM file tags in a terminal interface
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Compare two DICOM files (baseline vs modified)
     #[arg(short = 'd', long, value_names = ["BASELINE", "MODIFIED"], num_args = 2)]
     diff: Option<Vec<PathBuf>>,
@@ -25,6 +29,110 @@ struct Args {
     /// Path to the DICOM file to view (opens file explorer if omitted)
     #[arg(value_name = "FILE")]
     file: Option<PathBuf>,
+
+    /// Grayscale Softcopy Presentation State to apply to the preview of FILE
+    #[arg(long, value_name = "GSPS", requires = "file")]
+    gsps: Option<PathBuf>,
+
+    /// RT Structure Set or Segmentation to draw over the images of FILE's series
+    #[arg(long, value_name = "RTSTRUCT|SEG", requires = "file")]
+    seg: Option<PathBuf>,
+
+    /// Graphics protocol for the image preview
+    #[arg(long, value_enum, default_value_t = PreviewProtocol::Auto)]
+    protocol: PreviewProtocol,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Render frames to PNG, JPEG or an animated GIF without opening the viewer
+    ExportImage(ExportImageArgs),
+    /// Write the CT dose of a study as CSV, from a dose report or the CT
+    /// images next to the file
+    Dose(DoseArgs),
+    /// Save the PDF, CDA or other document of an encapsulated document object
+    ExtractDocument(ExtractDocumentArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct ExtractDocumentArgs {
+    /// Encapsulated document object
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// File to write (default: the DICOM file's name with the document's
+    /// extension, in the current directory)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+struct DoseArgs {
+    /// Radiation dose SR or CT image of the study
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// CSV file to write (default: standard output)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+struct ExportImageArgs {
+    /// DICOM file to render
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// Image to write; the extension picks PNG, JPEG or GIF
+    #[arg(short, long)]
+    output: PathBuf,
+
+    /// 1-based frame or range such as 2-10 (default: every frame for a GIF,
+    /// the first frame otherwise)
+    #[arg(short, long)]
+    frames: Option<String>,
+
+    /// Window center, used with --window-width
+    #[arg(long, requires = "window_width", allow_hyphen_values = true)]
+    window_center: Option<f64>,
+
+    /// Window width, used with --window-center
+    #[arg(long, requires = "window_center")]
+    window_width: Option<f64>,
+
+    /// Window preset: brain, lung, bone or abdomen
+    #[arg(long, conflicts_with = "window_center")]
+    preset: Option<String>,
+
+    /// Leave out overlay planes
+    #[arg(long)]
+    no_overlays: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum PreviewProtocol {
+    /// Use whatever the terminal reports, falling back to half-blocks
+    Auto,
+    Kitty,
+    Sixel,
+    Iterm2,
+    Halfblocks,
+}
+
+/// Terminal cell size assumed when the terminal does not report its font size
+const FALLBACK_FONT_SIZE: (u16, u16) = (8, 16);
+
+fn create_picker(protocol: PreviewProtocol) -> Picker {
+    let mut picker =
+        Picker::from_query_stdio().unwrap_or_else(|_| Picker::from_fontsize(FALLBACK_FONT_SIZE));
+    match protocol {
+        PreviewProtocol::Auto => {}
+        PreviewProtocol::Kitty => picker.set_protocol_type(ProtocolType::Kitty),
+        PreviewProtocol::Sixel => picker.set_protocol_type(ProtocolType::Sixel),
+        PreviewProtocol::Iterm2 => picker.set_protocol_type(ProtocolType::Iterm2),
+        PreviewProtocol::Halfblocks => picker.set_protocol_type(ProtocolType::Halfblocks),
+    }
+    picker
 }
 
 fn validate_path(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
@@ -43,10 +151,73 @@ fn validate_path(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+fn export_image(args: &ExportImageArgs) -> Result<(), Box<dyn std::error::Error>> {
+    validate_path(&args.file)?;
+    let voi = match (&args.preset, args.window_center, args.window_width) {
+        (Some(preset), _, _) => {
+            let &(name, center, width) = dicom::WINDOW_PRESETS
+                .iter()
+                .find(|(name, _, _)| name.eq_ignore_ascii_case(preset))
+                .ok_or_else(|| format!("unknown preset: {preset}"))?;
+            Some(dicom::Voi::window(name, center, width))
+        }
+        (None, Some(center), Some(width)) => Some(dicom::Voi::window("Custom", center, width)),
+        _ => None,
+    };
+    let gif = args
+        .output
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gif"));
+    let options = dicom::ExportOptions {
+        frames: args.frames.clone().or_else(|| (!gif).then(|| "1".to_string())),
+        voi,
+        hide_overlays: args.no_overlays,
+    };
+    let count = dicom::export_image(&args.file, &args.output, &options)?;
+    println!(
+        "Wrote {count} frame{} to {}",
+        if count == 1 { "" } else { "s" },
+        args.output.display()
+    );
+    Ok(())
+}
+
+fn dose(args: &DoseArgs) -> Result<(), Box<dyn std::error::Error>> {
+    validate_path(&args.file)?;
+    let csv = dicom::dose_summary(&args.file, &AtomicBool::new(false))?.csv();
+    match &args.output {
+        Some(output) => {
+            std::fs::write(output, csv)?;
+            println!("Wrote {}", output.display());
+        }
+        None => print!("{csv}"),
+    }
+    Ok(())
+}
+
+fn extract_document(args: &ExtractDocumentArgs) -> Result<(), Box<dyn std::error::Error>> {
+    validate_path(&args.file)?;
+    let document = dicom::EncapsulatedDocument::open(&args.file)?;
+    let output = args
+        .output
+        .clone()
+        .unwrap_or_else(|| document.file_name(&args.file, Path::new(".")));
+    document.save(&output)?;
+    println!("Wrote {} ({} bytes)", output.display(), document.data.len());
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
 
-    let picker = Picker::from_query_stdio().ok();
+    match &args.command {
+        Some(Command::ExportImage(export)) => return export_image(export),
+        Some(Command::Dose(dose_args)) => return dose(dose_args),
+        Some(Command::ExtractDocument(extract)) => return extract_document(extract),
+        None => {}
+    }
+
+    let picker = Some(create_picker(args.protocol));
 
     let mut app = if let Some(diff_files) = &args.diff {
         if diff_files.len() != 2 {
@@ -58,6 +229,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         validate_path(modified_path)?;
 
         let tags = dicom::compare_dicom_files(baseline_path, modified_path)?;
+        let obj = ::dicom::object::open_file(baseline_path)?;
 
         let baseline_name = baseline_path
             .file_name()
@@ -68,43 +240,44 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .map(|s| s.to_string_lossy().to_string())
             .unwrap_or_else(|| modified_path.to_string_lossy().to_string());
 
-        let sop_class =
-            validation::get_sop_class(baseline_path).unwrap_or(validation::SopClass::Unknown);
-        let validation_result = validation::validate_type1_fields(baseline_path)
-            .unwrap_or(validation::ValidationResult::NotApplicable);
-
-        App::new_with_diff(
+        let mut app = App::new_with_diff(
             tags,
             baseline_name,
             Some(modified_name),
-            validation_result,
-            sop_class,
             true,
             Some(baseline_path.clone()),
             picker,
-        )
+        );
+        app.meta.apply_obj(&obj);
+        app
     } else if let Some(file) = args.file {
         validate_path(&file)?;
         let obj = ::dicom::object::open_file(&file)?;
         let tags = dicom::extract_tags(&obj);
-        let sop_class = validation::get_sop_class_from_obj(&obj);
-        let validation_result = validation::validate_type1_fields_from_obj(&obj);
 
         let file_name = file
             .file_name()
             .map(|s| s.to_string_lossy().to_string())
             .unwrap_or_else(|| file.to_string_lossy().to_string());
 
-        App::new_with_diff(
+        let mut app = App::new_with_diff(
             tags,
             file_name,
             None,
-            validation_result,
-            sop_class,
             false,
             Some(file),
             picker,
-        )
+        );
+        app.meta.apply_obj(&obj);
+        if let Some(gsps) = args.gsps {
+            validate_path(&gsps)?;
+            app.preview.presentations.files.push(gsps);
+        }
+        if let Some(seg) = args.seg {
+            validate_path(&seg)?;
+            app.preview.open_segmentation(&seg);
+        }
+        app
     } else {
         App::new_explorer(picker)
     };
@@ -1,18 +1,24 @@
 use crate::app::{AppMode, Focus};
 use crate::app::App;
-use crate::dicom::{parse_dicom_datetime_delta_ms, DiffStatus};
-use crate::validation::{SopClass, ValidationResult};
+use crate::dicom::{parse_dicom_datetime_delta_ms, DiffStatus, DoseSource, ThumbnailSource};
+use crate::validation::{FindingKind, Severity, SopClass, ValidationResult};
+use dicom_pixeldata::image::{imageops, RgbImage};
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
+    symbols,
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    widgets::{Axis, Block, Borders, Cell, Chart, Dataset, GraphType, Paragraph, Row, Table, Wrap},
     Frame,
 };
 use ratatui_image::StatefulImage;
 use similar::{ChangeTag, TextDiff};
 
 pub fn render(frame: &mut Frame, app: &mut App) {
+    if app.preview.show && app.preview.fullscreen && app.has_dicom_loaded() {
+        render_preview_pane(frame, frame.area(), app);
+        return;
+    }
     match app.layout.mode {
         AppMode::Direct => render_direct(frame, app),
         AppMode::Explorer => render_explorer(frame, app),
@@ -32,27 +38,34 @@ fn render_direct(frame: &mut Frame, app: &mut App) {
         (full_area, None)
     };
 
-    let validation_height = if matches!(&app.meta.validation_result, ValidationResult::Invalid(_)) {
-        4
-    } else {
-        3
-    };
+    let validation_height = validation_pane_height(app);
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(5), Constraint::Length(validation_height)])
+        .constraints([
+            Constraint::Min(5),
+            Constraint::Length(dose_pane_height(app)),
+            Constraint::Length(validation_height),
+        ])
         .split(main_area);
 
     let area = chunks[0];
-    let validation_area = chunks[1];
+    let validation_area = chunks[2];
 
+    render_dose_pane(frame, chunks[1], app);
     render_validation_pane(frame, validation_area, app);
 
     if let Some(preview_area) = preview_area {
         render_preview_pane(frame, preview_area, app);
     }
 
-    render_tag_table(frame, area, app, false);
+    if app.document.is_some() {
+        render_document(frame, area, app);
+    } else if app.waveform.is_some() {
+        render_waveform(frame, area, app);
+    } else {
+        render_tag_table(frame, area, app, false);
+    }
     render_direct_help(frame, area, app);
 }
 
@@ -78,6 +91,15 @@ fn render_explorer(frame: &mut Frame, app: &mut App) {
     } else {
         (left_area, None)
     };
+    let (explorer_area, thumbnail_area) = if has_dicom && preview_area.is_none() {
+        let left_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Fill(1), Constraint::Length(THUMBNAIL_HEIGHT)])
+            .split(explorer_area);
+        (left_chunks[0], Some(left_chunks[1]))
+    } else {
+        (explorer_area, None)
+    };
 
     app.layout.explorer_area = explorer_area;
 
@@ -97,23 +119,33 @@ fn render_explorer(frame: &mut Frame, app: &mut App) {
         frame.render_widget_ref(explorer.widget(), inner);
     }
 
+    if let Some(thumbnail_area) = thumbnail_area {
+        render_thumbnail_pane(frame, thumbnail_area, app);
+    }
+
     if has_dicom {
         let tags_area = columns[1];
 
-        let validation_height =
-            if matches!(&app.meta.validation_result, ValidationResult::Invalid(_)) {
-                4
-            } else {
-                3
-            };
+        let validation_height = validation_pane_height(app);
 
         let v_chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(5), Constraint::Length(validation_height)])
+            .constraints([
+                Constraint::Min(5),
+                Constraint::Length(dose_pane_height(app)),
+                Constraint::Length(validation_height),
+            ])
             .split(tags_area);
 
-        render_tag_table(frame, v_chunks[0], app, true);
-        render_validation_pane(frame, v_chunks[1], app);
+        if app.document.is_some() {
+            render_document(frame, v_chunks[0], app);
+        } else if app.waveform.is_some() {
+            render_waveform(frame, v_chunks[0], app);
+        } else {
+            render_tag_table(frame, v_chunks[0], app, true);
+        }
+        render_dose_pane(frame, v_chunks[1], app);
+        render_validation_pane(frame, v_chunks[2], app);
 
         if let Some(preview_area) = preview_area {
             render_preview_pane(frame, preview_area, app);
@@ -146,23 +178,27 @@ fn render_tag_table(frame: &mut Frame, area: Rect, app: &mut App, in_explorer: b
             ),
         );
     }
+    let labels = match (&app.tag_view, app.frame_groups) {
+        (Some(_), None) => ["  Relationship", "Concept", "Type", "Value"],
+        _ => ["  Tag", "Name", "VR", "Value"],
+    };
     header_cells.extend(vec![
-        Cell::from("  Tag").style(
+        Cell::from(labels[0]).style(
             Style::default()
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
         ),
-        Cell::from("Name").style(
+        Cell::from(labels[1]).style(
             Style::default()
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
         ),
-        Cell::from("VR").style(
+        Cell::from(labels[2]).style(
             Style::default()
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
         ),
-        Cell::from("Value").style(
+        Cell::from(labels[3]).style(
             Style::default()
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
@@ -170,11 +206,40 @@ fn render_tag_table(frame: &mut Frame, area: Rect, app: &mut App, in_explorer: b
     ]);
     let header = Row::new(header_cells).height(1);
 
+    let finding_locations: Vec<(&[String], Severity)> = app
+        .meta
+        .findings
+        .iter()
+        .filter(|f| !f.location.is_empty())
+        .map(|f| (f.location.as_slice(), f.severity()))
+        .collect();
+    let flagged: Vec<Option<Severity>> = if finding_locations.is_empty() {
+        vec![None; app.tags.visible.len()]
+    } else {
+        app.tags
+            .visible_locations()
+            .iter()
+            .map(|row| {
+                let mut severities = finding_locations
+                    .iter()
+                    .filter(|(l, _)| l.starts_with(row))
+                    .map(|(_, severity)| *severity);
+                let first = severities.next()?;
+                if first == Severity::Error || severities.any(|s| s == Severity::Error) {
+                    Some(Severity::Error)
+                } else {
+                    Some(Severity::Warning)
+                }
+            })
+            .collect()
+    };
+
     let rows: Vec<Row> = app
         .tags
         .visible
         .iter()
-        .map(|tag| {
+        .zip(flagged)
+        .map(|(tag, flagged)| {
             let indent = "  ".repeat(tag.depth);
             let expand_indicator = if tag.is_expandable {
                 if tag.is_expanded {
@@ -249,8 +314,14 @@ fn render_tag_table(frame: &mut Frame, area: Rect, app: &mut App, in_explorer: b
                 row_cells.push(Cell::from(indicator).style(indicator_style));
             }
 
+            let tag_style = match flagged {
+                Some(Severity::Error) => row_style.fg(Color::Red),
+                Some(Severity::Warning) => row_style.fg(Color::Yellow),
+                None => row_style,
+            };
+
             row_cells.extend(vec![
-                Cell::from(tag_display).style(row_style),
+                Cell::from(tag_display).style(tag_style),
                 Cell::from(tag.name.as_str()).style(row_style),
                 Cell::from(tag.vr.as_str()).style(row_style),
                 value_cell,
@@ -268,6 +339,13 @@ fn render_tag_table(frame: &mut Frame, area: Rect, app: &mut App, in_explorer: b
             Constraint::Length(4),
             Constraint::Fill(1),
         ]
+    } else if app.tag_view.is_some() {
+        vec![
+            Constraint::Length(28),
+            Constraint::Length(36),
+            Constraint::Length(9),
+            Constraint::Fill(1),
+        ]
     } else {
         vec![
             Constraint::Length(16),
@@ -283,6 +361,17 @@ fn render_tag_table(frame: &mut Frame, area: Rect, app: &mut App, in_explorer: b
         } else {
             format!(" DICOM Diff: {} ", app.meta.name)
         }
+    } else if let (Some(frame), Some(groups)) = (app.frame_groups, &app.meta.functional_groups) {
+        format!(
+            " Frame {}/{} functional groups: {} ",
+            frame + 1,
+            groups.frame_count(),
+            app.meta.name
+        )
+    } else if app.tag_view.is_some() {
+        format!(" SR Content: {} ", app.meta.name)
+    } else if app.tags.only.is_some() {
+        format!(" DICOM Viewer: {} [not in IOD] ", app.meta.name)
     } else {
         format!(" DICOM Viewer: {} ", app.meta.name)
     };
@@ -348,7 +437,7 @@ fn render_direct_help(frame: &mut Frame, area: Rect, app: &App) {
         let search = Paragraph::new(search_text).style(Style::default().fg(Color::Yellow));
         frame.render_widget(search, help_area);
     } else {
-        let help_text = " ↑/↓: Navigate | →: Expand | ←: Collapse | /: Search | p: Preview | q/Esc: Quit ";
+        let help_text = " ↑/↓: Navigate | →: Expand | ←: Collapse | /: Search | n: Next issue | u: Not in IOD | C: SR tree | F: Frame groups | D: Dose | W: Waveform | O/x: Document | p: Preview | w/1-4/[]{}: W/L | ,/.: Frame | c: Cine | i: Inspect | m: Measure | M/v: MPR | R: Reference lines | P: GSPS | S/s/Space: Structures | o/t: Overlays/Text | g: Histogram | +/-/HJKL: Zoom/Pan | f: Full screen | x/X: Export | q/Esc: Quit ";
         let help = Paragraph::new(help_text).style(Style::default().fg(Color::Cyan));
         frame.render_widget(help, help_area);
     }
@@ -376,7 +465,7 @@ fn render_explorer_help(frame: &mut Frame, area: Rect, app: &App) {
                 }
             }
             Focus::TagTable => {
-                " Tab/Esc: Explorer | ↑/↓: Navigate | →: Expand | ←: Collapse | /: Search | p: Preview "
+                " Tab/Esc: Explorer | ↑/↓: Navigate | →: Expand | ←: Collapse | /: Search | n: Next issue | u: Not in IOD | C: SR tree | F: Frame groups | D: Dose | W: Waveform | O/x: Document | p: Preview | w/1-4/[]{}: W/L | ,/.: Frame | c: Cine | i: Inspect | m: Measure | M/v: MPR | R: Reference lines | P: GSPS | S/s/Space: Structures | o/t: Overlays/Text | g: Histogram | +/-/HJKL: Zoom/Pan | f: Full screen | x/X: Export "
             }
         };
         let help = Paragraph::new(help_text).style(Style::default().fg(Color::Cyan));
@@ -385,33 +474,471 @@ fn render_explorer_help(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 fn render_preview_pane(frame: &mut Frame, area: Rect, app: &mut App) {
+    let mut title = match app.preview.protocol_name() {
+        Some(protocol) => format!(" Preview ({protocol}) "),
+        None => " Preview ".to_string(),
+    };
+    if let Some(window) = app.preview.window_description() {
+        title.push_str(&format!("{window} "));
+    }
+    if let Some(frame) = app.preview.frame_description() {
+        title.push_str(&format!("{frame} "));
+    }
+    if let Some(zoom) = app.preview.zoom_description() {
+        title.push_str(&format!("{zoom} "));
+    }
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::DarkGray))
-        .title(" Preview ");
+        .title(title);
 
     if let Some(ref error) = app.preview.error {
         let paragraph = Paragraph::new(error.as_str())
             .style(Style::default().fg(Color::Red))
             .block(block);
         frame.render_widget(paragraph, area);
-    } else if let Some(ref mut protocol) = app.preview.image {
+    } else if app.preview.image.is_some() {
         let inner = block.inner(area);
         frame.render_widget(block, area);
-        let image = StatefulImage::new(None);
-        frame.render_stateful_widget(image, inner, protocol);
+        let status = app.preview.status_lines();
+        let corners = app.preview.corner_text().unwrap_or_default();
+        let band = |left: &[String], right: &[String]| left.len().max(right.len()) as u16;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(band(&corners.top_left, &corners.top_right)),
+                Constraint::Min(1),
+                Constraint::Length(band(&corners.bottom_left, &corners.bottom_right)),
+                Constraint::Length((status.len() as u16).min(inner.height / 3)),
+            ])
+            .split(inner);
+        render_corners(frame, chunks[0], &corners.top_left, &corners.top_right);
+        render_corners(
+            frame,
+            chunks[2],
+            &corners.bottom_left,
+            &corners.bottom_right,
+        );
+        let lines: Vec<Line> = status.into_iter().map(Line::from).collect();
+        let readout = Paragraph::new(lines).style(Style::default().fg(Color::Yellow));
+        frame.render_widget(readout, chunks[3]);
+        let image_area = if app.preview.show_histogram {
+            let split = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(chunks[1]);
+            render_histogram(frame, split[1], app);
+            split[0]
+        } else {
+            chunks[1]
+        };
+        if app.preview.mpr.is_some() {
+            render_mpr(frame, image_area, app);
+        } else {
+            app.preview.set_area(image_area);
+        }
+        if let (None, Some(ref mut protocol)) = (&app.preview.mpr, &mut app.preview.image) {
+            let image = StatefulImage::new(None);
+            frame.render_stateful_widget(image, image_area, protocol);
+        }
     } else {
-        let paragraph = Paragraph::new("Decoding...")
+        let status = match app.preview.decoding_for() {
+            Some(elapsed) => format!("Decoding... {:.1}s", elapsed.as_secs_f64()),
+            None => "Decoding...".to_string(),
+        };
+        let inner = block.inner(area);
+        let paragraph = Paragraph::new(status)
             .style(Style::default().fg(Color::DarkGray))
             .block(block);
         frame.render_widget(paragraph, area);
+        // The thumbnail stands in while the full image decodes
+        let thumbnail = app.meta.path.as_deref().and_then(|p| app.thumbnails.get(p));
+        if let Some(Ok(thumbnail)) = thumbnail {
+            let image_area = Rect {
+                y: inner.y + 1,
+                height: inner.height.saturating_sub(1),
+                ..inner
+            };
+            render_halfblocks(frame, image_area, &thumbnail.image);
+        }
+    }
+}
+
+/// The reformatted views side by side, the navigated one highlighted
+fn render_mpr(frame: &mut Frame, area: Rect, app: &mut App) {
+    let Some(mpr) = &app.preview.mpr else {
+        return;
+    };
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 3); 3])
+        .split(area);
+    let mut areas = [Rect::default(); 3];
+    for ((view, column), inner) in mpr.views.iter().zip(columns.iter()).zip(areas.iter_mut()) {
+        let color = if view.axis == mpr.active {
+            Color::Yellow
+        } else {
+            Color::DarkGray
+        };
+        let block = Block::default()
+            .borders(Borders::TOP)
+            .border_style(Style::default().fg(color))
+            .title(mpr.title(view.axis));
+        *inner = block.inner(*column);
+        frame.render_widget(block, *column);
+    }
+    app.preview.set_mpr_areas(areas);
+    if let Some(mpr) = &mut app.preview.mpr {
+        for (view, area) in mpr.views.iter_mut().zip(areas) {
+            if let Some(ref mut protocol) = view.image {
+                frame.render_stateful_widget(StatefulImage::new(None), area, protocol);
+            }
+        }
+    }
+}
+
+/// Value distribution of the previewed frame, with the window bounds
+fn render_histogram(frame: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .borders(Borders::TOP)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let Some(histogram) = &app.preview.histogram else {
+        let paragraph = Paragraph::new("Histogram needs a grayscale image")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block.title(" Histogram "));
+        frame.render_widget(paragraph, area);
+        return;
+    };
+
+    let unit = app.preview.units.unit.as_deref().unwrap_or("");
+    let mut title = format!(
+        " Histogram | min {:.1} max {:.1} mean {:.1} {unit} ",
+        histogram.min, histogram.max, histogram.mean
+    );
+    if app.preview.padding.is_some() {
+        title.push_str(&format!("| padding {:.1}% ", histogram.padding_percent()));
+    }
+    let points = histogram.points();
+    let peak = points.iter().map(|&(_, count)| count).fold(1.0, f64::max);
+    let window: Vec<Vec<(f64, f64)>> = app
+        .preview
+        .window_bounds()
+        .map(|(lo, hi)| vec![vec![(lo, 0.0), (lo, peak)], vec![(hi, 0.0), (hi, peak)]])
+        .unwrap_or_default();
+
+    let mut datasets = vec![Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Bar)
+        .style(Style::default().fg(Color::Cyan))
+        .data(&points)];
+    datasets.extend(window.iter().map(|line| {
+        Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Yellow))
+            .data(line)
+    }));
+    let (lo, hi) = (histogram.min, histogram.max.max(histogram.min + 1.0));
+    let chart = Chart::new(datasets)
+        .block(block.title(title))
+        .x_axis(
+            Axis::default()
+                .bounds([lo, hi])
+                .labels([format!("{lo:.0}"), format!("{hi:.0}")])
+                .style(Style::default().fg(Color::DarkGray)),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, peak])
+                .labels(["0".to_string(), format!("{peak:.0}")])
+                .style(Style::default().fg(Color::DarkGray)),
+        );
+    frame.render_widget(chart, area);
+}
+
+/// Rows of the explorer's thumbnail pane, borders included
+const THUMBNAIL_HEIGHT: u16 = 12;
+
+/// Thumbnail of the file under the explorer cursor
+fn render_thumbnail_pane(frame: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let path = app.meta.path.as_deref();
+    let status = match path.and_then(|p| app.thumbnails.get(p)) {
+        Some(Ok(thumbnail)) => {
+            let title = match thumbnail.source {
+                ThumbnailSource::Icon => " Icon ",
+                ThumbnailSource::Frame => " Frame 1 ",
+            };
+            let block = block.title(title);
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            render_halfblocks(frame, inner, &thumbnail.image);
+            return;
+        }
+        Some(Err(e)) => e.clone(),
+        None if path.is_some_and(|p| app.thumbnails.is_pending(p)) => "Reading...".into(),
+        None => String::new(),
+    };
+    let paragraph = Paragraph::new(status)
+        .style(Style::default().fg(Color::DarkGray))
+        .block(block.title(" Thumbnail "));
+    frame.render_widget(paragraph, area);
+}
+
+/// An image scaled to fit `area`, two pixels per cell with upper half
+/// blocks, centered
+fn render_halfblocks(frame: &mut Frame, area: Rect, image: &RgbImage) {
+    let (width, height) = image.dimensions();
+    if area.width == 0 || area.height == 0 || width == 0 || height == 0 {
+        return;
+    }
+    let scale = (area.width as f64 / width as f64).min(area.height as f64 * 2.0 / height as f64);
+    let columns = ((width as f64 * scale) as u32).max(1);
+    let rows = ((height as f64 * scale) as u32).max(1);
+    let scaled = imageops::resize(image, columns, rows, imageops::FilterType::Triangle);
+    let color = |x: u32, y: u32| {
+        let [r, g, b] = scaled.get_pixel(x, y).0;
+        Color::Rgb(r, g, b)
+    };
+    let lines: Vec<Line> = (0..rows.div_ceil(2))
+        .map(|cell_row| {
+            let spans: Vec<Span> = (0..columns)
+                .map(|x| {
+                    let upper = color(x, cell_row * 2);
+                    let mut style = Style::default().fg(upper);
+                    if cell_row * 2 + 1 < rows {
+                        style = style.bg(color(x, cell_row * 2 + 1));
+                    }
+                    Span::styled("▀", style)
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+    let x = area.x + (area.width - columns as u16) / 2;
+    let y = area.y + (area.height - rows.div_ceil(2) as u16) / 2;
+    let image_area = Rect::new(x, y, columns as u16, rows.div_ceil(2) as u16);
+    frame.render_widget(Paragraph::new(lines), image_area);
+}
+
+/// Extracted text of an encapsulated document, from the scrolled line
+fn render_document(frame: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let view = match &app.document {
+        Some(Ok(view)) => view,
+        Some(Err(e)) => {
+            let paragraph = Paragraph::new(e.as_str())
+                .style(Style::default().fg(Color::Red))
+                .block(block.title(" Document "));
+            frame.render_widget(paragraph, area);
+            return;
+        }
+        None => return,
+    };
+    let mut title = format!(" {} ", view.title());
+    if let Ok(lines) = &view.lines {
+        title.push_str(&format!("| line {}/{} ", view.scroll + 1, lines.len()));
+    }
+    if let Some(notice) = &view.notice {
+        title.push_str(&format!("| {notice} "));
     }
+    let block = block.title(title);
+    let paragraph = match &view.lines {
+        Ok(lines) if lines.is_empty() => Paragraph::new("No text found in the document")
+            .style(Style::default().fg(Color::DarkGray)),
+        Ok(lines) => Paragraph::new(
+            lines[view.scroll..]
+                .iter()
+                .map(|line| Line::from(line.as_str()))
+                .collect::<Vec<_>>(),
+        )
+        .wrap(Wrap { trim: false }),
+        Err(e) => Paragraph::new(format!("{e}; x saves the document"))
+            .style(Style::default().fg(Color::DarkGray)),
+    };
+    frame.render_widget(paragraph.block(block), area);
+}
+
+/// Colors of the plotted channels, in channel order
+const CHANNEL_COLORS: [Color; 6] = [
+    Color::Green,
+    Color::Cyan,
+    Color::Yellow,
+    Color::Magenta,
+    Color::LightBlue,
+    Color::LightRed,
+];
+
+/// The shown channels of a waveform group stacked over a shared time axis,
+/// under a line of channel names with the selected one highlighted
+fn render_waveform(frame: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let view = match &app.waveform {
+        Some(Ok(view)) => view,
+        Some(Err(e)) => {
+            let paragraph = Paragraph::new(e.as_str())
+                .style(Style::default().fg(Color::Red))
+                .block(block.title(" Waveform "));
+            frame.render_widget(paragraph, area);
+            return;
+        }
+        None => return,
+    };
+    let block = block.title(format!(" Waveform: {} ", view.title()));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let waveform = view.waveform();
+    let color = |index: usize| CHANNEL_COLORS[index % CHANNEL_COLORS.len()];
+    let names: Vec<Span> = waveform
+        .channels
+        .iter()
+        .enumerate()
+        .map(|(index, channel)| {
+            let mut style = match view.shown[index] {
+                true => Style::default().fg(color(index)),
+                false => Style::default().fg(Color::DarkGray),
+            };
+            if index == view.cursor {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            Span::styled(format!(" {} ", channel.label), style)
+        })
+        .collect();
+    let shown = view.shown_channels();
+    let mut constraints = vec![Constraint::Length(1)];
+    constraints.extend(
+        shown
+            .iter()
+            .map(|_| Constraint::Ratio(1, shown.len() as u32)),
+    );
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(inner);
+    frame.render_widget(Paragraph::new(Line::from(names)), rows[0]);
+
+    let (start, end) = (view.start, view.start + view.span);
+    for (&index, &row) in shown.iter().zip(rows.iter().skip(1)) {
+        let channel = &waveform.channels[index];
+        let points = waveform.points(index, start, end, row.width as usize * 2);
+        let low = points.iter().map(|&(_, v)| v).fold(f64::INFINITY, f64::min);
+        let high = points
+            .iter()
+            .map(|&(_, v)| v)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let (low, high) = match low.is_finite() {
+            true => (low, high.max(low + f64::EPSILON)),
+            false => (0.0, 1.0),
+        };
+        let units = channel.units.as_deref().unwrap_or("");
+        let dataset = Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(color(index)))
+            .data(&points);
+        let chart = Chart::new(vec![dataset])
+            .block(Block::default().title(Span::styled(
+                format!("{} {units}", channel.label),
+                Style::default().fg(color(index)),
+            )))
+            .x_axis(
+                Axis::default()
+                    .bounds([start, end])
+                    .style(Style::default().fg(Color::DarkGray)),
+            )
+            .y_axis(
+                Axis::default()
+                    .bounds([low, high])
+                    .labels([format!("{low:.1}"), format!("{high:.1}")])
+                    .style(Style::default().fg(Color::DarkGray)),
+            );
+        frame.render_widget(chart, row);
+    }
+}
+
+/// Corner annotations above or below the image, left and right aligned
+fn render_corners(frame: &mut Frame, area: Rect, left: &[String], right: &[String]) {
+    let style = Style::default().fg(Color::Gray);
+    let lines = |text: &[String]| -> Vec<Line> { text.iter().cloned().map(Line::from).collect() };
+    frame.render_widget(Paragraph::new(lines(left)).style(style), area);
+    frame.render_widget(
+        Paragraph::new(lines(right))
+            .style(style)
+            .alignment(Alignment::Right),
+        area,
+    );
+}
+
+/// Height of the dose panel, zero while it is hidden
+fn dose_pane_height(app: &App) -> u16 {
+    match app.dose() {
+        Some(Ok(summary)) => summary.lines().len() as u16 + 2,
+        Some(Err(_)) => 3,
+        None if app.is_dose_pending() => 3,
+        None => 0,
+    }
+}
+
+fn render_dose_pane(frame: &mut Frame, area: Rect, app: &App) {
+    let (title, lines, color) = match app.dose() {
+        None if app.is_dose_pending() => (
+            " Dose ",
+            vec![Line::from("Reading the study's images...")],
+            Color::DarkGray,
+        ),
+        None => return,
+        Some(Ok(summary)) => {
+            let title = match summary.source {
+                DoseSource::Report => " Dose (RDSR) ",
+                DoseSource::Images => " Dose (estimated from images) ",
+            };
+            let lines: Vec<Line> = summary.lines().into_iter().map(Line::from).collect();
+            (title, lines, Color::Magenta)
+        }
+        Some(Err(e)) => (" Dose ", vec![Line::from(e.clone())], Color::DarkGray),
+    };
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(color))
+            .title(title)
+            .title_style(Style::default().fg(color)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+fn validation_pane_height(app: &App) -> u16 {
+    let mut height = 3;
+    if matches!(&app.meta.validation_result, ValidationResult::Invalid(_)) {
+        height += 1;
+    }
+    height + warning_kinds(app).len() as u16
+}
+
+/// Warning kinds present in the current file, in order of first appearance
+fn warning_kinds(app: &App) -> Vec<FindingKind> {
+    let mut kinds = Vec::new();
+    for finding in &app.meta.findings {
+        if finding.severity() == Severity::Warning && !kinds.contains(&finding.kind) {
+            kinds.push(finding.kind);
+        }
+    }
+    kinds
 }
 
 fn render_validation_pane(frame: &mut Frame, area: Rect, app: &App) {
     let sop_class_text = match &app.meta.sop_class {
         SopClass::Ct => "CT Image Storage",
         SopClass::Mr => "MR Image Storage",
+        SopClass::EnhancedCt => "Enhanced CT Image Storage",
+        SopClass::EnhancedMr => "Enhanced MR Image Storage",
         SopClass::Other(_) => "Other",
         SopClass::Unknown => "Unknown",
     };
@@ -419,6 +946,8 @@ fn render_validation_pane(frame: &mut Frame, area: Rect, app: &App) {
     let sop_class_uid = match &app.meta.sop_class {
         SopClass::Ct => "1.2.840.10008.5.1.4.1.1.2",
         SopClass::Mr => "1.2.840.10008.5.1.4.1.1.4",
+        SopClass::EnhancedCt => "1.2.840.10008.5.1.4.1.1.2.1",
+        SopClass::EnhancedMr => "1.2.840.10008.5.1.4.1.1.4.1",
         SopClass::Other(uid) => uid.as_str(),
         SopClass::Unknown => "N/A",
     };
@@ -445,6 +974,23 @@ fn render_validation_pane(frame: &mut Frame, area: Rect, app: &App) {
         ]));
     }
 
+    for kind in warning_kinds(app) {
+        let names: Vec<&str> = app
+            .meta
+            .findings
+            .iter()
+            .filter(|f| f.kind == kind)
+            .map(|f| f.name.as_str())
+            .collect();
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<11}", format!("{}:", kind.label())),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(names.join(", "), Style::default().fg(Color::Yellow)),
+        ]));
+    }
+
     let paragraph = Paragraph::new(lines).block(
         Block::default()
             .borders(Borders::ALL)
@@ -0,0 +1,261 @@
+use super::types::{Finding, FindingKind};
+use super::validator::item_label;
+use crate::dicom::tag_label;
+use dicom::core::dictionary::DataDictionary;
+use dicom::core::header::Header;
+use dicom::core::{PrimitiveValue, VR};
+use dicom::dictionary_std::{tags, StandardDataDictionary};
+use dicom::encoding::text::{SpecificCharacterSet, TextCodec};
+use dicom::object::InMemDicomObject;
+
+const ESC: u8 = 0x1B;
+
+/// Escape sequences designating each ISO 2022 character set, as listed in
+/// PS3.3 C.12.1.1.2. Sets designated to G1 carry the upper half of the bytes.
+const ISO_2022_ESCAPES: &[(&str, &[u8], bool)] = &[
+    ("ISO 2022 IR 6", b"(B", false),
+    ("ISO 2022 IR 13", b")I", true),
+    ("ISO 2022 IR 13", b"(J", false),
+    ("ISO 2022 IR 87", b"$B", false),
+    ("ISO 2022 IR 159", b"$(D", false),
+    ("ISO 2022 IR 100", b"-A", true),
+    ("ISO 2022 IR 101", b"-B", true),
+    ("ISO 2022 IR 109", b"-C", true),
+    ("ISO 2022 IR 110", b"-D", true),
+    ("ISO 2022 IR 126", b"-F", true),
+    ("ISO 2022 IR 127", b"-G", true),
+    ("ISO 2022 IR 138", b"-H", true),
+    ("ISO 2022 IR 144", b"-L", true),
+    ("ISO 2022 IR 148", b"-M", true),
+    ("ISO 2022 IR 166", b"-T", true),
+    ("ISO 2022 IR 58", b"$)A", true),
+    ("ISO 2022 IR 149", b"$)C", true),
+];
+
+/// The declared SpecificCharacterSet of a dataset
+struct DeclaredCharset {
+    terms: Vec<String>,
+}
+
+impl DeclaredCharset {
+    fn from_obj(obj: &InMemDicomObject) -> Option<Self> {
+        let value = obj
+            .element(tags::SPECIFIC_CHARACTER_SET)
+            .ok()?
+            .to_str()
+            .ok()?
+            .into_owned();
+        let terms: Vec<String> = value.split('\\').map(|t| t.trim().to_string()).collect();
+        if terms.iter().all(|t| t.is_empty() || t == "ISO_IR 6") {
+            None
+        } else {
+            Some(Self { terms })
+        }
+    }
+
+    fn uses_iso_2022(&self) -> bool {
+        self.terms.iter().any(|t| t.starts_with("ISO 2022"))
+    }
+
+    /// Whether the first term puts a character set in G1, so upper-half bytes
+    /// are valid before any escape sequence
+    fn initial_g1(&self) -> bool {
+        let first = self.terms.first().map(String::as_str).unwrap_or("");
+        !first.is_empty() && first != "ISO 2022 IR 6" && first != "ISO 2022 IR 87"
+    }
+
+    /// The codec the parser decoded values with, used to recover their bytes
+    fn decoder(&self) -> SpecificCharacterSet {
+        self.terms
+            .first()
+            .and_then(|t| SpecificCharacterSet::from_code(t))
+            .unwrap_or_default()
+    }
+}
+
+/// Text values whose bytes do not fit the declared SpecificCharacterSet,
+/// searched through every sequence item
+pub fn find_charset_warnings(obj: &InMemDicomObject) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    collect_charset_warnings(obj, None, "", &[], &mut findings);
+    findings
+}
+
+fn collect_charset_warnings(
+    obj: &InMemDicomObject,
+    inherited: Option<&DeclaredCharset>,
+    prefix: &str,
+    location: &[String],
+    findings: &mut Vec<Finding>,
+) {
+    // Sequence items may redeclare the character set for their own content
+    let own = DeclaredCharset::from_obj(obj);
+    let declared = own.as_ref().or(inherited);
+
+    for element in obj {
+        let tag = element.tag();
+        let mut element_location = location.to_vec();
+        element_location.push(tag_label(tag));
+        let name = StandardDataDictionary
+            .by_tag(tag)
+            .map(|entry| entry.alias.to_string())
+            .unwrap_or_else(|| tag_label(tag));
+
+        if let Some(items) = element.items() {
+            for (idx, item) in items.iter().enumerate() {
+                let item_prefix = format!("{prefix}{name}[{}].", idx + 1);
+                let mut item_location = element_location.clone();
+                item_location.push(item_label(idx));
+                collect_charset_warnings(item, declared, &item_prefix, &item_location, findings);
+            }
+            continue;
+        }
+
+        let vr = element.vr();
+        if !matches!(
+            vr,
+            VR::SH | VR::LO | VR::ST | VR::LT | VR::UT | VR::PN | VR::UC
+        ) {
+            continue;
+        }
+        let Some(value) = element.value().primitive() else {
+            continue;
+        };
+
+        let codec = declared.map(DeclaredCharset::decoder).unwrap_or_default();
+        let kind = match value_bytes(value, &codec) {
+            Some(bytes) => check_value(&bytes, vr, declared),
+            // Bytes the declared set cannot decode, or characters it
+            // cannot encode
+            None if declared.is_some() => Some(FindingKind::InvalidCharacters),
+            None => Some(FindingKind::UndeclaredCharset),
+        };
+        if let Some(kind) = kind {
+            findings.push(Finding {
+                kind,
+                name: format!("{prefix}{name}"),
+                location: element_location,
+            });
+        }
+    }
+}
+
+/// The bytes a text value was read from. The parser splits values at `\`,
+/// decodes each with the first declared character set and writes every byte
+/// it cannot decode as a `\ooo` octal escape. The rest is encoded back with
+/// the same set. `None` when an escape shows the bytes did not decode, or a
+/// character has no encoding in the set.
+fn value_bytes(value: &PrimitiveValue, codec: &SpecificCharacterSet) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    match value {
+        PrimitiveValue::Str(text) => {
+            // A single text value may hold backslashes of its own, but not
+            // before an octal upper-half byte
+            encode_value(text, codec, b'2'..=b'3', &mut bytes)?;
+        }
+        PrimitiveValue::Strs(values) => {
+            for (i, text) in values.iter().enumerate() {
+                if i > 0 {
+                    bytes.push(b'\\');
+                }
+                encode_value(text, codec, b'0'..=b'3', &mut bytes)?;
+            }
+        }
+        // Empty values, or bytes stored as they are
+        other => bytes.extend(other.to_bytes().iter()),
+    }
+    Some(bytes)
+}
+
+/// Encodes one value, `None` at an escape whose first octal digit is in
+/// `escape_digits`
+fn encode_value(
+    text: &str,
+    codec: &SpecificCharacterSet,
+    escape_digits: std::ops::RangeInclusive<u8>,
+    bytes: &mut Vec<u8>,
+) -> Option<()> {
+    let raw = text.as_bytes();
+    let is_escape = |i: usize| {
+        raw[i] == b'\\'
+            && raw.len() > i + 3
+            && escape_digits.contains(&raw[i + 1])
+            && raw[i + 2..i + 4].iter().all(|d| (b'0'..=b'7').contains(d))
+    };
+    if (0..raw.len()).any(is_escape) {
+        return None;
+    }
+    bytes.extend(codec.encode(text).ok()?);
+    Some(())
+}
+
+fn check_value(bytes: &[u8], vr: VR, declared: Option<&DeclaredCharset>) -> Option<FindingKind> {
+    let text_vr = matches!(vr, VR::ST | VR::LT | VR::UT);
+    let has_disallowed_control = bytes.iter().any(|&b| {
+        b < 0x20 && b != ESC && !(text_vr && matches!(b, b'\t' | b'\n' | 0x0C | b'\r'))
+    });
+    if has_disallowed_control {
+        return Some(FindingKind::InvalidCharacters);
+    }
+
+    let Some(declared) = declared else {
+        return bytes
+            .iter()
+            .any(|&b| b >= 0x80 || b == ESC)
+            .then_some(FindingKind::UndeclaredCharset);
+    };
+
+    if !declared.uses_iso_2022() {
+        let first = declared.terms.first().map(String::as_str).unwrap_or("");
+        // Invalid UTF-8 and bytes missing from the other sets were already
+        // caught as undecodable by `value_bytes`
+        let invalid = if bytes.contains(&ESC) {
+            true
+        } else if first.starts_with("ISO_IR 1") && first != "ISO_IR 192" {
+            // C1 control codes have no graphic meaning in the ISO 8859 sets
+            bytes.iter().any(|b| (0x80..0xA0).contains(b))
+        } else {
+            false
+        };
+        return invalid.then_some(FindingKind::InvalidCharacters);
+    }
+
+    check_iso_2022(bytes, vr, declared)
+}
+
+fn check_iso_2022(bytes: &[u8], vr: VR, declared: &DeclaredCharset) -> Option<FindingKind> {
+    let mut g1 = declared.initial_g1();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == ESC {
+            let rest = &bytes[i + 1..];
+            let Some((term, seq, designates_g1)) = ISO_2022_ESCAPES
+                .iter()
+                .find(|(_, seq, _)| rest.starts_with(seq))
+            else {
+                return Some(FindingKind::InvalidCharacters);
+            };
+            if *term != "ISO 2022 IR 6" && !declared.terms.iter().any(|t| t == term) {
+                return Some(FindingKind::InvalidCharacters);
+            }
+            if *designates_g1 {
+                g1 = true;
+            }
+            i += 1 + seq.len();
+            continue;
+        }
+
+        // Designations return to their initial state at value and line
+        // delimiters, and at name component delimiters in PN values
+        let resets = matches!(b, b'\\' | b'\r' | b'\n' | b'\t' | 0x0C)
+            || (vr == VR::PN && matches!(b, b'^' | b'='));
+        if resets {
+            g1 = declared.initial_g1();
+        } else if b >= 0x80 && !g1 {
+            return Some(FindingKind::MissingEscape);
+        }
+        i += 1;
+    }
+    None
+}
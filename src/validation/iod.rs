@@ -0,0 +1,73 @@
+use super::retired::is_retired;
+use super::rules::*;
+use super::types::{Finding, FindingKind, SopClass};
+use super::validator::get_sop_class_from_obj;
+use crate::dicom::{is_private_group, tag_label};
+use dicom::core::dictionary::DataDictionary;
+use dicom::core::header::Header;
+use dicom::core::Tag;
+use dicom::dictionary_std::StandardDataDictionary;
+use dicom::object::{FileDicomObject, InMemDicomObject};
+
+/// Modules of the IOD of a SOP class, or `None` when the SOP class has no
+/// rules
+pub(super) fn iod_modules(sop_class: &SopClass) -> Option<&'static [&'static Module]> {
+    match sop_class {
+        SopClass::Ct => Some(CT_IOD_MODULES),
+        SopClass::Mr => Some(MR_IOD_MODULES),
+        SopClass::EnhancedCt => Some(ENHANCED_CT_IOD_MODULES),
+        SopClass::EnhancedMr => Some(ENHANCED_MR_IOD_MODULES),
+        SopClass::Other(_) | SopClass::Unknown => None,
+    }
+}
+
+/// Top-level standard attributes that the object's IOD does not define:
+/// those of a known module outside it, such as MR acquisition attributes
+/// left in a CT image, or else any other attribute of the dictionary
+pub fn find_unexpected_attributes(obj: &FileDicomObject<InMemDicomObject>) -> Vec<Finding> {
+    let Some(modules) = iod_modules(&get_sop_class_from_obj(obj)) else {
+        return Vec::new();
+    };
+
+    let mut findings = Vec::new();
+
+    for element in obj.iter() {
+        let tag = element.tag();
+        if modules.iter().any(|module| module.keyword(tag).is_some()) {
+            continue;
+        }
+
+        let foreign = KNOWN_MODULES
+            .iter()
+            .find_map(|module| module.keyword(tag).map(|name| (module.name, name)));
+        let label = match foreign {
+            Some((module, name)) => format!("{name} ({module})"),
+            None => match undefined_keyword(tag) {
+                Some(name) => format!("{name} (not in the IOD)"),
+                None => continue,
+            },
+        };
+        findings.push(Finding {
+            kind: FindingKind::Unexpected,
+            name: label,
+            location: vec![tag_label(tag)],
+        });
+    }
+
+    findings
+}
+
+/// Keyword of a standard attribute that no module of the supported IODs
+/// lists. Private attributes, group lengths, the repeating overlay and
+/// curve groups, and retired attributes, which are warned about instead,
+/// are left out.
+fn undefined_keyword(tag: Tag) -> Option<&'static str> {
+    let repeating = matches!(tag.group() & 0xFF00, 0x5000 | 0x6000);
+    if is_private_group(tag.group()) || tag.element() == 0 || repeating {
+        return None;
+    }
+    StandardDataDictionary
+        .by_tag(tag)
+        .map(|entry| entry.alias)
+        .filter(|alias| !is_retired(alias))
+}
@@ -1,8 +1,19 @@
+mod charset;
+mod iod;
+mod retired;
 mod rules;
 mod types;
 mod validator;
+mod warnings;
 
-pub use types::{SopClass, ValidationResult};
-pub use validator::{
-    get_sop_class, get_sop_class_from_obj, validate_type1_fields, validate_type1_fields_from_obj,
+pub use types::{Finding, FindingKind, Severity, SopClass, ValidationResult};
+pub use validator::{collect_findings, get_sop_class_from_obj, validate_type1_fields_from_obj};
+
+// Used by the tests but not by the binary, which builds this module again
+#[allow(unused_imports)]
+pub use {
+    charset::find_charset_warnings,
+    iod::find_unexpected_attributes,
+    validator::{find_missing_type1_fields, get_sop_class, validate_type1_fields},
+    warnings::find_warnings,
 };
@@ -0,0 +1,499 @@
+/// Keywords of every retired attribute, generated from the `#[deprecated]`
+/// tag constants of dicom-dictionary-std 0.8 and sorted for binary search.
+/// Regenerate it when the dictionary is upgraded.
+const RETIRED_KEYWORDS: &[&str] = &[
+    "AcquisitionComments",
+    "AcquisitionsInSeries",
+    "AcquisitionsInStudy",
+    "AdaptiveMapFormat",
+    "AddGrayScale",
+    "AdditionalRTROIIdentificationCodeSequence",
+    "AddressTrial",
+    "AlgorithmCodeSequenceTrial",
+    "AlgorithmDescriptionTrial",
+    "AnatomicApproachDirectionCodeSequenceTrial",
+    "AnatomicLocationOfExaminingInstrumentCodeSequenceTrial",
+    "AnatomicLocationOfExaminingInstrumentDescriptionTrial",
+    "AnatomicPerspectiveCodeSequenceTrial",
+    "AnatomicPerspectiveDescriptionTrial",
+    "AnatomicPortalOfEntranceCodeSequenceTrial",
+    "AnatomicStructure",
+    "AnatomicStructureSpaceOrRegionCodeSequenceTrial",
+    "AnatomicStructureSpaceOrRegionModifierCodeSequenceTrial",
+    "AnatomicStructureSpaceOrRegionSequence",
+    "AngleNumber",
+    "AngularPosition",
+    "AnnotationContentSequence",
+    "AnnotationFlag",
+    "Arbitrary",
+    "AttachedContours",
+    "AudioComments",
+    "AudioSampleData",
+    "AudioSampleFormat",
+    "AudioType",
+    "AverageBeamDosePointDepth",
+    "AverageBeamDosePointEquivalentDepth",
+    "AverageBeamDosePointSSD",
+    "AxisLabels",
+    "AxisUnits",
+    "BeamDoseSpecificationPoint",
+    "BeamOrderIndexTrial",
+    "BiPlaneAcquisitionSequence",
+    "BibliographicCitationTrial",
+    "BitsForCodeWord",
+    "BitsGrouped",
+    "BlockColumns",
+    "BlockRows",
+    "BlockedPixels",
+    "Borders",
+    "BottomRightHandCornerOfLocalizerArea",
+    "BulkMotionStatus",
+    "CADFileFormat",
+    "ChemicalShiftMaximumIntegrationLimitInHz",
+    "ChemicalShiftMinimumIntegrationLimitInHz",
+    "CodeLabel",
+    "CodeNumberFormat",
+    "CodeTableLocation",
+    "CoefficientCoding",
+    "CoefficientCodingPointers",
+    "CoefficientsSDDN",
+    "CoefficientsSDHN",
+    "CoefficientsSDVN",
+    "CollationFlag",
+    "ColorImagePrintingFlag",
+    "ColumnOverlap",
+    "ColumnsForNthOrderCoefficients",
+    "CommandLengthToEnd",
+    "CommandMagnificationType",
+    "CommandRecognitionCode",
+    "ComponentReferenceSystem",
+    "CompositingMethod",
+    "CompressionCode",
+    "CompressionDescription",
+    "CompressionLabel",
+    "CompressionOriginator",
+    "CompressionRecognitionCode",
+    "CompressionSequence",
+    "CompressionStepPointers",
+    "ContourOffsetVector",
+    "ContourSlabThickness",
+    "CoordinateStartValue",
+    "CoordinateStepValue",
+    "CoordinateSystemAxisCodeSequence",
+    "CoordinatesSetGeometricTypeTrial",
+    "Copies",
+    "CountsIncluded",
+    "CurrentObserverTrial",
+    "CurveActivationLayer",
+    "CurveData",
+    "CurveDataDescriptor",
+    "CurveDate",
+    "CurveDescription",
+    "CurveDimensions",
+    "CurveLabel",
+    "CurveNumber",
+    "CurveRange",
+    "CurveReferencedOverlayGroup",
+    "CurveReferencedOverlaySequence",
+    "CurveTime",
+    "DCTLabel",
+    "DataBlock",
+    "DataBlockDescription",
+    "DataSetSubtype",
+    "DataSetType",
+    "DataValueRepresentation",
+    "DateOfDocumentOrVerbalTransactionTrial",
+    "DeadTimeCorrectionFlag",
+    "DetailsOfCoefficients",
+    "DialogReceiver",
+    "DischargeDate",
+    "DischargeDiagnosisCodeSequence",
+    "DischargeDiagnosisDescription",
+    "DischargeTime",
+    "DisplayFormat",
+    "DisplayedAreaBottomRightHandCornerTrial",
+    "DisplayedAreaTopLeftHandCornerTrial",
+    "DistanceSourceToSupport",
+    "DistributionAddress",
+    "DistributionName",
+    "DocumentAuthorIdentifierCodeSequenceTrial",
+    "DocumentAuthorTrial",
+    "DocumentIdentifierCodeSequenceTrial",
+    "DocumentingObserverIdentifierCodeSequenceTrial",
+    "DocumentingOrganizationIdentifierCodeSequenceTrial",
+    "DopplerSampleVolumeXPositionRetired",
+    "DopplerSampleVolumeYPositionRetired",
+    "DoseValue",
+    "DoubleExposureFieldDeltaTrial",
+    "DoubleExposureMetersetTrial",
+    "DynamicRange",
+    "EdgePointIndexList",
+    "EndMessageID",
+    "EnergyWindowCenterline",
+    "EnergyWindowTotalWidth",
+    "EquivalentCDADocumentSequence",
+    "Erase",
+    "EscapeTriplet",
+    "EstimatedDoseSaving",
+    "ExposureDoseSequence",
+    "FillerOrderNumberImagingServiceRequestRetired",
+    "FillerOrderNumberProcedure",
+    "FilmBoxContentSequence",
+    "FindLocation",
+    "FindingsFlagTrial",
+    "FindingsGroupRecordingDateTrial",
+    "FindingsGroupRecordingTimeTrial",
+    "FindingsGroupUIDTrial",
+    "FindingsSequenceTrial",
+    "FindingsSourceCategoryCodeSequenceTrial",
+    "FrameOfReferenceRelationshipSequence",
+    "FrameOfReferenceTransformationType",
+    "GeneralPurposePerformedProcedureStepStatus",
+    "GeneralPurposeScheduledProcedureStepPriority",
+    "GeneralPurposeScheduledProcedureStepStatus",
+    "GraphicLayerRecommendedDisplayRGBValue",
+    "GrayLookupTableData",
+    "GrayLookupTableDescriptor",
+    "GrayScale",
+    "HardcopyCreationDeviceID",
+    "HardcopyDeviceManufacturer",
+    "HardcopyDeviceManufacturerModelName",
+    "HardcopyDeviceSoftwareVersion",
+    "HuffmanTableSize",
+    "HuffmanTableTriplet",
+    "IdentificationDescriptionTrial",
+    "IdentifierCodeSequenceTrial",
+    "IdentifyingComments",
+    "ImageBoxContentSequence",
+    "ImageBoxPresentationLUTFlag",
+    "ImageDataLocation",
+    "ImageDimensions",
+    "ImageFormat",
+    "ImageGeometryType",
+    "ImageLocation",
+    "ImageOrientation",
+    "ImageOverlayBoxContentSequence",
+    "ImageOverlayFlag",
+    "ImagePosition",
+    "ImagePresentationComments",
+    "ImageRotationRetired",
+    "ImageTransformationMatrix",
+    "ImageTranslationVector",
+    "ImagesInSeries",
+    "ImagesInStudy",
+    "Impressions",
+    "Initiator",
+    "InputAvailabilityFlag",
+    "InsurancePlanIdentification",
+    "InterpretationApprovalDate",
+    "InterpretationApprovalTime",
+    "InterpretationApproverSequence",
+    "InterpretationAuthor",
+    "InterpretationDiagnosisCodeSequence",
+    "InterpretationDiagnosisDescription",
+    "InterpretationID",
+    "InterpretationIDIssuer",
+    "InterpretationRecordedDate",
+    "InterpretationRecordedTime",
+    "InterpretationRecorder",
+    "InterpretationStatusID",
+    "InterpretationText",
+    "InterpretationTranscriber",
+    "InterpretationTranscriptionDate",
+    "InterpretationTranscriptionTime",
+    "InterpretationTypeID",
+    "IntervalNumber",
+    "IsotopeNumber",
+    "IssuerOfAdmissionID",
+    "IssuerOfServiceEpisodeID",
+    "LUTNumber",
+    "LanguageCodeSequenceTrial",
+    "LargeBluePaletteColorLookupTableData",
+    "LargeBluePaletteColorLookupTableDescriptor",
+    "LargeGreenPaletteColorLookupTableData",
+    "LargeGreenPaletteColorLookupTableDescriptor",
+    "LargePaletteColorLookupTableUID",
+    "LargeRedPaletteColorLookupTableData",
+    "LargeRedPaletteColorLookupTableDescriptor",
+    "LargestImagePixelValueInPlane",
+    "LargestMonochromePixelValue",
+    "LargestValidPixelValue",
+    "LengthToEnd",
+    "LensConstantDescription",
+    "Location",
+    "LossyImageCompressionRetired",
+    "MRDRDirectoryRecordOffset",
+    "MagnifyToNumberOfColumns",
+    "ManipulatedImage",
+    "MaskPointers",
+    "MaskingImage",
+    "MaterialPropertiesFileFormatRetired",
+    "MaximumCoordinateValue",
+    "MeasurementAutomationTrial",
+    "MeasurementPrecisionDescriptionTrial",
+    "MedicalRecordLocator",
+    "MessageSetID",
+    "MinimumCoordinateValue",
+    "ModifiedImageDate",
+    "ModifiedImageDescription",
+    "ModifiedImageID",
+    "ModifiedImageTime",
+    "ModifyingDeviceID",
+    "ModifyingDeviceManufacturer",
+    "MultipleCopiesFlag",
+    "NetworkID",
+    "NonDICOMOutputCodeSequence",
+    "NormalReverse",
+    "NormalizationFactorFormat",
+    "NuclearMedicineSeriesType",
+    "NumberOfChannels",
+    "NumberOfMatches",
+    "NumberOfPoints",
+    "NumberOfReferences",
+    "NumberOfSamples",
+    "NumberOfTables",
+    "NumberOfTransformSteps",
+    "ObjectBinaryIdentifierTrial",
+    "ObjectDirectoryBinaryIdentifierTrial",
+    "ObservationCategoryCodeSequenceTrial",
+    "ObservationDateTrial",
+    "ObservationSubjectClassTrial",
+    "ObservationSubjectContextFlagTrial",
+    "ObservationSubjectTypeCodeSequenceTrial",
+    "ObservationSubjectUIDTrial",
+    "ObservationTimeTrial",
+    "ObserverContextFlagTrial",
+    "OnAxisBackgroundAnatomicStructureCodeSequenceTrial",
+    "OphthalmicAxialLengthAcquisitionMethodCodeSequence",
+    "OphthalmicAxialLengthQualityMetricTypeCodeSequence",
+    "OphthalmicAxialLengthQualityMetricTypeDescription",
+    "OriginalImageIdentification",
+    "OriginalImageIdentificationNomenclature",
+    "OtherPatientIDs",
+    "OtherStudyNumbers",
+    "OverlayBackgroundDensity",
+    "OverlayBitsForCodeWord",
+    "OverlayBitsGrouped",
+    "OverlayCodeLabel",
+    "OverlayCodeTableLocation",
+    "OverlayComments",
+    "OverlayCompressionCode",
+    "OverlayCompressionDescription",
+    "OverlayCompressionLabel",
+    "OverlayCompressionOriginator",
+    "OverlayCompressionStepPointers",
+    "OverlayDate",
+    "OverlayDescriptorBlue",
+    "OverlayDescriptorGray",
+    "OverlayDescriptorGreen",
+    "OverlayDescriptorRed",
+    "OverlayForegroundDensity",
+    "OverlayFormat",
+    "OverlayLocation",
+    "OverlayMagnificationType",
+    "OverlayMode",
+    "OverlayNumber",
+    "OverlayNumberOfTables",
+    "OverlayOrImageMagnification",
+    "OverlayPixelDataSequence",
+    "OverlayPlaneOrigin",
+    "OverlayPlanes",
+    "OverlayRepeatInterval",
+    "OverlaySmoothingType",
+    "OverlayTime",
+    "Overlays",
+    "OverlaysBlue",
+    "OverlaysGray",
+    "OverlaysGreen",
+    "OverlaysRed",
+    "PagePositionID",
+    "ParallelReductionFactorInPlaneRetired",
+    "PatientSetupUID",
+    "PerformedProcessingApplicationsCodeSequence",
+    "PerimeterTable",
+    "PerimeterValue",
+    "PhaseNumber",
+    "PhysicianApprovingInterpretation",
+    "PixelCoordinatesSetTrial",
+    "PixelSpacingSequence",
+    "PlacerOrderNumberImagingServiceRequestRetired",
+    "PlacerOrderNumberProcedure",
+    "Planes",
+    "PostprocessingFunction",
+    "PredictorColumns",
+    "PredictorConstants",
+    "PredictorRows",
+    "PresentationLUTContentSequence",
+    "PresentationLUTFlag",
+    "PrimitivePointIndexList",
+    "Print",
+    "PrintJobDescriptionSequence",
+    "PrintJobID",
+    "PrintManagementCapabilitiesSequence",
+    "PrintQueueID",
+    "PrinterCharacteristicsSequence",
+    "ProcedureContextFlagTrial",
+    "ProcedureContextSequenceTrial",
+    "ProcedureIdentifierCodeSequenceTrial",
+    "ProcedureStepRelationshipType",
+    "QueueStatus",
+    "ROIObservationDescription",
+    "ROIObservationLabel",
+    "RTDoseROISequence",
+    "Radionuclide",
+    "ReasonForStudy",
+    "ReasonForTheImagingServiceRequest",
+    "Receiver",
+    "RecognitionCode",
+    "Reference",
+    "ReferenceToRecordedSound",
+    "ReferencedAccessionSequenceTrial",
+    "ReferencedCurveSequence",
+    "ReferencedFindingsGroupUIDTrial",
+    "ReferencedFrameNumbers",
+    "ReferencedGeneralPurposeScheduledProcedureStepSequence",
+    "ReferencedGeneralPurposeScheduledProcedureStepTransactionUID",
+    "ReferencedImageBoxSequenceRetired",
+    "ReferencedImageNavigationSequence",
+    "ReferencedImageOverlayBoxSequence",
+    "ReferencedInterpretationSequence",
+    "ReferencedObjectObservationClassTrial",
+    "ReferencedObservationClassTrial",
+    "ReferencedObservationUIDTrial",
+    "ReferencedOverlayPlaneGroups",
+    "ReferencedOverlayPlaneSequence",
+    "ReferencedOverlaySequence",
+    "ReferencedPatientAliasSequence",
+    "ReferencedPrintJobSequence",
+    "ReferencedPrintJobSequencePullStoredPrint",
+    "ReferencedProcedureStepSequence",
+    "ReferencedRTPatientSetupSequence",
+    "ReferencedResultsSequence",
+    "ReferencedStoredPrintSequence",
+    "ReferencedVOILUTBoxSequence",
+    "RelatedFrameOfReferenceUID",
+    "RelatedProcedureStepSequence",
+    "RelationshipSequenceTrial",
+    "RelationshipTypeCodeSequenceTrial",
+    "RelevantInformationSequence",
+    "RepeatInterval",
+    "ReportDetailSequenceTrial",
+    "ReportNumber",
+    "ReportProductionStatusTrial",
+    "ReportStatusCommentTrial",
+    "ReportStatusIDTrial",
+    "RequestedProcedureDescriptionTrial",
+    "RequestedSubsequentWorkitemCodeSequence",
+    "ResponseSequenceNumber",
+    "ResultingGeneralPurposePerformedProcedureStepsSequence",
+    "ResultsComments",
+    "ResultsDistributionListSequence",
+    "ResultsID",
+    "ResultsIDIssuer",
+    "RoboticBaseLocationIndicator",
+    "RotationOffset",
+    "RowOverlap",
+    "RowsForNthOrderCoefficients",
+    "RunLengthTriplet",
+    "SampleRate",
+    "ScheduledAdmissionDate",
+    "ScheduledAdmissionTime",
+    "ScheduledDischargeDate",
+    "ScheduledDischargeTime",
+    "ScheduledPatientInstitutionResidence",
+    "ScheduledProcessingApplicationsCodeSequence",
+    "ScheduledStudyLocation",
+    "ScheduledStudyLocationAETitle",
+    "ScheduledStudyStartDate",
+    "ScheduledStudyStartTime",
+    "ScheduledStudyStopDate",
+    "ScheduledStudyStopTime",
+    "SequenceOfCompressedData",
+    "SequencingIndicatorTrial",
+    "SeriesInStudy",
+    "ShiftTableSize",
+    "ShiftTableTriplet",
+    "SlideIdentifier",
+    "SmallestImagePixelValueInPlane",
+    "SmallestValidPixelValue",
+    "SourceImageIDs",
+    "SpecimenAccessionNumber",
+    "SpecimenDescriptionSequenceTrial",
+    "SpecimenDescriptionTrial",
+    "SpecimenSequence",
+    "StudyArrivalDate",
+    "StudyArrivalTime",
+    "StudyComments",
+    "StudyCompletionDate",
+    "StudyCompletionTime",
+    "StudyComponentStatusID",
+    "StudyIDIssuer",
+    "StudyPriorityID",
+    "StudyReadDate",
+    "StudyReadTime",
+    "StudyStatusID",
+    "StudyVerifiedDate",
+    "StudyVerifiedTime",
+    "TMLinePositionX0Retired",
+    "TMLinePositionX1Retired",
+    "TMLinePositionY0Retired",
+    "TMLinePositionY1Retired",
+    "TelephoneNumberTrial",
+    "TemplateExtensionCreatorUID",
+    "TemplateExtensionFlag",
+    "TemplateExtensionOrganizationUID",
+    "TemplateLocalVersion",
+    "TemplateVersion",
+    "TerminalType",
+    "TextComments",
+    "TextFormatID",
+    "TherapyDescription",
+    "TherapyType",
+    "ThresholdDensity",
+    "TimeOfDocumentCreationOrVerbalTransactionTrial",
+    "TimeSlotNumber",
+    "TopLeftHandCornerOfLocalizerArea",
+    "TopicAuthor",
+    "TopicKeywords",
+    "TopicSubject",
+    "TopicTitle",
+    "TotalGain",
+    "TotalNumberOfExposures",
+    "TotalTime",
+    "TotalTimeOfFluoroscopy",
+    "TransducerOrientation",
+    "TransducerOrientationModifierSequence",
+    "TransducerOrientationSequence",
+    "TransducerPosition",
+    "TransducerPositionModifierSequence",
+    "TransducerPositionSequence",
+    "TransformLabel",
+    "TransformVersionNumber",
+    "TreatmentSites",
+    "TreatmentTerminationCode",
+    "TrianglePointIndexList",
+    "TypeOfData",
+    "UniformResourceLocatorTrial",
+    "UpperLowerPixelValues",
+    "UrgencyOrPriorityAlertsTrial",
+    "VariableCoefficientsSDDN",
+    "VariableCoefficientsSDHN",
+    "VariableCoefficientsSDVN",
+    "VariableNextDataGroup",
+    "VariablePixelData",
+    "VerbalSourceIdentifierCodeSequenceTrial",
+    "VerbalSourceTrial",
+    "VertexPointIndexList",
+    "VitalStainCodeSequenceTrial",
+    "WeightingLookupTableData",
+    "WeightingLookupTableDescriptor",
+    "ZonalMap",
+    "ZonalMapFormat",
+    "ZonalMapLocation",
+    "ZonalMapNumberFormat",
+];
+
+/// Whether a dictionary keyword names a retired attribute
+pub(super) fn is_retired(keyword: &str) -> bool {
+    RETIRED_KEYWORDS.binary_search(&keyword).is_ok()
+}
@@ -49,3 +49,718 @@ pub const MR_IMAGE_TYPE1_TAGS: &[(Tag, &str)] = &[
     (tags::SEQUENCE_VARIANT, "SequenceVariant"),
     (tags::MR_ACQUISITION_TYPE, "MRAcquisitionType"),
 ];
+
+pub const CODE_SEQUENCE_ITEM_TYPE1_TAGS: &[(Tag, &str)] = &[
+    (tags::CODE_VALUE, "CodeValue"),
+    (tags::CODING_SCHEME_DESIGNATOR, "CodingSchemeDesignator"),
+    (tags::CODE_MEANING, "CodeMeaning"),
+];
+
+pub const IMAGE_REFERENCE_ITEM_TYPE1_TAGS: &[(Tag, &str)] = &[
+    (tags::REFERENCED_SOP_CLASS_UID, "ReferencedSOPClassUID"),
+    (tags::REFERENCED_SOP_INSTANCE_UID, "ReferencedSOPInstanceUID"),
+];
+
+/// Attributes required in every item of a sequence, addressed by the path of
+/// sequences leading to it. Nothing is checked when a sequence on the path is
+/// absent, since its presence is governed by the enclosing module.
+pub struct SequenceRule {
+    pub path: &'static [(Tag, &'static str)],
+    pub tags: &'static [(Tag, &'static str)],
+}
+
+pub const GENERAL_IMAGE_SEQUENCE_RULES: &[SequenceRule] = &[
+    SequenceRule {
+        path: &[(tags::ANATOMIC_REGION_SEQUENCE, "AnatomicRegionSequence")],
+        tags: CODE_SEQUENCE_ITEM_TYPE1_TAGS,
+    },
+    SequenceRule {
+        path: &[
+            (tags::ANATOMIC_REGION_SEQUENCE, "AnatomicRegionSequence"),
+            (tags::ANATOMIC_REGION_MODIFIER_SEQUENCE, "AnatomicRegionModifierSequence"),
+        ],
+        tags: CODE_SEQUENCE_ITEM_TYPE1_TAGS,
+    },
+    SequenceRule {
+        path: &[(tags::PROCEDURE_CODE_SEQUENCE, "ProcedureCodeSequence")],
+        tags: CODE_SEQUENCE_ITEM_TYPE1_TAGS,
+    },
+    SequenceRule {
+        path: &[(tags::REFERENCED_IMAGE_SEQUENCE, "ReferencedImageSequence")],
+        tags: IMAGE_REFERENCE_ITEM_TYPE1_TAGS,
+    },
+    SequenceRule {
+        path: &[(tags::SOURCE_IMAGE_SEQUENCE, "SourceImageSequence")],
+        tags: IMAGE_REFERENCE_ITEM_TYPE1_TAGS,
+    },
+];
+
+/// A functional group macro that every frame must carry, either in its
+/// PerFrameFunctionalGroupsSequence item or in the shared functional groups.
+pub struct FunctionalGroupRule {
+    pub sequence: (Tag, &'static str),
+    pub tags: &'static [(Tag, &'static str)],
+}
+
+pub const ENHANCED_FUNCTIONAL_GROUP_RULES: &[FunctionalGroupRule] = &[
+    FunctionalGroupRule {
+        sequence: (tags::PIXEL_MEASURES_SEQUENCE, "PixelMeasuresSequence"),
+        tags: &[(tags::PIXEL_SPACING, "PixelSpacing")],
+    },
+    FunctionalGroupRule {
+        sequence: (tags::FRAME_CONTENT_SEQUENCE, "FrameContentSequence"),
+        tags: &[],
+    },
+    FunctionalGroupRule {
+        sequence: (tags::PLANE_POSITION_SEQUENCE, "PlanePositionSequence"),
+        tags: &[(tags::IMAGE_POSITION_PATIENT, "ImagePositionPatient")],
+    },
+    FunctionalGroupRule {
+        sequence: (tags::PLANE_ORIENTATION_SEQUENCE, "PlaneOrientationSequence"),
+        tags: &[(tags::IMAGE_ORIENTATION_PATIENT, "ImageOrientationPatient")],
+    },
+];
+
+pub const ENHANCED_CT_FUNCTIONAL_GROUP_RULES: &[FunctionalGroupRule] = &[FunctionalGroupRule {
+    sequence: (tags::CT_IMAGE_FRAME_TYPE_SEQUENCE, "CTImageFrameTypeSequence"),
+    tags: &[(tags::FRAME_TYPE, "FrameType")],
+}];
+
+pub const ENHANCED_MR_FUNCTIONAL_GROUP_RULES: &[FunctionalGroupRule] = &[FunctionalGroupRule {
+    sequence: (tags::MR_IMAGE_FRAME_TYPE_SEQUENCE, "MRImageFrameTypeSequence"),
+    tags: &[(tags::FRAME_TYPE, "FrameType")],
+}];
+
+/// A module of the supported IODs. Objects whose IOD includes the module
+/// must carry its Type 1 attributes, sequence items and functional group
+/// macros; any of its attributes is out of place in IODs that leave it out.
+pub struct Module {
+    pub name: &'static str,
+    pub type1: &'static [(Tag, &'static str)],
+    /// Attributes of the other types
+    pub others: &'static [(Tag, &'static str)],
+    pub sequences: &'static [SequenceRule],
+    pub functional_groups: &'static [FunctionalGroupRule],
+}
+
+impl Module {
+    /// Keyword of the attribute when the module defines it
+    pub fn keyword(&self, tag: Tag) -> Option<&'static str> {
+        self.type1
+            .iter()
+            .chain(self.others)
+            .find(|(t, _)| *t == tag)
+            .map(|(_, name)| *name)
+    }
+}
+
+const EMPTY_MODULE: Module = Module {
+    name: "",
+    type1: &[],
+    others: &[],
+    sequences: &[],
+    functional_groups: &[],
+};
+
+pub const SOP_COMMON_MODULE: Module = Module {
+    name: "SOP Common",
+    type1: SOP_COMMON_TYPE1_TAGS,
+    others: &[
+        (tags::SPECIFIC_CHARACTER_SET, "SpecificCharacterSet"),
+        (tags::INSTANCE_CREATION_DATE, "InstanceCreationDate"),
+        (tags::INSTANCE_CREATION_TIME, "InstanceCreationTime"),
+        (tags::INSTANCE_CREATOR_UID, "InstanceCreatorUID"),
+        (tags::RELATED_GENERAL_SOP_CLASS_UID, "RelatedGeneralSOPClassUID"),
+        (tags::ORIGINAL_SPECIALIZED_SOP_CLASS_UID, "OriginalSpecializedSOPClassUID"),
+        (tags::CODING_SCHEME_IDENTIFICATION_SEQUENCE, "CodingSchemeIdentificationSequence"),
+        (tags::TIMEZONE_OFFSET_FROM_UTC, "TimezoneOffsetFromUTC"),
+        (tags::CONTRIBUTING_EQUIPMENT_SEQUENCE, "ContributingEquipmentSequence"),
+        (tags::SOP_INSTANCE_STATUS, "SOPInstanceStatus"),
+        (tags::SOP_AUTHORIZATION_DATE_TIME, "SOPAuthorizationDateTime"),
+        (tags::SOP_AUTHORIZATION_COMMENT, "SOPAuthorizationComment"),
+        (tags::AUTHORIZATION_EQUIPMENT_CERTIFICATION_NUMBER, "AuthorizationEquipmentCertificationNumber"),
+        (tags::ENCRYPTED_ATTRIBUTES_SEQUENCE, "EncryptedAttributesSequence"),
+        (tags::ORIGINAL_ATTRIBUTES_SEQUENCE, "OriginalAttributesSequence"),
+        (tags::INSTANCE_COERCION_DATE_TIME, "InstanceCoercionDateTime"),
+        (tags::LONGITUDINAL_TEMPORAL_INFORMATION_MODIFIED, "LongitudinalTemporalInformationModified"),
+        (tags::QUERY_RETRIEVE_VIEW, "QueryRetrieveView"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const PATIENT_MODULE: Module = Module {
+    name: "Patient",
+    others: &[
+        (tags::PATIENT_NAME, "PatientName"),
+        (tags::PATIENT_ID, "PatientID"),
+        (tags::ISSUER_OF_PATIENT_ID, "IssuerOfPatientID"),
+        (tags::ISSUER_OF_PATIENT_ID_QUALIFIERS_SEQUENCE, "IssuerOfPatientIDQualifiersSequence"),
+        (tags::TYPE_OF_PATIENT_ID, "TypeOfPatientID"),
+        (tags::PATIENT_BIRTH_DATE, "PatientBirthDate"),
+        (tags::PATIENT_BIRTH_TIME, "PatientBirthTime"),
+        (tags::PATIENT_SEX, "PatientSex"),
+        (tags::QUALITY_CONTROL_SUBJECT, "QualityControlSubject"),
+        (tags::REFERENCED_PATIENT_SEQUENCE, "ReferencedPatientSequence"),
+        (tags::OTHER_PATIENT_I_DS_SEQUENCE, "OtherPatientIDsSequence"),
+        (tags::OTHER_PATIENT_NAMES, "OtherPatientNames"),
+        (tags::ETHNIC_GROUP, "EthnicGroup"),
+        (tags::PATIENT_COMMENTS, "PatientComments"),
+        (tags::PATIENT_SPECIES_DESCRIPTION, "PatientSpeciesDescription"),
+        (tags::PATIENT_BREED_DESCRIPTION, "PatientBreedDescription"),
+        (tags::RESPONSIBLE_PERSON, "ResponsiblePerson"),
+        (tags::RESPONSIBLE_ORGANIZATION, "ResponsibleOrganization"),
+        (tags::PATIENT_IDENTITY_REMOVED, "PatientIdentityRemoved"),
+        (tags::DEIDENTIFICATION_METHOD, "DeidentificationMethod"),
+        (tags::DEIDENTIFICATION_METHOD_CODE_SEQUENCE, "DeidentificationMethodCodeSequence"),
+        (tags::CONFIDENTIALITY_CONSTRAINT_ON_PATIENT_DATA_DESCRIPTION, "ConfidentialityConstraintOnPatientDataDescription"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const GENERAL_STUDY_MODULE: Module = Module {
+    name: "General Study",
+    type1: GENERAL_STUDY_TYPE1_TAGS,
+    others: &[
+        (tags::STUDY_DATE, "StudyDate"),
+        (tags::STUDY_TIME, "StudyTime"),
+        (tags::REFERRING_PHYSICIAN_NAME, "ReferringPhysicianName"),
+        (tags::REFERRING_PHYSICIAN_IDENTIFICATION_SEQUENCE, "ReferringPhysicianIdentificationSequence"),
+        (tags::CONSULTING_PHYSICIAN_NAME, "ConsultingPhysicianName"),
+        (tags::STUDY_ID, "StudyID"),
+        (tags::ACCESSION_NUMBER, "AccessionNumber"),
+        (tags::ISSUER_OF_ACCESSION_NUMBER_SEQUENCE, "IssuerOfAccessionNumberSequence"),
+        (tags::STUDY_DESCRIPTION, "StudyDescription"),
+        (tags::PHYSICIANS_OF_RECORD, "PhysiciansOfRecord"),
+        (tags::NAME_OF_PHYSICIANS_READING_STUDY, "NameOfPhysiciansReadingStudy"),
+        (tags::REQUESTING_SERVICE, "RequestingService"),
+        (tags::REQUESTING_SERVICE_CODE_SEQUENCE, "RequestingServiceCodeSequence"),
+        (tags::REFERENCED_STUDY_SEQUENCE, "ReferencedStudySequence"),
+        (tags::PROCEDURE_CODE_SEQUENCE, "ProcedureCodeSequence"),
+        (tags::REASON_FOR_PERFORMED_PROCEDURE_CODE_SEQUENCE, "ReasonForPerformedProcedureCodeSequence"),
+        // Left at the top level by older objects, from the retired Study
+        // Scheduling module
+        (tags::REQUESTING_PHYSICIAN, "RequestingPhysician"),
+        (tags::REQUESTED_PROCEDURE_DESCRIPTION, "RequestedProcedureDescription"),
+        (tags::REQUESTED_PROCEDURE_CODE_SEQUENCE, "RequestedProcedureCodeSequence"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const PATIENT_STUDY_MODULE: Module = Module {
+    name: "Patient Study",
+    others: &[
+        (tags::ADMITTING_DIAGNOSES_DESCRIPTION, "AdmittingDiagnosesDescription"),
+        (tags::ADMITTING_DIAGNOSES_CODE_SEQUENCE, "AdmittingDiagnosesCodeSequence"),
+        (tags::PATIENT_AGE, "PatientAge"),
+        (tags::PATIENT_SIZE, "PatientSize"),
+        (tags::PATIENT_WEIGHT, "PatientWeight"),
+        (tags::PATIENT_BODY_MASS_INDEX, "PatientBodyMassIndex"),
+        (tags::MEASURED_AP_DIMENSION, "MeasuredAPDimension"),
+        (tags::MEASURED_LATERAL_DIMENSION, "MeasuredLateralDimension"),
+        (tags::PATIENT_SIZE_CODE_SEQUENCE, "PatientSizeCodeSequence"),
+        (tags::MEDICAL_ALERTS, "MedicalAlerts"),
+        (tags::ALLERGIES, "Allergies"),
+        (tags::SMOKING_STATUS, "SmokingStatus"),
+        (tags::PREGNANCY_STATUS, "PregnancyStatus"),
+        (tags::LAST_MENSTRUAL_DATE, "LastMenstrualDate"),
+        (tags::PATIENT_STATE, "PatientState"),
+        (tags::OCCUPATION, "Occupation"),
+        (tags::ADDITIONAL_PATIENT_HISTORY, "AdditionalPatientHistory"),
+        (tags::ADMISSION_ID, "AdmissionID"),
+        (tags::ISSUER_OF_ADMISSION_ID_SEQUENCE, "IssuerOfAdmissionIDSequence"),
+        (tags::SERVICE_EPISODE_ID, "ServiceEpisodeID"),
+        (tags::SERVICE_EPISODE_DESCRIPTION, "ServiceEpisodeDescription"),
+        (tags::PATIENT_SEX_NEUTERED, "PatientSexNeutered"),
+        (tags::REASON_FOR_VISIT, "ReasonForVisit"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const GENERAL_SERIES_MODULE: Module = Module {
+    name: "General Series",
+    type1: GENERAL_SERIES_TYPE1_TAGS,
+    others: &[
+        (tags::LATERALITY, "Laterality"),
+        (tags::SERIES_NUMBER, "SeriesNumber"),
+        (tags::SERIES_DATE, "SeriesDate"),
+        (tags::SERIES_TIME, "SeriesTime"),
+        (tags::PERFORMING_PHYSICIAN_NAME, "PerformingPhysicianName"),
+        (tags::PERFORMING_PHYSICIAN_IDENTIFICATION_SEQUENCE, "PerformingPhysicianIdentificationSequence"),
+        (tags::PROTOCOL_NAME, "ProtocolName"),
+        (tags::SERIES_DESCRIPTION, "SeriesDescription"),
+        (tags::SERIES_DESCRIPTION_CODE_SEQUENCE, "SeriesDescriptionCodeSequence"),
+        (tags::OPERATORS_NAME, "OperatorsName"),
+        (tags::OPERATOR_IDENTIFICATION_SEQUENCE, "OperatorIdentificationSequence"),
+        (tags::REFERENCED_PERFORMED_PROCEDURE_STEP_SEQUENCE, "ReferencedPerformedProcedureStepSequence"),
+        (tags::RELATED_SERIES_SEQUENCE, "RelatedSeriesSequence"),
+        (tags::BODY_PART_EXAMINED, "BodyPartExamined"),
+        (tags::PATIENT_POSITION, "PatientPosition"),
+        (tags::SMALLEST_PIXEL_VALUE_IN_SERIES, "SmallestPixelValueInSeries"),
+        (tags::LARGEST_PIXEL_VALUE_IN_SERIES, "LargestPixelValueInSeries"),
+        (tags::REQUEST_ATTRIBUTES_SEQUENCE, "RequestAttributesSequence"),
+        (tags::PERFORMED_PROCEDURE_STEP_ID, "PerformedProcedureStepID"),
+        (tags::PERFORMED_PROCEDURE_STEP_START_DATE, "PerformedProcedureStepStartDate"),
+        (tags::PERFORMED_PROCEDURE_STEP_START_TIME, "PerformedProcedureStepStartTime"),
+        (tags::PERFORMED_PROCEDURE_STEP_END_DATE, "PerformedProcedureStepEndDate"),
+        (tags::PERFORMED_PROCEDURE_STEP_END_TIME, "PerformedProcedureStepEndTime"),
+        (tags::PERFORMED_PROCEDURE_STEP_DESCRIPTION, "PerformedProcedureStepDescription"),
+        (tags::PERFORMED_PROTOCOL_CODE_SEQUENCE, "PerformedProtocolCodeSequence"),
+        (tags::COMMENTS_ON_THE_PERFORMED_PROCEDURE_STEP, "CommentsOnThePerformedProcedureStep"),
+        (tags::ANATOMICAL_ORIENTATION_TYPE, "AnatomicalOrientationType"),
+        (tags::TREATMENT_SESSION_UID, "TreatmentSessionUID"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const FRAME_OF_REFERENCE_MODULE: Module = Module {
+    name: "Frame of Reference",
+    type1: FRAME_OF_REFERENCE_TYPE1_TAGS,
+    others: &[
+        (tags::POSITION_REFERENCE_INDICATOR, "PositionReferenceIndicator"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const GENERAL_EQUIPMENT_MODULE: Module = Module {
+    name: "General Equipment",
+    others: &[
+        (tags::MANUFACTURER, "Manufacturer"),
+        (tags::INSTITUTION_NAME, "InstitutionName"),
+        (tags::INSTITUTION_ADDRESS, "InstitutionAddress"),
+        (tags::STATION_NAME, "StationName"),
+        (tags::INSTITUTIONAL_DEPARTMENT_NAME, "InstitutionalDepartmentName"),
+        (tags::INSTITUTIONAL_DEPARTMENT_TYPE_CODE_SEQUENCE, "InstitutionalDepartmentTypeCodeSequence"),
+        (tags::MANUFACTURER_MODEL_NAME, "ManufacturerModelName"),
+        (tags::MANUFACTURER_DEVICE_CLASS_UID, "ManufacturerDeviceClassUID"),
+        (tags::DEVICE_SERIAL_NUMBER, "DeviceSerialNumber"),
+        (tags::SOFTWARE_VERSIONS, "SoftwareVersions"),
+        (tags::GANTRY_ID, "GantryID"),
+        (tags::UDI_SEQUENCE, "UDISequence"),
+        (tags::DEVICE_UID, "DeviceUID"),
+        (tags::SPATIAL_RESOLUTION, "SpatialResolution"),
+        (tags::DATE_OF_LAST_CALIBRATION, "DateOfLastCalibration"),
+        (tags::TIME_OF_LAST_CALIBRATION, "TimeOfLastCalibration"),
+        (tags::PIXEL_PADDING_VALUE, "PixelPaddingValue"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const GENERAL_ACQUISITION_MODULE: Module = Module {
+    name: "General Acquisition",
+    others: &[
+        (tags::ACQUISITION_UID, "AcquisitionUID"),
+        (tags::ACQUISITION_NUMBER, "AcquisitionNumber"),
+        (tags::ACQUISITION_DATE, "AcquisitionDate"),
+        (tags::ACQUISITION_TIME, "AcquisitionTime"),
+        (tags::ACQUISITION_DATE_TIME, "AcquisitionDateTime"),
+        (tags::ACQUISITION_DURATION, "AcquisitionDuration"),
+        (tags::IMAGES_IN_ACQUISITION, "ImagesInAcquisition"),
+        (tags::IRRADIATION_EVENT_UID, "IrradiationEventUID"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const IMAGE_PIXEL_MODULE: Module = Module {
+    name: "Image Pixel",
+    type1: IMAGE_PIXEL_TYPE1_TAGS,
+    others: &[
+        (tags::PLANAR_CONFIGURATION, "PlanarConfiguration"),
+        (tags::PIXEL_ASPECT_RATIO, "PixelAspectRatio"),
+        (tags::SMALLEST_IMAGE_PIXEL_VALUE, "SmallestImagePixelValue"),
+        (tags::LARGEST_IMAGE_PIXEL_VALUE, "LargestImagePixelValue"),
+        (tags::RED_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR, "RedPaletteColorLookupTableDescriptor"),
+        (tags::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR, "GreenPaletteColorLookupTableDescriptor"),
+        (tags::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR, "BluePaletteColorLookupTableDescriptor"),
+        (tags::RED_PALETTE_COLOR_LOOKUP_TABLE_DATA, "RedPaletteColorLookupTableData"),
+        (tags::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA, "GreenPaletteColorLookupTableData"),
+        (tags::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA, "BluePaletteColorLookupTableData"),
+        (tags::ICC_PROFILE, "ICCProfile"),
+        (tags::COLOR_SPACE, "ColorSpace"),
+        (tags::PIXEL_PADDING_RANGE_LIMIT, "PixelPaddingRangeLimit"),
+        (tags::EXTENDED_OFFSET_TABLE, "ExtendedOffsetTable"),
+        (tags::EXTENDED_OFFSET_TABLE_LENGTHS, "ExtendedOffsetTableLengths"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const GENERAL_IMAGE_MODULE: Module = Module {
+    name: "General Image",
+    others: &[
+        (tags::INSTANCE_NUMBER, "InstanceNumber"),
+        (tags::PATIENT_ORIENTATION, "PatientOrientation"),
+        (tags::CONTENT_DATE, "ContentDate"),
+        (tags::CONTENT_TIME, "ContentTime"),
+        (tags::IMAGE_TYPE, "ImageType"),
+        (tags::ACQUISITION_NUMBER, "AcquisitionNumber"),
+        (tags::ACQUISITION_DATE_TIME, "AcquisitionDateTime"),
+        (tags::IRRADIATION_EVENT_UID, "IrradiationEventUID"),
+        (tags::ANATOMIC_REGION_SEQUENCE, "AnatomicRegionSequence"),
+        (tags::REFERENCED_IMAGE_SEQUENCE, "ReferencedImageSequence"),
+        (tags::SOURCE_IMAGE_SEQUENCE, "SourceImageSequence"),
+    ],
+    sequences: GENERAL_IMAGE_SEQUENCE_RULES,
+    ..EMPTY_MODULE
+};
+
+pub const CONTRAST_BOLUS_MODULE: Module = Module {
+    name: "Contrast/Bolus",
+    others: &[
+        (tags::CONTRAST_BOLUS_AGENT, "ContrastBolusAgent"),
+        (tags::CONTRAST_BOLUS_AGENT_SEQUENCE, "ContrastBolusAgentSequence"),
+        (tags::CONTRAST_BOLUS_ROUTE, "ContrastBolusRoute"),
+        (tags::CONTRAST_BOLUS_ADMINISTRATION_ROUTE_SEQUENCE, "ContrastBolusAdministrationRouteSequence"),
+        (tags::CONTRAST_BOLUS_VOLUME, "ContrastBolusVolume"),
+        (tags::CONTRAST_BOLUS_START_TIME, "ContrastBolusStartTime"),
+        (tags::CONTRAST_BOLUS_STOP_TIME, "ContrastBolusStopTime"),
+        (tags::CONTRAST_BOLUS_TOTAL_DOSE, "ContrastBolusTotalDose"),
+        (tags::CONTRAST_FLOW_RATE, "ContrastFlowRate"),
+        (tags::CONTRAST_FLOW_DURATION, "ContrastFlowDuration"),
+        (tags::CONTRAST_BOLUS_INGREDIENT, "ContrastBolusIngredient"),
+        (tags::CONTRAST_BOLUS_INGREDIENT_CONCENTRATION, "ContrastBolusIngredientConcentration"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const VOI_LUT_MODULE: Module = Module {
+    name: "VOI LUT",
+    others: &[
+        (tags::WINDOW_CENTER, "WindowCenter"),
+        (tags::WINDOW_WIDTH, "WindowWidth"),
+        (tags::WINDOW_CENTER_WIDTH_EXPLANATION, "WindowCenterWidthExplanation"),
+        (tags::VOILUT_FUNCTION, "VOILUTFunction"),
+        (tags::VOILUT_SEQUENCE, "VOILUTSequence"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const ACQUISITION_CONTEXT_MODULE: Module = Module {
+    name: "Acquisition Context",
+    others: &[
+        (tags::ACQUISITION_CONTEXT_SEQUENCE, "AcquisitionContextSequence"),
+        (tags::ACQUISITION_CONTEXT_DESCRIPTION, "AcquisitionContextDescription"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const IMAGE_PLANE_MODULE: Module = Module {
+    name: "Image Plane",
+    type1: IMAGE_PLANE_TYPE1_TAGS,
+    others: &[
+        (tags::SLICE_THICKNESS, "SliceThickness"),
+        (tags::SLICE_LOCATION, "SliceLocation"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const CT_IMAGE_MODULE: Module = Module {
+    name: "CT Image",
+    type1: CT_IMAGE_TYPE1_TAGS,
+    others: &[
+        (tags::KVP, "KVP"),
+        (tags::DATA_COLLECTION_DIAMETER, "DataCollectionDiameter"),
+        (tags::DATA_COLLECTION_CENTER_PATIENT, "DataCollectionCenterPatient"),
+        (tags::RECONSTRUCTION_DIAMETER, "ReconstructionDiameter"),
+        (tags::RECONSTRUCTION_TARGET_CENTER_PATIENT, "ReconstructionTargetCenterPatient"),
+        (tags::DISTANCE_SOURCE_TO_DETECTOR, "DistanceSourceToDetector"),
+        (tags::DISTANCE_SOURCE_TO_PATIENT, "DistanceSourceToPatient"),
+        (tags::GANTRY_DETECTOR_TILT, "GantryDetectorTilt"),
+        (tags::TABLE_HEIGHT, "TableHeight"),
+        (tags::ROTATION_DIRECTION, "RotationDirection"),
+        (tags::EXPOSURE_TIME, "ExposureTime"),
+        (tags::X_RAY_TUBE_CURRENT, "XRayTubeCurrent"),
+        (tags::EXPOSURE, "Exposure"),
+        (tags::EXPOSURE_INU_AS, "ExposureInuAs"),
+        (tags::FILTER_TYPE, "FilterType"),
+        (tags::GENERATOR_POWER, "GeneratorPower"),
+        (tags::FOCAL_SPOTS, "FocalSpots"),
+        (tags::CONVOLUTION_KERNEL, "ConvolutionKernel"),
+        (tags::REVOLUTION_TIME, "RevolutionTime"),
+        (tags::SINGLE_COLLIMATION_WIDTH, "SingleCollimationWidth"),
+        (tags::TOTAL_COLLIMATION_WIDTH, "TotalCollimationWidth"),
+        (tags::TABLE_SPEED, "TableSpeed"),
+        (tags::TABLE_FEED_PER_ROTATION, "TableFeedPerRotation"),
+        (tags::SPIRAL_PITCH_FACTOR, "SpiralPitchFactor"),
+        (tags::EXPOSURE_MODULATION_TYPE, "ExposureModulationType"),
+        (tags::CTD_IVOL, "CTDIvol"),
+        (tags::CTDI_PHANTOM_TYPE_CODE_SEQUENCE, "CTDIPhantomTypeCodeSequence"),
+        (tags::CALCIUM_SCORING_MASS_FACTOR_PATIENT, "CalciumScoringMassFactorPatient"),
+        (tags::CALCIUM_SCORING_MASS_FACTOR_DEVICE, "CalciumScoringMassFactorDevice"),
+        (tags::RESCALE_TYPE, "RescaleType"),
+        (tags::SCAN_OPTIONS, "ScanOptions"),
+        (tags::WATER_EQUIVALENT_DIAMETER, "WaterEquivalentDiameter"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const MR_IMAGE_MODULE: Module = Module {
+    name: "MR Image",
+    type1: MR_IMAGE_TYPE1_TAGS,
+    others: &[
+        (tags::REPETITION_TIME, "RepetitionTime"),
+        (tags::ECHO_TIME, "EchoTime"),
+        (tags::ECHO_TRAIN_LENGTH, "EchoTrainLength"),
+        (tags::INVERSION_TIME, "InversionTime"),
+        (tags::TRIGGER_TIME, "TriggerTime"),
+        (tags::SEQUENCE_NAME, "SequenceName"),
+        (tags::ANGIO_FLAG, "AngioFlag"),
+        (tags::NUMBER_OF_AVERAGES, "NumberOfAverages"),
+        (tags::IMAGING_FREQUENCY, "ImagingFrequency"),
+        (tags::IMAGED_NUCLEUS, "ImagedNucleus"),
+        (tags::ECHO_NUMBERS, "EchoNumbers"),
+        (tags::MAGNETIC_FIELD_STRENGTH, "MagneticFieldStrength"),
+        (tags::SPACING_BETWEEN_SLICES, "SpacingBetweenSlices"),
+        (tags::NUMBER_OF_PHASE_ENCODING_STEPS, "NumberOfPhaseEncodingSteps"),
+        (tags::PERCENT_SAMPLING, "PercentSampling"),
+        (tags::PERCENT_PHASE_FIELD_OF_VIEW, "PercentPhaseFieldOfView"),
+        (tags::PIXEL_BANDWIDTH, "PixelBandwidth"),
+        (tags::RECEIVE_COIL_NAME, "ReceiveCoilName"),
+        (tags::TRANSMIT_COIL_NAME, "TransmitCoilName"),
+        (tags::ACQUISITION_MATRIX, "AcquisitionMatrix"),
+        (tags::IN_PLANE_PHASE_ENCODING_DIRECTION, "InPlanePhaseEncodingDirection"),
+        (tags::FLIP_ANGLE, "FlipAngle"),
+        (tags::SAR, "SAR"),
+        (tags::VARIABLE_FLIP_ANGLE_FLAG, "VariableFlipAngleFlag"),
+        (tags::D_BDT, "dBdt"),
+        (tags::TEMPORAL_POSITION_IDENTIFIER, "TemporalPositionIdentifier"),
+        (tags::NUMBER_OF_TEMPORAL_POSITIONS, "NumberOfTemporalPositions"),
+        (tags::TEMPORAL_RESOLUTION, "TemporalResolution"),
+        (tags::RECONSTRUCTION_DIAMETER, "ReconstructionDiameter"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const MULTI_FRAME_FUNCTIONAL_GROUPS_MODULE: Module = Module {
+    name: "Multi-frame Functional Groups",
+    type1: &[
+        (tags::INSTANCE_NUMBER, "InstanceNumber"),
+        (tags::CONTENT_DATE, "ContentDate"),
+        (tags::CONTENT_TIME, "ContentTime"),
+        (tags::NUMBER_OF_FRAMES, "NumberOfFrames"),
+        (tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE, "PerFrameFunctionalGroupsSequence"),
+    ],
+    others: &[
+        (tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE, "SharedFunctionalGroupsSequence"),
+        (tags::CONCATENATION_FRAME_OFFSET_NUMBER, "ConcatenationFrameOffsetNumber"),
+        (tags::REPRESENTATIVE_FRAME_NUMBER, "RepresentativeFrameNumber"),
+        (tags::CONCATENATION_UID, "ConcatenationUID"),
+        (tags::IN_CONCATENATION_NUMBER, "InConcatenationNumber"),
+        (tags::IN_CONCATENATION_TOTAL_NUMBER, "InConcatenationTotalNumber"),
+    ],
+    functional_groups: ENHANCED_FUNCTIONAL_GROUP_RULES,
+    ..EMPTY_MODULE
+};
+
+pub const MULTI_FRAME_DIMENSION_MODULE: Module = Module {
+    name: "Multi-frame Dimension",
+    others: &[
+        (tags::DIMENSION_ORGANIZATION_SEQUENCE, "DimensionOrganizationSequence"),
+        (tags::DIMENSION_ORGANIZATION_TYPE, "DimensionOrganizationType"),
+        (tags::DIMENSION_INDEX_SEQUENCE, "DimensionIndexSequence"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const ENHANCED_CT_IMAGE_MODULE: Module = Module {
+    name: "Enhanced CT Image",
+    type1: &[
+        (tags::IMAGE_TYPE, "ImageType"),
+        (tags::PIXEL_PRESENTATION, "PixelPresentation"),
+        (tags::VOLUMETRIC_PROPERTIES, "VolumetricProperties"),
+        (tags::VOLUME_BASED_CALCULATION_TECHNIQUE, "VolumeBasedCalculationTechnique"),
+    ],
+    others: &[
+        (tags::ACQUISITION_NUMBER, "AcquisitionNumber"),
+        (tags::ACQUISITION_DATE_TIME, "AcquisitionDateTime"),
+        (tags::ACQUISITION_DURATION, "AcquisitionDuration"),
+        (tags::CONTENT_QUALIFICATION, "ContentQualification"),
+        (tags::ISOCENTER_POSITION, "IsocenterPosition"),
+    ],
+    functional_groups: ENHANCED_CT_FUNCTIONAL_GROUP_RULES,
+    ..EMPTY_MODULE
+};
+
+pub const ENHANCED_MR_IMAGE_MODULE: Module = Module {
+    name: "Enhanced MR Image",
+    type1: &[
+        (tags::IMAGE_TYPE, "ImageType"),
+        (tags::PIXEL_PRESENTATION, "PixelPresentation"),
+        (tags::VOLUMETRIC_PROPERTIES, "VolumetricProperties"),
+        (tags::VOLUME_BASED_CALCULATION_TECHNIQUE, "VolumeBasedCalculationTechnique"),
+        (tags::COMPLEX_IMAGE_COMPONENT, "ComplexImageComponent"),
+        (tags::ACQUISITION_CONTRAST, "AcquisitionContrast"),
+    ],
+    others: &[
+        (tags::ACQUISITION_NUMBER, "AcquisitionNumber"),
+        (tags::ACQUISITION_DATE_TIME, "AcquisitionDateTime"),
+        (tags::ACQUISITION_DURATION, "AcquisitionDuration"),
+        (tags::CONTENT_QUALIFICATION, "ContentQualification"),
+        (tags::RESONANT_NUCLEUS, "ResonantNucleus"),
+        (tags::K_SPACE_FILTERING, "KSpaceFiltering"),
+        (tags::MAGNETIC_FIELD_STRENGTH, "MagneticFieldStrength"),
+        (tags::APPLICABLE_SAFETY_STANDARD_AGENCY, "ApplicableSafetyStandardAgency"),
+        (tags::APPLICABLE_SAFETY_STANDARD_DESCRIPTION, "ApplicableSafetyStandardDescription"),
+        (tags::SPACING_BETWEEN_SLICES, "SpacingBetweenSlices"),
+    ],
+    functional_groups: ENHANCED_MR_FUNCTIONAL_GROUP_RULES,
+    ..EMPTY_MODULE
+};
+
+pub const MR_PULSE_SEQUENCE_MODULE: Module = Module {
+    name: "MR Pulse Sequence",
+    others: &[
+        (tags::PULSE_SEQUENCE_NAME, "PulseSequenceName"),
+        (tags::MR_ACQUISITION_TYPE, "MRAcquisitionType"),
+        (tags::ECHO_PULSE_SEQUENCE, "EchoPulseSequence"),
+        (tags::MULTIPLE_SPIN_ECHO, "MultipleSpinEcho"),
+        (tags::MULTI_PLANAR_EXCITATION, "MultiPlanarExcitation"),
+        (tags::PHASE_CONTRAST, "PhaseContrast"),
+        (tags::TIME_OF_FLIGHT_CONTRAST, "TimeOfFlightContrast"),
+        (tags::SPOILING, "Spoiling"),
+        (tags::STEADY_STATE_PULSE_SEQUENCE, "SteadyStatePulseSequence"),
+        (tags::ECHO_PLANAR_PULSE_SEQUENCE, "EchoPlanarPulseSequence"),
+        (tags::SATURATION_RECOVERY, "SaturationRecovery"),
+        (tags::SPECTRALLY_SELECTED_SUPPRESSION, "SpectrallySelectedSuppression"),
+        (tags::OVERSAMPLING_PHASE, "OversamplingPhase"),
+        (tags::GEOMETRY_OF_K_SPACE_TRAVERSAL, "GeometryOfKSpaceTraversal"),
+        (tags::SEGMENTED_K_SPACE_TRAVERSAL, "SegmentedKSpaceTraversal"),
+        (tags::RECTILINEAR_PHASE_ENCODE_REORDERING, "RectilinearPhaseEncodeReordering"),
+        (tags::COVERAGE_OF_K_SPACE, "CoverageOfKSpace"),
+        (tags::NUMBER_OF_K_SPACE_TRAJECTORIES, "NumberOfKSpaceTrajectories"),
+    ],
+    ..EMPTY_MODULE
+};
+
+/// Modules of IODs without validation rules, kept so that their attributes
+/// are reported when left in a supported IOD
+pub const PET_IMAGE_MODULE: Module = Module {
+    name: "PET Image",
+    others: &[
+        (tags::UNITS, "Units"),
+        (tags::COUNTS_SOURCE, "CountsSource"),
+        (tags::DECAY_CORRECTION, "DecayCorrection"),
+        (tags::CORRECTED_IMAGE, "CorrectedImage"),
+        (tags::FRAME_REFERENCE_TIME, "FrameReferenceTime"),
+        (tags::DECAY_FACTOR, "DecayFactor"),
+        (tags::DOSE_CALIBRATION_FACTOR, "DoseCalibrationFactor"),
+        (tags::SCATTER_FRACTION_FACTOR, "ScatterFractionFactor"),
+        (tags::DEAD_TIME_FACTOR, "DeadTimeFactor"),
+        (tags::IMAGE_INDEX, "ImageIndex"),
+        (tags::ACTUAL_FRAME_DURATION, "ActualFrameDuration"),
+        (tags::PRIMARY_PROMPTS_COUNTS_ACCUMULATED, "PrimaryPromptsCountsAccumulated"),
+        (tags::RADIOPHARMACEUTICAL_INFORMATION_SEQUENCE, "RadiopharmaceuticalInformationSequence"),
+    ],
+    ..EMPTY_MODULE
+};
+
+pub const US_IMAGE_MODULE: Module = Module {
+    name: "US Image",
+    others: &[
+        (tags::SEQUENCE_OF_ULTRASOUND_REGIONS, "SequenceOfUltrasoundRegions"),
+        (tags::TRANSDUCER_DATA, "TransducerData"),
+        (tags::TRANSDUCER_TYPE, "TransducerType"),
+        (tags::TRANSDUCER_FREQUENCY, "TransducerFrequency"),
+        (tags::MECHANICAL_INDEX, "MechanicalIndex"),
+        (tags::BONE_THERMAL_INDEX, "BoneThermalIndex"),
+        (tags::CRANIAL_THERMAL_INDEX, "CranialThermalIndex"),
+        (tags::SOFT_TISSUE_THERMAL_INDEX, "SoftTissueThermalIndex"),
+        (tags::DEPTH_OF_SCAN_FIELD, "DepthOfScanField"),
+        (tags::STAGE_NAME, "StageName"),
+        (tags::VIEW_NAME, "ViewName"),
+    ],
+    ..EMPTY_MODULE
+};
+
+/// Every module, in the order an out-of-place attribute is attributed to
+/// one. Modality modules come before the enhanced ones that reuse some of
+/// their attributes.
+pub const KNOWN_MODULES: &[&Module] = &[
+    &SOP_COMMON_MODULE,
+    &PATIENT_MODULE,
+    &GENERAL_STUDY_MODULE,
+    &PATIENT_STUDY_MODULE,
+    &GENERAL_SERIES_MODULE,
+    &FRAME_OF_REFERENCE_MODULE,
+    &GENERAL_EQUIPMENT_MODULE,
+    &GENERAL_ACQUISITION_MODULE,
+    &GENERAL_IMAGE_MODULE,
+    &IMAGE_PLANE_MODULE,
+    &IMAGE_PIXEL_MODULE,
+    &CONTRAST_BOLUS_MODULE,
+    &VOI_LUT_MODULE,
+    &CT_IMAGE_MODULE,
+    &MR_IMAGE_MODULE,
+    &PET_IMAGE_MODULE,
+    &US_IMAGE_MODULE,
+    &ACQUISITION_CONTEXT_MODULE,
+    &MULTI_FRAME_FUNCTIONAL_GROUPS_MODULE,
+    &MULTI_FRAME_DIMENSION_MODULE,
+    &ENHANCED_CT_IMAGE_MODULE,
+    &ENHANCED_MR_IMAGE_MODULE,
+    &MR_PULSE_SEQUENCE_MODULE,
+];
+
+pub const CT_IOD_MODULES: &[&Module] = &[
+    &SOP_COMMON_MODULE,
+    &PATIENT_MODULE,
+    &GENERAL_STUDY_MODULE,
+    &PATIENT_STUDY_MODULE,
+    &GENERAL_SERIES_MODULE,
+    &FRAME_OF_REFERENCE_MODULE,
+    &GENERAL_EQUIPMENT_MODULE,
+    &GENERAL_ACQUISITION_MODULE,
+    &GENERAL_IMAGE_MODULE,
+    &IMAGE_PLANE_MODULE,
+    &IMAGE_PIXEL_MODULE,
+    &CONTRAST_BOLUS_MODULE,
+    &VOI_LUT_MODULE,
+    &CT_IMAGE_MODULE,
+];
+
+pub const MR_IOD_MODULES: &[&Module] = &[
+    &SOP_COMMON_MODULE,
+    &PATIENT_MODULE,
+    &GENERAL_STUDY_MODULE,
+    &PATIENT_STUDY_MODULE,
+    &GENERAL_SERIES_MODULE,
+    &FRAME_OF_REFERENCE_MODULE,
+    &GENERAL_EQUIPMENT_MODULE,
+    &GENERAL_ACQUISITION_MODULE,
+    &GENERAL_IMAGE_MODULE,
+    &IMAGE_PLANE_MODULE,
+    &IMAGE_PIXEL_MODULE,
+    &CONTRAST_BOLUS_MODULE,
+    &VOI_LUT_MODULE,
+    &MR_IMAGE_MODULE,
+];
+
+/// Enhanced objects carry the image plane attributes in functional groups
+pub const ENHANCED_CT_IOD_MODULES: &[&Module] = &[
+    &SOP_COMMON_MODULE,
+    &PATIENT_MODULE,
+    &GENERAL_STUDY_MODULE,
+    &PATIENT_STUDY_MODULE,
+    &GENERAL_SERIES_MODULE,
+    &FRAME_OF_REFERENCE_MODULE,
+    &GENERAL_EQUIPMENT_MODULE,
+    &IMAGE_PIXEL_MODULE,
+    &ACQUISITION_CONTEXT_MODULE,
+    &MULTI_FRAME_FUNCTIONAL_GROUPS_MODULE,
+    &MULTI_FRAME_DIMENSION_MODULE,
+    &ENHANCED_CT_IMAGE_MODULE,
+];
+
+pub const ENHANCED_MR_IOD_MODULES: &[&Module] = &[
+    &SOP_COMMON_MODULE,
+    &PATIENT_MODULE,
+    &GENERAL_STUDY_MODULE,
+    &PATIENT_STUDY_MODULE,
+    &GENERAL_SERIES_MODULE,
+    &FRAME_OF_REFERENCE_MODULE,
+    &GENERAL_EQUIPMENT_MODULE,
+    &IMAGE_PIXEL_MODULE,
+    &ACQUISITION_CONTEXT_MODULE,
+    &MULTI_FRAME_FUNCTIONAL_GROUPS_MODULE,
+    &MULTI_FRAME_DIMENSION_MODULE,
+    &ENHANCED_MR_IMAGE_MODULE,
+    &MR_PULSE_SEQUENCE_MODULE,
+];
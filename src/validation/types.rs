@@ -3,6 +3,8 @@
 pub enum SopClass {
     Ct,
     Mr,
+    EnhancedCt,
+    EnhancedMr,
     Other(String),
     Unknown,
 }
@@ -14,3 +16,74 @@ pub enum ValidationResult {
     Invalid(Vec<String>),
     NotApplicable,
 }
+
+/// How serious a finding is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// What a finding reports
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FindingKind {
+    /// A Type 1 attribute is absent or empty
+    Missing,
+    /// A retired attribute is present
+    Retired,
+    /// A private element sits in a block with no Private Creator
+    UnreservedPrivate,
+    /// A text value has non-ASCII bytes but no SpecificCharacterSet is declared
+    UndeclaredCharset,
+    /// A text value has bytes outside the declared SpecificCharacterSet
+    InvalidCharacters,
+    /// A text value uses a non-initial ISO 2022 set without its escape sequence
+    MissingEscape,
+    /// A standard attribute belongs to a module outside the object's IOD
+    Unexpected,
+}
+
+impl FindingKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            FindingKind::Missing => Severity::Error,
+            FindingKind::Retired
+            | FindingKind::UnreservedPrivate
+            | FindingKind::UndeclaredCharset
+            | FindingKind::InvalidCharacters
+            | FindingKind::MissingEscape
+            | FindingKind::Unexpected => Severity::Warning,
+        }
+    }
+
+    /// Short heading used when listing findings of this kind
+    pub fn label(&self) -> &'static str {
+        match self {
+            FindingKind::Missing => "Missing",
+            FindingKind::Retired => "Retired",
+            FindingKind::UnreservedPrivate => "No creator",
+            FindingKind::UndeclaredCharset => "No charset",
+            FindingKind::InvalidCharacters => "Bad chars",
+            FindingKind::MissingEscape => "No escape",
+            FindingKind::Unexpected => "Not in IOD",
+        }
+    }
+}
+
+/// A validation finding, located by the tag table rows leading to it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    pub kind: FindingKind,
+    /// Attribute path, e.g. `AnatomicRegionSequence[1].CodeMeaning`
+    pub name: String,
+    /// Row labels from the top level down to the offending element, or to the
+    /// item that should hold a missing attribute; empty for missing top-level
+    /// attributes
+    pub location: Vec<String>,
+}
+
+impl Finding {
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
@@ -1,11 +1,18 @@
+use super::charset::find_charset_warnings;
+use super::iod::{find_unexpected_attributes, iod_modules};
 use super::rules::*;
-use super::types::{SopClass, ValidationResult};
+use super::types::{Finding, FindingKind, SopClass, ValidationResult};
+use super::warnings::find_warnings;
+use crate::dicom::tag_label;
 use dicom::core::Tag;
 use dicom::dictionary_std::tags;
-use dicom::dictionary_std::uids::{CT_IMAGE_STORAGE, MR_IMAGE_STORAGE};
+use dicom::dictionary_std::uids::{
+    CT_IMAGE_STORAGE, ENHANCED_CT_IMAGE_STORAGE, ENHANCED_MR_IMAGE_STORAGE, MR_IMAGE_STORAGE,
+};
 use dicom::object::{open_file, FileDicomObject, InMemDicomObject};
 use std::path::Path;
 
+#[allow(dead_code)] // used by the tests only
 pub fn validate_type1_fields<P: AsRef<Path>>(
     path: P,
 ) -> Result<ValidationResult, Box<dyn std::error::Error>> {
@@ -14,57 +21,153 @@ pub fn validate_type1_fields<P: AsRef<Path>>(
 }
 
 pub fn validate_type1_fields_from_obj(obj: &FileDicomObject<InMemDicomObject>) -> ValidationResult {
-    let sop_class_uid = obj
-        .element(tags::SOP_CLASS_UID)
-        .ok()
-        .and_then(|e| e.to_str().ok())
-        .map(|s| s.trim().to_string());
+    match find_missing_type1_fields(obj) {
+        None => ValidationResult::NotApplicable,
+        Some(findings) if findings.is_empty() => ValidationResult::Valid,
+        Some(findings) => ValidationResult::Invalid(findings.into_iter().map(|f| f.name).collect()),
+    }
+}
 
-    let sop_class_uid = match sop_class_uid {
-        Some(uid) => uid,
-        None => return ValidationResult::NotApplicable,
-    };
+/// Missing Type 1 attributes followed by consistency warnings
+pub fn collect_findings(obj: &FileDicomObject<InMemDicomObject>) -> Vec<Finding> {
+    let mut findings = find_missing_type1_fields(obj).unwrap_or_default();
+    findings.extend(find_warnings(obj));
+    findings.extend(find_charset_warnings(obj));
+    findings.extend(find_unexpected_attributes(obj));
+    findings
+}
 
-    let modality_tags: &[(Tag, &str)] = if sop_class_uid == CT_IMAGE_STORAGE {
-        CT_IMAGE_TYPE1_TAGS
-    } else if sop_class_uid == MR_IMAGE_STORAGE {
-        MR_IMAGE_TYPE1_TAGS
-    } else {
-        return ValidationResult::NotApplicable;
-    };
+/// Missing Type 1 attributes, including those nested in sequence items.
+/// Returns `None` when the SOP class has no validation rules.
+pub fn find_missing_type1_fields(obj: &FileDicomObject<InMemDicomObject>) -> Option<Vec<Finding>> {
+    let modules = iod_modules(&get_sop_class_from_obj(obj))?;
 
-    let mut missing_tags = Vec::new();
+    let mut findings = Vec::new();
 
-    let all_tag_groups: &[&[(Tag, &str)]] = &[
-        SOP_COMMON_TYPE1_TAGS,
-        GENERAL_STUDY_TYPE1_TAGS,
-        GENERAL_SERIES_TYPE1_TAGS,
-        FRAME_OF_REFERENCE_TYPE1_TAGS,
-        IMAGE_PLANE_TYPE1_TAGS,
-        IMAGE_PIXEL_TYPE1_TAGS,
-        modality_tags,
-    ];
+    for module in modules {
+        for (tag, name) in module.type1 {
+            if !is_tag_present(obj, *tag) {
+                findings.push(Finding {
+                    kind: FindingKind::Missing,
+                    name: name.to_string(),
+                    location: Vec::new(),
+                });
+            }
+        }
+    }
 
-    for group in all_tag_groups {
-        for (tag, name) in *group {
+    for module in modules {
+        for rule in module.sequences {
+            check_sequence_rule(obj, rule.path, rule.tags, "", &[], &mut findings);
+        }
+        for rule in module.functional_groups {
+            check_functional_group_rule(obj, rule, &mut findings);
+        }
+    }
+
+    Some(findings)
+}
+
+fn check_sequence_rule(
+    obj: &InMemDicomObject,
+    path: &[(Tag, &str)],
+    required: &[(Tag, &str)],
+    prefix: &str,
+    location: &[String],
+    findings: &mut Vec<Finding>,
+) {
+    let Some(((seq_tag, seq_name), rest)) = path.split_first() else {
+        for (tag, name) in required {
             if !is_tag_present(obj, *tag) {
-                missing_tags.push(name.to_string());
+                findings.push(Finding {
+                    kind: FindingKind::Missing,
+                    name: format!("{prefix}{name}"),
+                    location: location.to_vec(),
+                });
             }
         }
+        return;
+    };
+
+    let Some(items) = sequence_items(obj, *seq_tag) else {
+        return;
+    };
+
+    for (idx, item) in items.iter().enumerate() {
+        let item_prefix = format!("{prefix}{seq_name}[{}].", idx + 1);
+        let mut item_location = location.to_vec();
+        item_location.push(tag_label(*seq_tag));
+        item_location.push(item_label(idx));
+        check_sequence_rule(item, rest, required, &item_prefix, &item_location, findings);
     }
+}
+
+fn check_functional_group_rule(
+    obj: &InMemDicomObject,
+    rule: &FunctionalGroupRule,
+    findings: &mut Vec<Finding>,
+) {
+    let (macro_tag, macro_name) = rule.sequence;
+    let Some(per_frame) = sequence_items(obj, tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE) else {
+        return;
+    };
+    let shared = sequence_items(obj, tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE)
+        .and_then(|items| items.first());
+
+    let mut shared_checked = false;
 
-    if missing_tags.is_empty() {
-        ValidationResult::Valid
-    } else {
-        ValidationResult::Invalid(missing_tags)
+    for (idx, frame) in per_frame.iter().enumerate() {
+        let frame_location = vec![
+            tag_label(tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE),
+            item_label(idx),
+        ];
+        let frame_prefix = format!("PerFrameFunctionalGroupsSequence[{}].", idx + 1);
+
+        if sequence_items(frame, macro_tag).is_some() {
+            let path = [(macro_tag, macro_name)];
+            check_sequence_rule(frame, &path, rule.tags, &frame_prefix, &frame_location, findings);
+        } else if let Some(shared) = shared.filter(|s| sequence_items(s, macro_tag).is_some()) {
+            // Report the shared macro once rather than for every frame
+            if !shared_checked {
+                shared_checked = true;
+                let path = [(macro_tag, macro_name)];
+                let location = vec![
+                    tag_label(tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE),
+                    item_label(0),
+                ];
+                let prefix = "SharedFunctionalGroupsSequence[1].";
+                check_sequence_rule(shared, &path, rule.tags, prefix, &location, findings);
+            }
+        } else {
+            findings.push(Finding {
+                kind: FindingKind::Missing,
+                name: format!("{frame_prefix}{macro_name}"),
+                location: frame_location,
+            });
+        }
     }
 }
 
-fn is_tag_present(obj: &FileDicomObject<InMemDicomObject>, tag: Tag) -> bool {
+fn sequence_items(obj: &InMemDicomObject, tag: Tag) -> Option<&[InMemDicomObject]> {
+    obj.element(tag)
+        .ok()
+        .and_then(|e| e.items())
+        .filter(|items| !items.is_empty())
+}
+
+pub(super) fn item_label(idx: usize) -> String {
+    format!("Item #{}", idx + 1)
+}
+
+/// A Type 1 sequence needs at least one item, so an empty one counts as
+/// missing
+fn is_tag_present(obj: &InMemDicomObject, tag: Tag) -> bool {
     obj.element(tag)
         .ok()
         .map(|e| {
-            if let Ok(s) = e.to_str() {
+            if let Some(items) = e.items() {
+                !items.is_empty()
+            } else if let Ok(s) = e.to_str() {
                 !s.trim().is_empty()
             } else {
                 true
@@ -73,6 +176,7 @@ fn is_tag_present(obj: &FileDicomObject<InMemDicomObject>, tag: Tag) -> bool {
         .unwrap_or(false)
 }
 
+#[allow(dead_code)] // used by the tests only
 pub fn get_sop_class<P: AsRef<Path>>(
     path: P,
 ) -> Result<SopClass, Box<dyn std::error::Error>> {
@@ -90,6 +194,8 @@ pub fn get_sop_class_from_obj(obj: &FileDicomObject<InMemDicomObject>) -> SopCla
     match sop_class_uid {
         Some(uid) if uid == CT_IMAGE_STORAGE => SopClass::Ct,
         Some(uid) if uid == MR_IMAGE_STORAGE => SopClass::Mr,
+        Some(uid) if uid == ENHANCED_CT_IMAGE_STORAGE => SopClass::EnhancedCt,
+        Some(uid) if uid == ENHANCED_MR_IMAGE_STORAGE => SopClass::EnhancedMr,
         Some(uid) => SopClass::Other(uid),
         None => SopClass::Unknown,
     }
@@ -0,0 +1,84 @@
+use super::retired::is_retired;
+use super::types::{Finding, FindingKind};
+use super::validator::item_label;
+use crate::dicom::{is_private_group, tag_label};
+use dicom::core::dictionary::DataDictionary;
+use dicom::core::header::Header;
+use dicom::core::Tag;
+use dicom::dictionary_std::StandardDataDictionary;
+use dicom::object::InMemDicomObject;
+
+/// Retired attributes and private elements without a reserved block,
+/// searched through every sequence item
+pub fn find_warnings(obj: &InMemDicomObject) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    collect_warnings(obj, "", &[], &mut findings);
+    findings
+}
+
+fn collect_warnings(
+    obj: &InMemDicomObject,
+    prefix: &str,
+    location: &[String],
+    findings: &mut Vec<Finding>,
+) {
+    for element in obj {
+        let tag = element.tag();
+        let mut element_location = location.to_vec();
+        element_location.push(tag_label(tag));
+
+        if let Some(name) = retired_name(tag) {
+            findings.push(Finding {
+                kind: FindingKind::Retired,
+                name: format!("{prefix}{name}"),
+                location: element_location.clone(),
+            });
+        } else if is_private_group(tag.group()) && !is_private_block_reserved(obj, tag) {
+            findings.push(Finding {
+                kind: FindingKind::UnreservedPrivate,
+                name: format!("{prefix}{}", tag_label(tag)),
+                location: element_location.clone(),
+            });
+        }
+
+        if let Some(items) = element.items() {
+            let seq_name = StandardDataDictionary
+                .by_tag(tag)
+                .map(|entry| entry.alias.to_string())
+                .unwrap_or_else(|| tag_label(tag));
+            for (idx, item) in items.iter().enumerate() {
+                let item_prefix = format!("{prefix}{seq_name}[{}].", idx + 1);
+                let mut item_location = element_location.clone();
+                item_location.push(item_label(idx));
+                collect_warnings(item, &item_prefix, &item_location, findings);
+            }
+        }
+    }
+}
+
+/// Private data elements (gggg,xxee) need a Private Creator at (gggg,00xx)
+fn is_private_block_reserved(obj: &InMemDicomObject, tag: Tag) -> bool {
+    let element = tag.element();
+    if element < 0x1000 {
+        // Group length and the Private Creator elements themselves
+        return true;
+    }
+
+    let creator = Tag(tag.group(), element >> 8);
+    obj.element(creator)
+        .ok()
+        .and_then(|e| e.to_str().ok())
+        .map(|s| !s.trim().is_empty())
+        .unwrap_or(false)
+}
+
+/// Group length elements are retired outside the command and file meta groups
+fn retired_name(tag: Tag) -> Option<&'static str> {
+    if tag.element() == 0x0000 && tag.group() > 0x0002 {
+        return Some("GenericGroupLength");
+    }
+    StandardDataDictionary
+        .by_tag(tag)
+        .map(|entry| entry.alias)
+        .filter(|alias| is_retired(alias))
+}
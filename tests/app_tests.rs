@@ -1,8 +1,11 @@
+// Tests match on enum variants with `assert!(true)` arms
+#![allow(clippy::assertions_on_constants)]
+
 use std::path::PathBuf;
 
 use dcr::app::App;
 use dcr::dicom::DicomTag;
-use dcr::validation::{SopClass, ValidationResult};
+use dcr::validation::{Finding, FindingKind, SopClass, ValidationResult};
 
 fn create_test_tag(tag: &str, name: &str, depth: usize, expandable: bool, children: Vec<DicomTag>) -> DicomTag {
     DicomTag {
@@ -433,8 +436,6 @@ fn new_with_diff_stores_fields_correctly() {
         tags,
         "baseline.dcm".to_string(),
         Some("modified.dcm".to_string()),
-        ValidationResult::Valid,
-        SopClass::Ct,
         true,
         Some(PathBuf::from("/tmp/test.dcm")),
         None,
@@ -447,3 +448,108 @@ fn new_with_diff_stores_fields_correctly() {
     assert_eq!(app.tags.visible.len(), 1);
     assert_eq!(app.tags.table_state.selected(), Some(0));
 }
+
+// --- Finding navigation tests ---
+
+#[test]
+fn reveal_expands_path_and_selects_item() {
+    let grandchildren = vec![create_test_tag("(0008,0100)", "CodeValue", 2, false, Vec::new())];
+    let item = create_test_tag("Item #1", "", 1, true, grandchildren);
+    let seq = create_test_tag("(0008,2218)", "AnatomicRegionSequence", 0, true, vec![item]);
+    let tags = vec![
+        create_test_tag("(0008,0060)", "Modality", 0, false, Vec::new()),
+        seq,
+    ];
+    let mut app = App::new(tags, "test.dcm".to_string(), ValidationResult::Valid, SopClass::Ct);
+    assert_eq!(app.tags.visible.len(), 2);
+
+    app.tags.reveal(&["(0008,2218)".to_string(), "Item #1".to_string()]);
+
+    assert_eq!(app.tags.visible.len(), 4);
+    assert_eq!(app.tags.table_state.selected(), Some(2));
+    assert_eq!(app.tags.visible[2].tag, "Item #1");
+}
+
+#[test]
+fn reveal_unknown_location_is_noop() {
+    let mut app = create_app_with_n_tags(3);
+    app.tags.table_state.select(Some(1));
+    app.tags.reveal(&["(7777,0000)".to_string()]);
+    assert_eq!(app.tags.table_state.selected(), Some(1));
+}
+
+#[test]
+fn visible_locations_follow_depth() {
+    let grandchildren = vec![create_test_tag("(0008,0100)", "CodeValue", 2, false, Vec::new())];
+    let mut item = create_test_tag("Item #1", "", 1, true, grandchildren);
+    item.is_expanded = true;
+    let mut seq = create_test_tag("(0008,2218)", "AnatomicRegionSequence", 0, true, vec![item]);
+    seq.is_expanded = true;
+    let app = App::new(vec![seq], "test.dcm".to_string(), ValidationResult::Valid, SopClass::Ct);
+
+    let locations = app.tags.visible_locations();
+    assert_eq!(locations.len(), 3);
+    assert_eq!(locations[2], vec!["(0008,2218)", "Item #1", "(0008,0100)"]);
+}
+
+#[test]
+fn reveal_next_finding_cycles_nested_findings() {
+    let item1 = create_test_tag("Item #1", "", 1, false, Vec::new());
+    let item2 = create_test_tag("Item #2", "", 1, false, Vec::new());
+    let seq = create_test_tag("(0008,1140)", "ReferencedImageSequence", 0, true, vec![item1, item2]);
+    let mut app = App::new(vec![seq], "test.dcm".to_string(), ValidationResult::Valid, SopClass::Ct);
+    app.meta.findings = vec![
+        Finding {
+            kind: FindingKind::Missing,
+            name: "Modality".to_string(),
+            location: Vec::new(),
+        },
+        Finding {
+            kind: FindingKind::Missing,
+            name: "ReferencedImageSequence[2].ReferencedSOPClassUID".to_string(),
+            location: vec!["(0008,1140)".to_string(), "Item #2".to_string()],
+        },
+    ];
+
+    app.reveal_next_finding();
+    assert_eq!(app.tags.table_state.selected(), Some(2));
+    assert_eq!(app.meta.finding_cursor, 0);
+}
+
+#[test]
+fn unexpected_filter_shows_only_flagged_rows() {
+    let tags = vec![
+        create_test_tag("(0008,0060)", "Modality", 0, false, Vec::new()),
+        create_test_tag("(0018,0080)", "RepetitionTime", 0, false, Vec::new()),
+        create_test_tag("(0018,0060)", "KVP", 0, false, Vec::new()),
+    ];
+    let mut app = App::new(tags, "test.dcm".to_string(), ValidationResult::Valid, SopClass::Ct);
+    app.meta.findings = vec![Finding {
+        kind: FindingKind::Unexpected,
+        name: "RepetitionTime (MR Image)".to_string(),
+        location: vec!["(0018,0080)".to_string()],
+    }];
+
+    app.toggle_unexpected_filter();
+    assert_eq!(app.tags.visible.len(), 1);
+    assert_eq!(app.tags.visible[0].name, "RepetitionTime");
+
+    app.toggle_unexpected_filter();
+    assert_eq!(app.tags.visible.len(), 3);
+    assert!(app.tags.filtered.is_none());
+}
+
+#[test]
+fn unexpected_filter_combines_with_search() {
+    let tags = vec![
+        create_test_tag("(0018,0080)", "RepetitionTime", 0, false, Vec::new()),
+        create_test_tag("(0018,0081)", "EchoTime", 0, false, Vec::new()),
+    ];
+    let mut app = App::new(tags, "test.dcm".to_string(), ValidationResult::Valid, SopClass::Ct);
+    app.tags.only = Some(vec!["(0018,0080)".to_string(), "(0018,0081)".to_string()]);
+    app.tags.filter("echo");
+    assert_eq!(app.tags.visible.len(), 1);
+
+    app.tags.clear_filter();
+    assert_eq!(app.tags.visible.len(), 2, "Clearing the search keeps the IOD filter");
+}
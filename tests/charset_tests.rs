@@ -0,0 +1,137 @@
+use dcr::validation::{find_charset_warnings, FindingKind};
+use dicom::core::{DataElement, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::{open_file, FileMetaTableBuilder, InMemDicomObject};
+use std::path::PathBuf;
+
+fn object_with(charset: Option<&str>, patient_name: &str) -> InMemDicomObject {
+    let mut obj = InMemDicomObject::new_empty();
+    if let Some(charset) = charset {
+        obj.put(DataElement::new(tags::SPECIFIC_CHARACTER_SET, VR::CS, charset));
+    }
+    obj.put(DataElement::new(tags::PATIENT_NAME, VR::PN, patient_name));
+    obj
+}
+
+/// Writes a file with a PatientName of `name`, then puts `byte` in place of
+/// its `#` so the value holds bytes no encoder would write
+fn file_with_byte(file: &str, charset: &str, name: &str, byte: u8) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("dcr-{}-{file}", std::process::id()));
+    let meta = FileMetaTableBuilder::new()
+        .transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN)
+        .media_storage_sop_class_uid(uids::SECONDARY_CAPTURE_IMAGE_STORAGE)
+        .media_storage_sop_instance_uid("1.2.3.4");
+    object_with(Some(charset), name)
+        .with_meta(meta)
+        .unwrap()
+        .write_to_file(&path)
+        .unwrap();
+    let mut bytes = std::fs::read(&path).unwrap();
+    let at = bytes.iter().rposition(|&b| b == b'#').unwrap();
+    bytes[at] = byte;
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+fn kinds(obj: &InMemDicomObject) -> Vec<FindingKind> {
+    find_charset_warnings(obj).into_iter().map(|f| f.kind).collect()
+}
+
+#[test]
+fn ascii_without_charset_is_fine() {
+    assert!(kinds(&object_with(None, "Doe^John")).is_empty());
+}
+
+#[test]
+fn non_ascii_without_charset_is_flagged() {
+    let obj = object_with(None, "M\u{00FC}ller^Hans");
+    let findings = find_charset_warnings(&obj);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].kind, FindingKind::UndeclaredCharset);
+    assert_eq!(findings[0].name, "PatientName");
+    assert_eq!(findings[0].location, vec!["(0010,0010)"]);
+}
+
+#[test]
+fn latin1_with_iso_ir_100_is_fine() {
+    assert!(kinds(&object_with(Some("ISO_IR 100"), "M\u{00FC}ller^Hans")).is_empty());
+}
+
+#[test]
+fn control_characters_are_flagged() {
+    let obj = object_with(Some("ISO_IR 100"), "Doe\u{0007}^John");
+    assert_eq!(kinds(&obj), vec![FindingKind::InvalidCharacters]);
+}
+
+#[test]
+fn japanese_with_escapes_is_fine() {
+    // Yamada^Tarou=<kanji>^<kanji> from PS3.5 H.3.1, bytes kept as decoded by ISO_IR 6
+    let name = "Yamada^Tarou=\u{1b}$B;3ED\u{1b}(B^\u{1b}$BB@O:\u{1b}(B";
+    assert!(kinds(&object_with(Some("\\ISO 2022 IR 87"), name)).is_empty());
+}
+
+#[test]
+fn upper_half_bytes_without_escape_are_flagged() {
+    // Shift-JIS bytes in a value declared as ISO 2022 kanji
+    let name = "Yamada^Tarou=\u{0092}\u{00B8}\u{0093}\u{00EC}";
+    let obj = object_with(Some("\\ISO 2022 IR 87"), name);
+    assert_eq!(kinds(&obj), vec![FindingKind::MissingEscape]);
+}
+
+#[test]
+fn cyrillic_needs_escape_when_not_first_set() {
+    let without = object_with(Some("\\ISO 2022 IR 144"), "\u{00BB}\u{00E3}\u{00D1}");
+    assert_eq!(kinds(&without), vec![FindingKind::MissingEscape]);
+
+    let with = object_with(Some("\\ISO 2022 IR 144"), "\u{1b}-L\u{00BB}\u{00E3}\u{00D1}");
+    assert!(kinds(&with).is_empty());
+}
+
+#[test]
+fn escape_for_undeclared_set_is_flagged() {
+    let obj = object_with(Some("\\ISO 2022 IR 87"), "\u{1b}-L\u{00BB}");
+    assert_eq!(kinds(&obj), vec![FindingKind::InvalidCharacters]);
+}
+
+#[test]
+fn sequence_items_are_checked() {
+    let item = object_with(None, "M\u{00FC}ller");
+    let mut obj = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(
+        tags::OTHER_PATIENT_I_DS_SEQUENCE,
+        VR::SQ,
+        dicom::core::value::DataSetSequence::from(vec![item]),
+    ));
+
+    let findings = find_charset_warnings(&obj);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].name, "OtherPatientIDsSequence[1].PatientName");
+    assert_eq!(findings[0].location, vec!["(0010,1002)", "Item #1", "(0010,0010)"]);
+}
+
+#[test]
+fn invalid_utf8_in_a_parsed_file_is_flagged() {
+    let path = file_with_byte("utf8.dcm", "ISO_IR 192", "M#ller^Hans", 0xFC);
+    let obj = open_file(&path).unwrap();
+    let findings = find_charset_warnings(&obj);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].kind, FindingKind::InvalidCharacters);
+    assert_eq!(findings[0].name, "PatientName");
+}
+
+#[test]
+fn c1_bytes_in_a_parsed_latin1_file_are_flagged() {
+    let path = file_with_byte("latin1-c1.dcm", "ISO_IR 100", "M#ller^Hans", 0x85);
+    let obj = open_file(&path).unwrap();
+    assert_eq!(kinds(&obj), vec![FindingKind::InvalidCharacters]);
+
+    let path = file_with_byte("latin1.dcm", "ISO_IR 100", "M#ller^Hans", 0xFC);
+    assert!(kinds(&open_file(&path).unwrap()).is_empty());
+}
+
+#[test]
+fn characters_outside_the_declared_set_are_flagged() {
+    // Cyrillic cannot be encoded in Latin-1
+    let obj = object_with(Some("ISO_IR 100"), "\u{0418}\u{0432}\u{0430}\u{043D}");
+    assert_eq!(kinds(&obj), vec![FindingKind::InvalidCharacters]);
+}
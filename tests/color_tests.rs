@@ -0,0 +1,220 @@
+use dcr::dicom::{expand_segments, FramePixels, PaletteLut};
+use dicom::core::value::PrimitiveValue;
+use dicom::core::{DataElement, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::{FileMetaTableBuilder, InMemDicomObject};
+use dicom_pixeldata::image::RgbImage;
+use dicom_pixeldata::PixelDecoder;
+
+/// An uncompressed 8-bit image of one row
+fn image(
+    photometric: &str,
+    samples_per_pixel: u16,
+    columns: u16,
+    pixels: Vec<u8>,
+) -> InMemDicomObject {
+    let mut obj = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(
+        tags::SOP_CLASS_UID,
+        VR::UI,
+        uids::SECONDARY_CAPTURE_IMAGE_STORAGE,
+    ));
+    obj.put(DataElement::new(tags::SOP_INSTANCE_UID, VR::UI, "1.2.3.4"));
+    obj.put(DataElement::new(
+        tags::SAMPLES_PER_PIXEL,
+        VR::US,
+        PrimitiveValue::from(samples_per_pixel),
+    ));
+    obj.put(DataElement::new(
+        tags::PHOTOMETRIC_INTERPRETATION,
+        VR::CS,
+        photometric,
+    ));
+    obj.put(DataElement::new(
+        tags::ROWS,
+        VR::US,
+        PrimitiveValue::from(1u16),
+    ));
+    obj.put(DataElement::new(
+        tags::COLUMNS,
+        VR::US,
+        PrimitiveValue::from(columns),
+    ));
+    for (tag, value) in [
+        (tags::BITS_ALLOCATED, 8u16),
+        (tags::BITS_STORED, 8),
+        (tags::HIGH_BIT, 7),
+        (tags::PIXEL_REPRESENTATION, 0),
+        (tags::PLANAR_CONFIGURATION, 0),
+    ] {
+        obj.put(DataElement::new(tag, VR::US, PrimitiveValue::from(value)));
+    }
+    obj.put(DataElement::new(
+        tags::PIXEL_DATA,
+        VR::OB,
+        PrimitiveValue::from(pixels),
+    ));
+    obj
+}
+
+fn render(obj: InMemDicomObject) -> RgbImage {
+    let palette = PaletteLut::from_obj(&obj);
+    let file = obj
+        .with_meta(FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN))
+        .unwrap();
+    let data = file.decode_pixel_data().unwrap();
+    match FramePixels::from_decoded(&data, 0, palette.as_ref()).unwrap() {
+        FramePixels::Color(image) => image,
+        FramePixels::Monochrome(_) => panic!("expected a color frame"),
+    }
+}
+
+fn colors(image: &RgbImage) -> Vec<[u8; 3]> {
+    image.pixels().map(|p| p.0).collect()
+}
+
+fn assert_close(actual: [u8; 3], expected: [u8; 3]) {
+    let close = actual
+        .iter()
+        .zip(expected)
+        .all(|(a, e)| (*a as i16 - e as i16).abs() <= 2);
+    assert!(close, "{actual:?} is not close to {expected:?}");
+}
+
+/// 16-bit little endian LUT words
+fn words(values: &[u16]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+#[test]
+fn planar_rgb_is_interleaved() {
+    let mut obj = image("RGB", 3, 2, vec![255, 0, 0, 255, 0, 10]);
+    obj.put(DataElement::new(
+        tags::PLANAR_CONFIGURATION,
+        VR::US,
+        PrimitiveValue::from(1u16),
+    ));
+    assert_eq!(colors(&render(obj)), [[255, 0, 0], [0, 255, 10]]);
+}
+
+#[test]
+fn ybr_full_converts_to_rgb() {
+    let image = render(image("YBR_FULL", 3, 2, vec![76, 85, 255, 128, 128, 128]));
+    let colors = colors(&image);
+    assert_close(colors[0], [254, 0, 0]);
+    assert_eq!(colors[1], [128, 128, 128]);
+}
+
+#[test]
+fn ybr_full_422_shares_chroma_between_pixel_pairs() {
+    // Y1 Y2 Cb Cr: black and white, then two reds
+    let pixels = vec![0, 255, 128, 128, 76, 76, 85, 255];
+    let colors = colors(&render(image("YBR_FULL_422", 3, 4, pixels)));
+    assert_eq!(colors[0], [0, 0, 0]);
+    assert_eq!(colors[1], [255, 255, 255]);
+    assert_close(colors[2], [254, 0, 0]);
+    assert_close(colors[3], [254, 0, 0]);
+}
+
+#[test]
+fn palette_color_maps_through_the_lut() {
+    let mut obj = image("PALETTE COLOR", 1, 3, vec![5, 6, 7]);
+    let channels = [
+        (
+            tags::RED_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR,
+            tags::RED_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+            [65535, 0, 0],
+        ),
+        (
+            tags::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR,
+            tags::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+            [0, 65535, 0],
+        ),
+        (
+            tags::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR,
+            tags::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+            [0, 32768, 65535],
+        ),
+    ];
+    for (descriptor, data, entries) in channels {
+        obj.put(DataElement::new(
+            descriptor,
+            VR::US,
+            PrimitiveValue::from([3u16, 5, 16]),
+        ));
+        obj.put(DataElement::new(
+            data,
+            VR::OW,
+            PrimitiveValue::from(words(&entries)),
+        ));
+    }
+    assert_eq!(
+        colors(&render(obj)),
+        [[255, 0, 0], [0, 255, 128], [0, 0, 255]]
+    );
+}
+
+#[test]
+fn segmented_palettes_expand_discrete_linear_and_indirect_segments() {
+    assert_eq!(
+        expand_segments(&[0, 1, 0, 1, 4, 400]),
+        Some(vec![0, 100, 200, 300, 400])
+    );
+    // The indirect segment repeats the discrete one at byte offset 0
+    assert_eq!(
+        expand_segments(&[0, 2, 10, 20, 2, 1, 0, 0]),
+        Some(vec![10, 20, 10, 20])
+    );
+    assert_eq!(expand_segments(&[1, 4, 400]), None);
+    assert_eq!(expand_segments(&[7, 1]), None);
+}
+
+#[test]
+fn segmented_palette_color_images_render() {
+    let mut obj = image("PALETTE COLOR", 1, 2, vec![0, 4]);
+    let channels = [
+        (
+            tags::RED_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR,
+            tags::SEGMENTED_RED_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+        ),
+        (
+            tags::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR,
+            tags::SEGMENTED_GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+        ),
+        (
+            tags::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR,
+            tags::SEGMENTED_BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+        ),
+    ];
+    for (descriptor, data) in channels {
+        obj.put(DataElement::new(
+            descriptor,
+            VR::US,
+            PrimitiveValue::from([5u16, 0, 16]),
+        ));
+        // A ramp from black to white over 5 entries
+        obj.put(DataElement::new(
+            data,
+            VR::OW,
+            PrimitiveValue::from(words(&[0, 1, 0, 1, 4, 65535])),
+        ));
+    }
+    assert_eq!(colors(&render(obj)), [[0, 0, 0], [255, 255, 255]]);
+}
+
+#[test]
+fn zero_rescale_slope_is_read_as_one() {
+    let mut obj = image("MONOCHROME2", 1, 3, vec![0, 10, 20]);
+    obj.put(DataElement::new(tags::RESCALE_SLOPE, VR::DS, "0"));
+    obj.put(DataElement::new(tags::RESCALE_INTERCEPT, VR::DS, "-100"));
+    let file = obj
+        .with_meta(FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN))
+        .unwrap();
+    let data = file.decode_pixel_data().unwrap();
+    let FramePixels::Monochrome(frame) = FramePixels::from_decoded(&data, 0, None).unwrap() else {
+        panic!("expected a grayscale frame");
+    };
+    assert_eq!(frame.slope, 1.0);
+    assert_eq!(frame.values, [-100.0, -90.0, -80.0]);
+    assert_eq!(frame.stored_value(0, 2), Some(20.0));
+}
@@ -0,0 +1,129 @@
+use dcr::app::state::document::DocumentView;
+use dcr::dicom::{extract_tags, EncapsulatedDocument};
+use dicom::core::value::PrimitiveValue;
+use dicom::core::{DataElement, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::{open_file, FileMetaTableBuilder, InMemDicomObject};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Encapsulated document object, its data padded to an even length
+fn document_object(mime: &str, data: &[u8]) -> InMemDicomObject {
+    let mut padded = data.to_vec();
+    if padded.len() % 2 == 1 {
+        padded.push(0);
+    }
+    InMemDicomObject::from_element_iter([
+        DataElement::new(tags::SOP_CLASS_UID, VR::UI, uids::ENCAPSULATED_PDF_STORAGE),
+        DataElement::new(tags::SOP_INSTANCE_UID, VR::UI, "1.2.3.4"),
+        DataElement::new(tags::DOCUMENT_TITLE, VR::ST, "Lab report"),
+        DataElement::new(tags::MIME_TYPE_OF_ENCAPSULATED_DOCUMENT, VR::LO, mime),
+        DataElement::new(
+            tags::ENCAPSULATED_DOCUMENT_LENGTH,
+            VR::UL,
+            PrimitiveValue::from(data.len() as u32),
+        ),
+        DataElement::new(
+            tags::ENCAPSULATED_DOCUMENT,
+            VR::OB,
+            PrimitiveValue::from(padded),
+        ),
+    ])
+}
+
+/// A PDF with a Flate encoded page and an uncompressed one
+fn pdf() -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(
+            b"BT /F1 12 Tf 72 700 Td (Hello \\(world\\)) Tj 0 -14 Td [(Sec) -300 (ond)] TJ ET",
+        )
+        .unwrap();
+    let compressed = encoder.finish().unwrap();
+    let mut pdf = b"%PDF-1.4\n4 0 obj\n<< /Length 0 /Filter /FlateDecode >>\nstream\n".to_vec();
+    pdf.extend(compressed);
+    pdf.extend(b"\nendstream\nendobj\n5 0 obj\n<< /Length 0 >>\nstream\r\nBT <54686972> Tj ET\nendstream\nendobj\n%%EOF");
+    pdf
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dcr-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn pdf_text_comes_from_its_content_streams() {
+    let document =
+        EncapsulatedDocument::from_obj(&document_object("application/pdf", &pdf())).unwrap();
+    assert_eq!(document.data, pdf());
+    assert_eq!(document.extension(), "pdf");
+    assert_eq!(
+        document.text().unwrap(),
+        ["Hello (world)", "Sec ond", "Thir"]
+    );
+}
+
+#[test]
+fn cda_narrative_is_split_into_blocks() {
+    let cda = "<?xml version=\"1.0\"?><ClinicalDocument xmlns=\"urn:hl7-org:v3\">\
+        <title>Discharge</title><component><section><title>Findings</title><text>\
+        <paragraph>No  acute\n   findings &amp; stable.</paragraph>\
+        <list><item>One</item><item>Two</item></list></text></section></component>\
+        </ClinicalDocument>";
+    let document =
+        EncapsulatedDocument::from_obj(&document_object("text/XML", cda.as_bytes())).unwrap();
+    assert_eq!(document.extension(), "xml");
+    assert_eq!(
+        document.text().unwrap(),
+        [
+            "Discharge",
+            "Findings",
+            "No acute findings & stable.",
+            "One",
+            "Two"
+        ]
+    );
+
+    let model = EncapsulatedDocument::from_obj(&document_object("model/stl", b"solid")).unwrap();
+    assert_eq!(model.extension(), "stl");
+    assert!(model.text().is_err());
+    assert!(EncapsulatedDocument::from_obj(&InMemDicomObject::new_empty()).is_none());
+}
+
+#[test]
+fn documents_are_saved_under_the_dicom_file_name() {
+    let dir = temp_dir("document");
+    let path = dir.join("report.dcm");
+    document_object("application/pdf", &pdf())
+        .with_meta(FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN))
+        .unwrap()
+        .write_to_file(&path)
+        .unwrap();
+
+    // The tag table describes the document instead of listing its bytes
+    let tags = extract_tags(&open_file(&path).unwrap());
+    let element = tags.iter().find(|t| t.tag == "(0042,0011)").unwrap();
+    assert_eq!(
+        element.value,
+        format!("<application/pdf document, {} bytes>", pdf().len())
+    );
+
+    let mut view = DocumentView::open(&path).unwrap();
+    assert_eq!(view.title(), "Lab report (application/pdf)");
+    view.scroll_by(10);
+    assert_eq!(view.scroll, 2);
+    assert_eq!(view.save_dir(), dir);
+    let saved = view.save(&dir);
+    view.report(&saved);
+    let saved = saved.unwrap();
+    assert_eq!(saved, dir.join("report.pdf"));
+    assert_eq!(std::fs::read(&saved).unwrap(), pdf());
+    assert_eq!(
+        view.notice.as_deref(),
+        Some(format!("Saved {}", saved.display()).as_str())
+    );
+}
@@ -0,0 +1,251 @@
+use dcr::app::App;
+use dcr::dicom::{dose_summary, report_dose, DoseSource};
+use dicom::core::value::{DataSetSequence, PrimitiveValue};
+use dicom::core::{DataElement, Tag, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::{FileMetaTableBuilder, InMemDicomObject};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+fn item(elements: Vec<DataElement<InMemDicomObject>>) -> InMemDicomObject {
+    InMemDicomObject::from_element_iter(elements)
+}
+
+fn sequence(tag: Tag, items: Vec<InMemDicomObject>) -> DataElement<InMemDicomObject> {
+    DataElement::new(tag, VR::SQ, DataSetSequence::from(items))
+}
+
+fn code(tag: Tag, value: &str, meaning: &str) -> DataElement<InMemDicomObject> {
+    sequence(
+        tag,
+        vec![item(vec![
+            DataElement::new(tags::CODE_VALUE, VR::SH, value),
+            DataElement::new(tags::CODING_SCHEME_DESIGNATOR, VR::SH, "DCM"),
+            DataElement::new(tags::CODE_MEANING, VR::LO, meaning),
+        ])],
+    )
+}
+
+fn content_item(
+    value_type: &str,
+    concept: &str,
+    mut elements: Vec<DataElement<InMemDicomObject>>,
+) -> InMemDicomObject {
+    elements.extend([
+        DataElement::new(tags::RELATIONSHIP_TYPE, VR::CS, "CONTAINS"),
+        DataElement::new(tags::VALUE_TYPE, VR::CS, value_type),
+        code(tags::CONCEPT_NAME_CODE_SEQUENCE, concept, concept),
+    ]);
+    item(elements)
+}
+
+fn container(concept: &str, children: Vec<InMemDicomObject>) -> InMemDicomObject {
+    content_item(
+        "CONTAINER",
+        concept,
+        vec![sequence(tags::CONTENT_SEQUENCE, children)],
+    )
+}
+
+fn num(concept: &str, value: &str) -> InMemDicomObject {
+    content_item(
+        "NUM",
+        concept,
+        vec![sequence(
+            tags::MEASURED_VALUE_SEQUENCE,
+            vec![item(vec![DataElement::new(
+                tags::NUMERIC_VALUE,
+                VR::DS,
+                value,
+            )])],
+        )],
+    )
+}
+
+/// CT acquisition of a dose report with its event UID, protocol and dose
+fn acquisition(uid: &str, protocol: &str, ctdi_vol: &str, dlp: &str) -> InMemDicomObject {
+    container(
+        "113819",
+        vec![
+            content_item(
+                "TEXT",
+                "125203",
+                vec![DataElement::new(tags::TEXT_VALUE, VR::UT, protocol)],
+            ),
+            content_item(
+                "UIDREF",
+                "113769",
+                vec![DataElement::new(tags::UID, VR::UI, uid)],
+            ),
+            container(
+                "113829",
+                vec![
+                    num("113830", ctdi_vol),
+                    num("113838", dlp),
+                    content_item(
+                        "CODE",
+                        "113835",
+                        vec![code(
+                            tags::CONCEPT_CODE_SEQUENCE,
+                            "113691",
+                            "IEC Body Dosimetry Phantom",
+                        )],
+                    ),
+                ],
+            ),
+        ],
+    )
+}
+
+/// An X-Ray Radiation Dose SR with two CT acquisitions and the study total
+fn report() -> InMemDicomObject {
+    let mut root = container(
+        "113701",
+        vec![
+            container("113811", vec![num("113813", "650")]),
+            acquisition("1.2.3.1", "Head, routine", "40.5", "600"),
+            acquisition("1.2.3.2", "Scout", "0.1", "5"),
+        ],
+    );
+    root.put(DataElement::new(tags::STUDY_INSTANCE_UID, VR::UI, "1.2.3"));
+    root
+}
+
+#[test]
+fn dose_reports_list_their_events_and_total() {
+    let summary = report_dose(&report()).unwrap();
+    assert_eq!(summary.source, DoseSource::Report);
+    assert_eq!(summary.events.len(), 2);
+    let head = &summary.events[0];
+    assert_eq!(head.uid.as_deref(), Some("1.2.3.1"));
+    assert_eq!(head.protocol.as_deref(), Some("Head, routine"));
+    assert_eq!(head.ctdi_vol, Some(40.5));
+    assert_eq!(head.dlp, Some(600.0));
+    assert_eq!(head.phantom.as_deref(), Some("IEC Body Dosimetry Phantom"));
+    // The report's own total wins over the sum of its events
+    assert_eq!(summary.total_dlp(), Some(650.0));
+    assert!(summary
+        .lines()
+        .last()
+        .unwrap()
+        .contains("650.0 mGy·cm over 2 event(s)"));
+
+    assert!(report_dose(&container("113701", Vec::new())).is_none());
+}
+
+#[test]
+fn dose_csv_has_a_row_per_event_and_a_total() {
+    let csv = report_dose(&report()).unwrap().csv();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        rows,
+        [
+            "study_instance_uid,irradiation_event_uid,protocol,ctdivol_mgy,dlp_mgy_cm,phantom,source",
+            "1.2.3,1.2.3.1,\"Head, routine\",40.50,600.00,IEC Body Dosimetry Phantom,report",
+            "1.2.3,1.2.3.2,Scout,0.10,5.00,IEC Body Dosimetry Phantom,report",
+            "1.2.3,TOTAL,,,650.00,,report",
+        ]
+    );
+}
+
+/// Writes CT slice headers with the given CTDIvol into `dir`
+fn write_slices(
+    dir: &std::path::Path,
+    series: &str,
+    study: &str,
+    positions: &[f64],
+    ctdi_vol: f64,
+) {
+    for (index, z) in positions.iter().enumerate() {
+        item(vec![
+            DataElement::new(tags::SOP_CLASS_UID, VR::UI, uids::CT_IMAGE_STORAGE),
+            DataElement::new(tags::SOP_INSTANCE_UID, VR::UI, format!("{series}.{index}")),
+            DataElement::new(tags::STUDY_INSTANCE_UID, VR::UI, study),
+            DataElement::new(tags::SERIES_INSTANCE_UID, VR::UI, series),
+            DataElement::new(tags::MODALITY, VR::CS, "CT"),
+            DataElement::new(tags::PROTOCOL_NAME, VR::LO, format!("Protocol {series}")),
+            DataElement::new(tags::IMAGE_POSITION_PATIENT, VR::DS, format!("0\\0\\{z}")),
+            DataElement::new(tags::IMAGE_ORIENTATION_PATIENT, VR::DS, "1\\0\\0\\0\\1\\0"),
+            DataElement::new(tags::PIXEL_SPACING, VR::DS, "1\\1"),
+            DataElement::new(tags::SLICE_THICKNESS, VR::DS, "5"),
+            DataElement::new(tags::CTD_IVOL, VR::FD, PrimitiveValue::from(ctdi_vol)),
+        ])
+        .with_meta(FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN))
+        .unwrap()
+        .write_to_file(dir.join(format!("{series}-{index}.dcm")))
+        .unwrap();
+    }
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dcr-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn ct_images_estimate_the_dose_of_their_study() {
+    let dir = temp_dir("dose-images");
+    write_slices(&dir, "1.2.3.10", "1.2.3", &[0.0, 5.0, 10.0], 10.0);
+    write_slices(&dir, "1.2.3.20", "1.2.3", &[100.0, 105.0], 4.0);
+    write_slices(&dir, "1.2.3.30", "9.9.9", &[0.0, 500.0], 99.0);
+
+    let summary = dose_summary(&dir.join("1.2.3.10-0.dcm"), &AtomicBool::new(false)).unwrap();
+    assert_eq!(summary.source, DoseSource::Images);
+    assert_eq!(summary.events.len(), 2);
+    let first = &summary.events[0];
+    assert_eq!(first.protocol.as_deref(), Some("Protocol 1.2.3.10"));
+    assert_eq!(first.ctdi_vol, Some(10.0));
+    // 10 mm between the outer slices plus one slice thickness
+    assert_eq!(first.dlp, Some(15.0));
+    assert_eq!(summary.events[1].dlp, Some(4.0));
+    assert_eq!(summary.total_dlp(), Some(19.0));
+    assert!(summary.csv().ends_with("1.2.3,TOTAL,,,19.00,,images\n"));
+}
+
+#[test]
+fn dose_needs_ct_images_or_a_report() {
+    let dir = temp_dir("dose-mr");
+    let path = dir.join("mr.dcm");
+    item(vec![
+        DataElement::new(tags::SOP_CLASS_UID, VR::UI, uids::MR_IMAGE_STORAGE),
+        DataElement::new(tags::SOP_INSTANCE_UID, VR::UI, "1.2.3.4"),
+        DataElement::new(tags::MODALITY, VR::CS, "MR"),
+    ])
+    .with_meta(FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN))
+    .unwrap()
+    .write_to_file(&path)
+    .unwrap();
+    assert!(dose_summary(&path, &AtomicBool::new(false)).is_err());
+}
+
+#[test]
+fn dose_is_read_once_per_study_folder() {
+    let dir = temp_dir("dose-cache");
+    write_slices(&dir, "1.2.3.10", "1.2.3", &[0.0, 5.0], 10.0);
+
+    let mut app = App::new_explorer(None);
+    app.toggle_dose_panel();
+    app.load_dicom_file(&dir.join("1.2.3.10-0.dcm"));
+    // The folder is read on a worker
+    assert!(app.is_dose_pending());
+    let started = Instant::now();
+    while app.dose().is_none() && started.elapsed() < Duration::from_secs(10) {
+        app.tick_preview_debounce();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(app.dose().unwrap().as_ref().unwrap().events.len(), 1);
+
+    // Another slice of the study gets the summary without a new scan
+    app.load_dicom_file(&dir.join("1.2.3.10-1.dcm"));
+    assert!(!app.is_dose_pending());
+    assert_eq!(
+        app.dose().unwrap().as_ref().unwrap().total_dlp(),
+        Some(10.0)
+    );
+
+    app.toggle_dose_panel();
+    assert!(app.dose().is_none());
+}
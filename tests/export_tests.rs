@@ -0,0 +1,75 @@
+use dcr::dicom::{export_image, parse_frame_range, save_frames, ExportOptions, Voi};
+use dicom_pixeldata::image::{self, AnimationDecoder, RgbImage};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn fixture_path(filename: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("dicom")
+        .join(filename)
+}
+
+fn output_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("dcr-export-tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+#[test]
+fn frame_ranges_are_one_based_and_inclusive() {
+    assert_eq!(parse_frame_range(None, 3), Ok(vec![0, 1, 2]));
+    assert_eq!(parse_frame_range(Some("2"), 3), Ok(vec![1]));
+    assert_eq!(parse_frame_range(Some("2-3"), 3), Ok(vec![1, 2]));
+    assert!(parse_frame_range(Some("0"), 3).is_err());
+    assert!(parse_frame_range(Some("3-2"), 3).is_err());
+    assert!(parse_frame_range(Some("2-4"), 3).is_err());
+    assert!(parse_frame_range(Some("two"), 3).is_err());
+}
+
+#[test]
+fn single_frames_export_as_png_with_the_window() {
+    let output = output_path("ct-lung.png");
+    let options = ExportOptions {
+        voi: Some(Voi::window("Lung", -600.0, 1500.0)),
+        ..ExportOptions::default()
+    };
+    assert_eq!(
+        export_image(&fixture_path("ct-tap.dcm"), &output, &options),
+        Ok(1)
+    );
+    let image = image::open(&output).unwrap();
+    assert_eq!((image.width(), image.height()), (512, 512));
+}
+
+#[test]
+fn several_frames_need_a_gif() {
+    let frames = vec![RgbImage::new(2, 2), RgbImage::new(2, 2)];
+    let interval = Duration::from_millis(40);
+    assert!(save_frames(&frames, interval, &output_path("frames.png")).is_err());
+    assert!(save_frames(&frames, interval, &output_path("frames.bmp")).is_err());
+
+    let gif = output_path("frames.gif");
+    save_frames(&frames, interval, &gif).unwrap();
+    let decoder =
+        image::codecs::gif::GifDecoder::new(BufReader::new(File::open(&gif).unwrap())).unwrap();
+    let decoded = decoder.into_frames().collect_frames().unwrap();
+    assert_eq!(decoded.len(), 2);
+    assert_eq!(
+        Duration::from(decoded[0].delay()),
+        Duration::from_millis(40)
+    );
+}
+
+#[test]
+fn missing_files_fail_to_export() {
+    let result = export_image(
+        &fixture_path("missing.dcm"),
+        &output_path("missing.png"),
+        &ExportOptions::default(),
+    );
+    assert!(result.unwrap_err().starts_with("Failed to open DICOM file"));
+}
@@ -0,0 +1,169 @@
+use dcr::app::App;
+use dcr::dicom::{DicomTag, FunctionalGroups};
+use dcr::validation::{SopClass, ValidationResult};
+use dicom::core::value::{DataSetSequence, PrimitiveValue};
+use dicom::core::{DataElement, Tag, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// A functional group macro holding one item
+fn group(tag: Tag, elements: Vec<DataElement<InMemDicomObject>>) -> DataElement<InMemDicomObject> {
+    DataElement::new(
+        tag,
+        VR::SQ,
+        DataSetSequence::from(vec![InMemDicomObject::from_element_iter(elements)]),
+    )
+}
+
+fn sequence(tag: Tag, items: Vec<InMemDicomObject>) -> DataElement<InMemDicomObject> {
+    DataElement::new(tag, VR::SQ, DataSetSequence::from(items))
+}
+
+/// Per-frame item of a two-dimensional stack: position and frame content
+fn frame(index: u32) -> InMemDicomObject {
+    InMemDicomObject::from_element_iter([
+        group(
+            tags::FRAME_CONTENT_SEQUENCE,
+            vec![DataElement::new(
+                tags::DIMENSION_INDEX_VALUES,
+                VR::UL,
+                PrimitiveValue::from([1u32, index + 1]),
+            )],
+        ),
+        group(
+            tags::PLANE_POSITION_SEQUENCE,
+            vec![DataElement::new(
+                tags::IMAGE_POSITION_PATIENT,
+                VR::DS,
+                PrimitiveValue::F64(vec![0.0, 0.0, index as f64 * 5.0].into()),
+            )],
+        ),
+    ])
+}
+
+/// Enhanced MR-like object with three frames, its pixel measures and
+/// orientation shared, a per-frame orientation on the last frame
+fn enhanced() -> InMemDicomObject {
+    let mut last = frame(2);
+    last.put(group(
+        tags::PLANE_ORIENTATION_SEQUENCE,
+        vec![DataElement::new(
+            tags::IMAGE_ORIENTATION_PATIENT,
+            VR::DS,
+            "0\\1\\0\\0\\0\\-1",
+        )],
+    ));
+    let shared = InMemDicomObject::from_element_iter([
+        group(
+            tags::PIXEL_MEASURES_SEQUENCE,
+            vec![
+                DataElement::new(tags::PIXEL_SPACING, VR::DS, "0.5\\0.5"),
+                DataElement::new(tags::SLICE_THICKNESS, VR::DS, "5"),
+            ],
+        ),
+        group(
+            tags::PLANE_ORIENTATION_SEQUENCE,
+            vec![DataElement::new(
+                tags::IMAGE_ORIENTATION_PATIENT,
+                VR::DS,
+                "1\\0\\0\\0\\1\\0",
+            )],
+        ),
+    ]);
+    let dimension = |pointer: Tag, label: Option<&str>| {
+        let mut item = InMemDicomObject::from_element_iter([DataElement::new(
+            tags::DIMENSION_INDEX_POINTER,
+            VR::AT,
+            PrimitiveValue::Tags(vec![pointer].into()),
+        )]);
+        if let Some(label) = label {
+            item.put(DataElement::new(
+                tags::DIMENSION_DESCRIPTION_LABEL,
+                VR::LO,
+                label,
+            ));
+        }
+        item
+    };
+    InMemDicomObject::from_element_iter([
+        sequence(
+            tags::DIMENSION_INDEX_SEQUENCE,
+            vec![
+                dimension(tags::STACK_ID, None),
+                dimension(tags::IN_STACK_POSITION_NUMBER, Some("Slice")),
+            ],
+        ),
+        sequence(tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE, vec![shared]),
+        sequence(
+            tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+            vec![frame(0), frame(1), last],
+        ),
+    ])
+}
+
+fn row<'a>(rows: &'a [DicomTag], tag: &str) -> &'a DicomTag {
+    rows.iter().find(|r| r.tag == tag).unwrap()
+}
+
+#[test]
+fn shared_and_per_frame_groups_are_merged_per_frame() {
+    let groups = FunctionalGroups::from_obj(&enhanced()).unwrap();
+    assert_eq!(groups.frame_count(), 3);
+
+    let rows = groups.rows(1);
+    let labels: Vec<&str> = rows.iter().map(|r| r.tag.as_str()).collect();
+    // Frame content, plane position, plane orientation, pixel measures
+    assert_eq!(
+        labels,
+        [
+            "(0020,9157)",
+            "(0020,0032)",
+            "(0020,0037)",
+            "(0018,0050)",
+            "(0028,0030)"
+        ]
+    );
+    assert!(rows.iter().all(|r| r.depth == 0));
+    let indices = row(&rows, "(0020,9157)");
+    assert_eq!(indices.name, "DimensionIndexValues");
+    assert_eq!(indices.value, "1\\2 (StackID=1, Slice=2)");
+    assert_eq!(row(&rows, "(0020,0032)").value, "0\\0\\5");
+    let orientation = row(&rows, "(0020,0037)");
+    assert_eq!(orientation.name, "ImageOrientationPatient (shared)");
+    assert_eq!(orientation.value, "1\\0\\0\\0\\1\\0");
+    assert_eq!(row(&rows, "(0028,0030)").name, "PixelSpacing (shared)");
+
+    // The last frame's own orientation replaces the shared one
+    let rows = groups.rows(2);
+    let orientation = row(&rows, "(0020,0037)");
+    assert_eq!(orientation.name, "ImageOrientationPatient");
+    assert_eq!(orientation.value, "0\\1\\0\\0\\0\\-1");
+
+    assert!(FunctionalGroups::from_obj(&InMemDicomObject::new_empty()).is_none());
+}
+
+#[test]
+fn frame_groups_view_follows_the_preview_frame() {
+    let mut app = App::new(
+        Vec::new(),
+        "enhanced.dcm".to_string(),
+        ValidationResult::Valid,
+        SopClass::Unknown,
+    );
+    app.meta.functional_groups = FunctionalGroups::from_obj(&enhanced());
+    app.toggle_frame_groups();
+    assert_eq!(app.frame_groups, Some(0));
+    assert_eq!(app.tags.visible[0].value, "1\\1 (StackID=1, Slice=1)");
+
+    app.tags.scroll_down(1);
+    app.preview.frame = 2;
+    app.tick_preview_debounce();
+    assert_eq!(app.frame_groups, Some(2));
+    assert_eq!(app.tags.visible[0].value, "1\\3 (StackID=1, Slice=3)");
+    assert_eq!(app.tags.table_state.selected(), Some(1));
+
+    app.toggle_frame_groups();
+    assert_eq!(app.frame_groups, None);
+    assert!(app.tag_view.is_none());
+    assert!(app.tags.visible.is_empty());
+}
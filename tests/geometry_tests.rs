@@ -0,0 +1,142 @@
+use dcr::dicom::{frame_planes, suv_factor, ImagePlane, ValueUnits};
+use dicom::core::value::{DataSetSequence, PrimitiveValue};
+use dicom::core::{DataElement, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::{open_file, InMemDicomObject};
+use std::path::PathBuf;
+
+fn fixture_path(filename: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("dicom")
+        .join(filename)
+}
+
+fn axial_plane() -> ImagePlane {
+    ImagePlane {
+        position: [-100.0, -50.0, 20.0],
+        row_direction: [1.0, 0.0, 0.0],
+        column_direction: [0.0, 1.0, 0.0],
+        spacing: [0.5, 0.8],
+    }
+}
+
+#[test]
+fn patient_point_uses_row_and_column_spacing() {
+    let plane = axial_plane();
+    assert_eq!(plane.patient_point(0.0, 0.0), [-100.0, -50.0, 20.0]);
+    // Columns step along the row direction by the column spacing
+    assert_eq!(plane.patient_point(10.0, 10.0), [-92.0, -45.0, 20.0]);
+    assert_eq!(plane.normal(), [0.0, 0.0, 1.0]);
+}
+
+#[test]
+fn ct_fixture_has_a_single_plane() {
+    let obj = open_file(fixture_path("ct-tap.dcm")).unwrap();
+    let planes = frame_planes(&obj);
+    assert_eq!(planes.len(), 1);
+    assert_eq!(planes[0].normal().map(f64::abs), [0.0, 0.0, 1.0]);
+}
+
+#[test]
+fn enhanced_planes_merge_shared_and_per_frame_groups() {
+    let item = |elements: Vec<DataElement<InMemDicomObject>>| {
+        InMemDicomObject::from_element_iter(elements)
+    };
+    let sequence = |tag, items: Vec<InMemDicomObject>| {
+        DataElement::new(tag, VR::SQ, DataSetSequence::from(items))
+    };
+
+    let shared = item(vec![
+        sequence(
+            tags::PLANE_ORIENTATION_SEQUENCE,
+            vec![item(vec![DataElement::new(
+                tags::IMAGE_ORIENTATION_PATIENT,
+                VR::DS,
+                PrimitiveValue::from([1.0, 0.0, 0.0, 0.0, 1.0, 0.0]),
+            )])],
+        ),
+        sequence(
+            tags::PIXEL_MEASURES_SEQUENCE,
+            vec![item(vec![DataElement::new(
+                tags::PIXEL_SPACING,
+                VR::DS,
+                PrimitiveValue::from([1.0, 1.0]),
+            )])],
+        ),
+    ]);
+    let per_frame: Vec<InMemDicomObject> = [[0.0, 0.0, 0.0], [0.0, 0.0, 5.0]]
+        .into_iter()
+        .map(|position| {
+            item(vec![sequence(
+                tags::PLANE_POSITION_SEQUENCE,
+                vec![item(vec![DataElement::new(
+                    tags::IMAGE_POSITION_PATIENT,
+                    VR::DS,
+                    PrimitiveValue::from(position),
+                )])],
+            )])
+        })
+        .collect();
+
+    let obj = item(vec![
+        DataElement::new(tags::NUMBER_OF_FRAMES, VR::IS, "2"),
+        sequence(tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE, vec![shared]),
+        sequence(tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE, per_frame),
+    ]);
+
+    let planes = frame_planes(&obj);
+    assert_eq!(planes.len(), 2);
+    assert_eq!(planes[1].position, [0.0, 0.0, 5.0]);
+    assert_eq!(planes[1].spacing, [1.0, 1.0]);
+}
+
+#[test]
+fn ct_values_are_hounsfield_units() {
+    let obj = open_file(fixture_path("ct-tap.dcm")).unwrap();
+    let units = ValueUnits::from_obj(&obj);
+    assert_eq!(units.unit.as_deref(), Some("HU"));
+    assert_eq!(units.suv_factor, None);
+}
+
+#[test]
+fn suv_factor_decays_the_injected_dose() {
+    let mut radiopharmaceutical = InMemDicomObject::new_empty();
+    radiopharmaceutical.put(DataElement::new(
+        tags::RADIONUCLIDE_TOTAL_DOSE,
+        VR::DS,
+        "370000000",
+    ));
+    radiopharmaceutical.put(DataElement::new(
+        tags::RADIONUCLIDE_HALF_LIFE,
+        VR::DS,
+        "6586.2",
+    ));
+    radiopharmaceutical.put(DataElement::new(
+        tags::RADIOPHARMACEUTICAL_START_TIME,
+        VR::TM,
+        "100000",
+    ));
+
+    let mut obj = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(tags::MODALITY, VR::CS, "PT"));
+    obj.put(DataElement::new(tags::UNITS, VR::CS, "BQML"));
+    obj.put(DataElement::new(tags::DECAY_CORRECTION, VR::CS, "START"));
+    obj.put(DataElement::new(tags::PATIENT_WEIGHT, VR::DS, "70"));
+    obj.put(DataElement::new(tags::SERIES_TIME, VR::TM, "114949.2"));
+    obj.put(DataElement::new(
+        tags::RADIOPHARMACEUTICAL_INFORMATION_SEQUENCE,
+        VR::SQ,
+        DataSetSequence::from(vec![radiopharmaceutical]),
+    ));
+
+    // One half-life after injection, half of the dose remains
+    let factor = suv_factor(&obj).unwrap();
+    let expected = 70_000.0 / 185_000_000.0;
+    assert!((factor - expected).abs() / expected < 1e-3, "{factor}");
+    assert_eq!(ValueUnits::from_obj(&obj).unit.as_deref(), Some("Bq/ml"));
+
+    obj.put(DataElement::new(tags::DECAY_CORRECTION, VR::CS, "NONE"));
+    assert_eq!(suv_factor(&obj), None);
+}
@@ -0,0 +1,67 @@
+use dcr::dicom::{pixel_padding, Histogram, MonochromeFrame};
+use dicom::core::value::PrimitiveValue;
+use dicom::core::{DataElement, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// Stored values 0..10 with a rescale intercept of -100, the first two
+/// pixels padded with -2000
+fn frame() -> MonochromeFrame {
+    let mut values: Vec<f32> = (0..10).map(|v| v as f32 - 100.0).collect();
+    values[0] = -2100.0;
+    values[1] = -2100.0;
+    MonochromeFrame {
+        rows: 2,
+        columns: 5,
+        values,
+        slope: 1.0,
+        intercept: -100.0,
+        invert: false,
+    }
+}
+
+#[test]
+fn values_are_binned_over_their_range() {
+    let histogram = Histogram::from_frame(&frame(), None, 4).unwrap();
+    assert_eq!((histogram.min, histogram.max), (-2100.0, -91.0));
+    assert_eq!(histogram.counts, [2, 0, 0, 8]);
+    assert_eq!(histogram.padding, 0);
+}
+
+#[test]
+fn padding_is_counted_apart() {
+    let histogram = Histogram::from_frame(&frame(), Some((-2000.0, -2000.0)), 4).unwrap();
+    assert_eq!((histogram.min, histogram.max), (-98.0, -91.0));
+    assert_eq!(histogram.mean, -94.5);
+    assert_eq!(histogram.counts.iter().sum::<u64>(), 8);
+    assert_eq!(histogram.padding_percent(), 20.0);
+    assert_eq!(histogram.points()[0], (-97.125, 2.0));
+}
+
+#[test]
+fn flat_frames_fill_a_single_bin() {
+    let flat = MonochromeFrame {
+        values: vec![5.0; 10],
+        ..frame()
+    };
+    let histogram = Histogram::from_frame(&flat, None, 4).unwrap();
+    assert_eq!(histogram.counts, [10, 0, 0, 0]);
+}
+
+#[test]
+fn padding_range_comes_from_value_and_limit() {
+    let mut obj = InMemDicomObject::new_empty();
+    assert_eq!(pixel_padding(&obj), None);
+    obj.put(DataElement::new(
+        tags::PIXEL_PADDING_VALUE,
+        VR::SS,
+        PrimitiveValue::from(-2000i16),
+    ));
+    assert_eq!(pixel_padding(&obj), Some((-2000.0, -2000.0)));
+    obj.put(DataElement::new(
+        tags::PIXEL_PADDING_RANGE_LIMIT,
+        VR::SS,
+        PrimitiveValue::from(-3000i16),
+    ));
+    assert_eq!(pixel_padding(&obj), Some((-3000.0, -2000.0)));
+}
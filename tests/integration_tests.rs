@@ -1,5 +1,13 @@
+use dcr::app::App;
 use dcr::dicom::{load_dicom_file, DicomTag};
-use dcr::validation::{get_sop_class, validate_type1_fields, SopClass, ValidationResult};
+use dcr::validation::{
+    find_missing_type1_fields, find_unexpected_attributes, find_warnings, get_sop_class, validate_type1_fields,
+    validate_type1_fields_from_obj, FindingKind, Severity, SopClass, ValidationResult,
+};
+use dicom::core::value::DataSetSequence;
+use dicom::core::{DataElement, Tag, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::{open_file, InMemDicomObject};
 use std::path::PathBuf;
 
 fn fixture_path(filename: &str) -> PathBuf {
@@ -415,3 +423,329 @@ fn test_load_non_dicom_file_error() {
     let result = load_dicom_file(&path);
     assert!(result.is_err(), "Should return error for non-DICOM file");
 }
+
+#[test]
+fn test_complete_ct_file_has_no_nested_findings() {
+    let obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    let findings = find_missing_type1_fields(&obj).expect("CT validation should be applicable");
+    assert!(findings.is_empty(), "Unexpected findings: {:?}", findings);
+}
+
+#[test]
+fn test_missing_attribute_in_sequence_item_is_reported() {
+    let mut obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    let mut items: Vec<InMemDicomObject> = obj
+        .element(tags::PROCEDURE_CODE_SEQUENCE)
+        .expect("Fixture should have ProcedureCodeSequence")
+        .items()
+        .expect("ProcedureCodeSequence should have items")
+        .to_vec();
+    items[0].remove_element(tags::CODE_MEANING);
+    obj.put(DataElement::new(
+        tags::PROCEDURE_CODE_SEQUENCE,
+        VR::SQ,
+        DataSetSequence::from(items),
+    ));
+
+    let findings = find_missing_type1_fields(&obj).expect("CT validation should be applicable");
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].name, "ProcedureCodeSequence[1].CodeMeaning");
+    assert_eq!(findings[0].location, vec!["(0008,1032)", "Item #1"]);
+
+    match validate_type1_fields_from_obj(&obj) {
+        ValidationResult::Invalid(missing) => {
+            assert_eq!(missing, vec!["ProcedureCodeSequence[1].CodeMeaning"]);
+        }
+        other => panic!("Expected ValidationResult::Invalid, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_empty_type1_sequence_is_reported_missing() {
+    let mut obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    obj.put(DataElement::new(
+        tags::SOP_CLASS_UID,
+        VR::UI,
+        dicom::dictionary_std::uids::ENHANCED_CT_IMAGE_STORAGE,
+    ));
+    obj.put(DataElement::new(
+        tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+        VR::SQ,
+        DataSetSequence::<InMemDicomObject>::empty(),
+    ));
+
+    let findings = find_missing_type1_fields(&obj).expect("Enhanced CT validation should apply");
+    assert!(findings
+        .iter()
+        .any(|f| f.name == "PerFrameFunctionalGroupsSequence"));
+
+    // The same sequence with an item is present
+    obj.put(DataElement::new(
+        tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+        VR::SQ,
+        DataSetSequence::from(vec![InMemDicomObject::new_empty()]),
+    ));
+    let findings = find_missing_type1_fields(&obj).expect("Enhanced CT validation should apply");
+    assert!(!findings
+        .iter()
+        .any(|f| f.name == "PerFrameFunctionalGroupsSequence"));
+}
+
+#[test]
+fn test_functional_group_macro_checked_per_frame_and_shared() {
+    let mut obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    obj.put(DataElement::new(
+        tags::SOP_CLASS_UID,
+        VR::UI,
+        dicom::dictionary_std::uids::ENHANCED_CT_IMAGE_STORAGE,
+    ));
+
+    let position = InMemDicomObject::from_element_iter([DataElement::new(
+        tags::IMAGE_POSITION_PATIENT,
+        VR::DS,
+        "0\\0\\0",
+    )]);
+    let frame_with_position = InMemDicomObject::from_element_iter([DataElement::new(
+        tags::PLANE_POSITION_SEQUENCE,
+        VR::SQ,
+        DataSetSequence::from(vec![position]),
+    )]);
+    let frame_without_position = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(
+        tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+        VR::SQ,
+        DataSetSequence::from(vec![frame_with_position, frame_without_position]),
+    ));
+
+    let findings = find_missing_type1_fields(&obj).expect("Enhanced CT validation should apply");
+    let position_finding = findings
+        .iter()
+        .find(|f| f.name.ends_with("PlanePositionSequence"))
+        .expect("Frame 2 should be missing PlanePositionSequence");
+    assert_eq!(
+        position_finding.name,
+        "PerFrameFunctionalGroupsSequence[2].PlanePositionSequence"
+    );
+    assert_eq!(position_finding.location, vec!["(5200,9230)", "Item #2"]);
+    assert!(
+        !findings.iter().any(|f| f.name == "ImagePositionPatient"),
+        "Enhanced objects should not require a top-level ImagePositionPatient"
+    );
+}
+
+#[test]
+fn test_retired_attributes_reported_as_warnings() {
+    let obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    let warnings = find_warnings(&obj);
+
+    let dose_saving = warnings
+        .iter()
+        .find(|f| f.name == "EstimatedDoseSaving")
+        .expect("EstimatedDoseSaving is retired");
+    assert_eq!(dose_saving.kind, FindingKind::Retired);
+    assert_eq!(dose_saving.severity(), Severity::Warning);
+    assert_eq!(dose_saving.location, vec!["(0018,9324)"]);
+
+    assert!(
+        warnings
+            .iter()
+            .any(|f| f.name == "GenericGroupLength" && f.location == vec!["(0008,0000)"]),
+        "Group length elements are retired"
+    );
+    assert!(
+        !warnings.iter().any(|f| f.kind == FindingKind::UnreservedPrivate),
+        "All private blocks in the fixture have a creator"
+    );
+
+    match validate_type1_fields_from_obj(&obj) {
+        ValidationResult::Valid => {}
+        other => panic!("Warnings must not invalidate the file, got {:?}", other),
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_every_retired_dictionary_entry_is_reported() {
+    let mut obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    // A curve group (50xx) entry and one outside any hand-picked list
+    obj.put(DataElement::new(
+        dicom::core::Tag(0x5002, 0x0005),
+        VR::US,
+        dicom::core::PrimitiveValue::from(1_u16),
+    ));
+    obj.put(DataElement::new(tags::ACQUISITIONS_IN_STUDY, VR::IS, "2"));
+
+    let retired: Vec<String> = find_warnings(&obj)
+        .into_iter()
+        .filter(|f| f.kind == FindingKind::Retired)
+        .map(|f| f.name)
+        .collect();
+    assert!(retired.contains(&"CurveDimensions".to_string()));
+    assert!(retired.contains(&"AcquisitionsInStudy".to_string()));
+    assert!(!retired.contains(&"Modality".to_string()));
+}
+
+#[test]
+fn test_private_element_without_creator_is_reported() {
+    let mut obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    obj.remove_element(dicom::core::Tag(0x0019, 0x0010));
+
+    let unreserved: Vec<_> = find_warnings(&obj)
+        .into_iter()
+        .filter(|f| f.kind == FindingKind::UnreservedPrivate)
+        .collect();
+    assert_eq!(unreserved.len(), 5);
+    assert_eq!(unreserved[0].name, "(0019,1090)");
+    assert_eq!(unreserved[0].location, vec!["(0019,1090)"]);
+}
+
+#[test]
+fn test_private_creator_inside_sequence_item_reserves_block() {
+    let obj = open_file(fixture_path("ct-tap-with-missing-data.dcm"))
+        .expect("Failed to open CT file");
+    assert!(
+        !find_warnings(&obj)
+            .iter()
+            .any(|f| f.kind == FindingKind::UnreservedPrivate),
+        "Private items carry their own creator"
+    );
+}
+
+#[test]
+fn test_fixture_text_matches_declared_charset() {
+    let obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    let findings = dcr::validation::find_charset_warnings(&obj);
+    assert!(findings.is_empty(), "Unexpected charset findings: {:?}", findings);
+}
+
+#[test]
+fn test_ct_fixture_has_no_unexpected_attributes() {
+    let obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    let findings = find_unexpected_attributes(&obj);
+    assert!(findings.is_empty(), "Unexpected attributes: {:?}", findings);
+}
+
+#[test]
+fn test_mr_attribute_in_ct_is_unexpected() {
+    let mut obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    obj.put(DataElement::new(tags::REPETITION_TIME, VR::DS, "500"));
+
+    let findings = find_unexpected_attributes(&obj);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].kind, FindingKind::Unexpected);
+    assert_eq!(findings[0].name, "RepetitionTime (MR Image)");
+    assert_eq!(findings[0].location, vec!["(0018,0080)"]);
+}
+
+#[test]
+fn test_dictionary_attribute_outside_every_module_is_unexpected() {
+    let mut obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    obj.put(DataElement::new(tags::RT_PLAN_LABEL, VR::SH, "PLAN"));
+    obj.put(DataElement::new(Tag(0x0009, 0x1001), VR::LO, "VENDOR"));
+
+    let findings = find_unexpected_attributes(&obj);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].kind, FindingKind::Unexpected);
+    assert_eq!(findings[0].name, "RTPlanLabel (not in the IOD)");
+    assert_eq!(findings[0].location, vec!["(300A,0002)"]);
+}
+
+#[test]
+fn test_classic_ct_attributes_unexpected_in_enhanced_ct() {
+    let mut obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    obj.put(DataElement::new(
+        tags::SOP_CLASS_UID,
+        VR::UI,
+        dicom::dictionary_std::uids::ENHANCED_CT_IMAGE_STORAGE,
+    ));
+
+    let names: Vec<String> = find_unexpected_attributes(&obj)
+        .into_iter()
+        .map(|f| f.name)
+        .collect();
+    assert!(names.contains(&"KVP (CT Image)".to_string()));
+    assert!(names.contains(&"ImagePositionPatient (Image Plane)".to_string()));
+}
+
+#[test]
+fn test_type1_attributes_of_a_foreign_module_are_unexpected() {
+    let mut obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    obj.put(DataElement::new(tags::PIXEL_PRESENTATION, VR::CS, "MONOCHROME"));
+
+    let names: Vec<String> = find_unexpected_attributes(&obj)
+        .into_iter()
+        .map(|f| f.name)
+        .collect();
+    assert_eq!(names, vec!["PixelPresentation (Enhanced CT Image)"]);
+
+    // The CT Image module's own Type 1 attributes are out of place in Enhanced CT
+    obj.put(DataElement::new(
+        tags::SOP_CLASS_UID,
+        VR::UI,
+        dicom::dictionary_std::uids::ENHANCED_CT_IMAGE_STORAGE,
+    ));
+    let names: Vec<String> = find_unexpected_attributes(&obj)
+        .into_iter()
+        .map(|f| f.name)
+        .collect();
+    assert!(names.contains(&"RescaleSlope (CT Image)".to_string()));
+    assert!(!names.iter().any(|n| n.starts_with("PixelPresentation")));
+}
+
+#[test]
+fn test_enhanced_mr_type1_attributes_come_from_its_modules() {
+    let mut obj = open_file(fixture_path("ct-tap.dcm")).expect("Failed to open CT file");
+    obj.put(DataElement::new(
+        tags::SOP_CLASS_UID,
+        VR::UI,
+        dicom::dictionary_std::uids::ENHANCED_MR_IMAGE_STORAGE,
+    ));
+
+    let names: Vec<String> = find_missing_type1_fields(&obj)
+        .expect("Enhanced MR validation should apply")
+        .into_iter()
+        .map(|f| f.name)
+        .collect();
+    for name in [
+        "NumberOfFrames",
+        "PerFrameFunctionalGroupsSequence",
+        "PixelPresentation",
+        "ComplexImageComponent",
+        "AcquisitionContrast",
+    ] {
+        assert!(names.contains(&name.to_string()), "{name} missing from {names:?}");
+    }
+    assert!(!names.contains(&"RescaleSlope".to_string()));
+    assert!(!names.contains(&"ImagePositionPatient".to_string()));
+}
+
+#[test]
+fn test_apply_obj_matches_explorer_load() {
+    let path = fixture_path("ct-tap.dcm");
+    let obj = open_file(&path).expect("Failed to open CT file");
+
+    // Direct and diff mode start from an empty App and apply the object
+    let mut direct = App::new(
+        Vec::new(),
+        "ct-tap.dcm".to_string(),
+        ValidationResult::NotApplicable,
+        SopClass::Unknown,
+    );
+    direct.meta.finding_cursor = 3;
+    direct.meta.apply_obj(&obj);
+
+    let mut explorer = App::new_explorer(None);
+    explorer.load_dicom_file(&path);
+
+    for meta in [&direct.meta, &explorer.meta] {
+        assert!(matches!(meta.sop_class, SopClass::Ct));
+        assert_eq!(meta.finding_cursor, 0);
+        assert!(meta.content_tree.is_none());
+        assert!(meta.functional_groups.is_none());
+    }
+    assert_eq!(direct.meta.findings, explorer.meta.findings);
+    assert_eq!(
+        format!("{:?}", direct.meta.validation_result),
+        format!("{:?}", explorer.meta.validation_result)
+    );
+}
@@ -0,0 +1,110 @@
+use dcr::dicom::{
+    measurements_json, roi_stats, Calibration, Measurement, MonochromeFrame, Shape, SpacingSource,
+};
+use dicom::core::value::PrimitiveValue;
+use dicom::core::{DataElement, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// A 4x4 frame holding 0..16, row by row
+fn ramp() -> MonochromeFrame {
+    MonochromeFrame {
+        rows: 4,
+        columns: 4,
+        values: (0..16).map(|v| v as f32).collect(),
+        slope: 1.0,
+        intercept: 0.0,
+        invert: false,
+    }
+}
+
+#[test]
+fn rectangle_stats_cover_the_box_inclusively() {
+    let calibration = Calibration {
+        spacing: [0.5, 2.0],
+        source: SpacingSource::PixelSpacing,
+    };
+    let stats = roi_stats(
+        &ramp(),
+        Shape::Rectangle,
+        [2, 2],
+        [1, 1],
+        Some(&calibration),
+    )
+    .unwrap();
+    // 5, 6, 9, 10
+    assert_eq!(stats.pixels, 4);
+    assert_eq!((stats.min, stats.max, stats.mean), (5.0, 10.0, 7.5));
+    assert!((stats.std_dev - 2.0615528).abs() < 1e-6);
+    assert_eq!(stats.area_mm2, Some(4.0));
+}
+
+#[test]
+fn ellipse_leaves_out_the_corners() {
+    let stats = roi_stats(&ramp(), Shape::Ellipse, [0, 0], [3, 3], None).unwrap();
+    assert_eq!(stats.pixels, 12);
+    assert_eq!(stats.area_mm2, None);
+}
+
+#[test]
+fn distances_use_row_and_column_spacing() {
+    let calibration = Calibration {
+        spacing: [2.0, 1.0],
+        source: SpacingSource::PixelSpacing,
+    };
+    let measurement = Measurement::distance(0, [0, 0], [3, 4], Some(&calibration));
+    assert_eq!(measurement.describe(), "Distance 7.21 mm");
+    let uncalibrated = Measurement::distance(0, [0, 0], [3, 4], None);
+    assert_eq!(uncalibrated.describe(), "Distance 5.0 px");
+}
+
+#[test]
+fn imager_pixel_spacing_comes_with_a_warning() {
+    let mut obj = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(
+        tags::IMAGER_PIXEL_SPACING,
+        VR::DS,
+        PrimitiveValue::from([0.2, 0.3]),
+    ));
+    let calibration = Calibration::from_obj(&obj, 0).unwrap();
+    assert_eq!(calibration.spacing, [0.2, 0.3]);
+    assert!(calibration.warning().is_some());
+
+    obj.put(DataElement::new(
+        tags::PIXEL_SPACING,
+        VR::DS,
+        PrimitiveValue::from([0.5, 0.5]),
+    ));
+    let calibration = Calibration::from_obj(&obj, 0).unwrap();
+    assert_eq!(calibration.source, SpacingSource::PixelSpacing);
+    assert_eq!(calibration.warning(), None);
+}
+
+#[test]
+fn export_lists_each_measurement_with_its_type() {
+    let calibration = Calibration {
+        spacing: [0.2, 0.2],
+        source: SpacingSource::ImagerPixelSpacing,
+    };
+    let measurements = [
+        Measurement::roi(
+            &ramp(),
+            0,
+            Shape::Rectangle,
+            [0, 0],
+            [1, 1],
+            Some(&calibration),
+        )
+        .unwrap(),
+        Measurement::distance(0, [0, 0], [0, 3], Some(&calibration)),
+    ];
+    let json = measurements_json("image.dcm", Some(&calibration), &measurements).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["file"], "image.dcm");
+    assert!(value["warning"].is_string());
+    assert_eq!(value["measurements"][0]["type"], "roi");
+    assert_eq!(value["measurements"][0]["shape"], "rectangle");
+    assert_eq!(value["measurements"][0]["stats"]["pixels"], 4);
+    assert_eq!(value["measurements"][1]["type"], "distance");
+    assert_eq!(value["measurements"][1]["start"], serde_json::json!([0, 0]));
+}
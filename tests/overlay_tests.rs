@@ -0,0 +1,95 @@
+use dcr::dicom::{corner_text, overlay_planes};
+use dicom::core::value::PrimitiveValue;
+use dicom::core::{DataElement, Tag, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// A 4x4 overlay in group 6002 marking its diagonal, placed one row down
+/// and two columns right of the image origin
+fn with_overlay() -> InMemDicomObject {
+    let mut obj = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(
+        Tag(0x6002, 0x0010),
+        VR::US,
+        PrimitiveValue::from(4u16),
+    ));
+    obj.put(DataElement::new(
+        Tag(0x6002, 0x0011),
+        VR::US,
+        PrimitiveValue::from(4u16),
+    ));
+    obj.put(DataElement::new(Tag(0x6002, 0x0040), VR::CS, "G"));
+    obj.put(DataElement::new(
+        Tag(0x6002, 0x0050),
+        VR::SS,
+        PrimitiveValue::from([2i16, 3]),
+    ));
+    obj.put(DataElement::new(
+        Tag(0x6002, 0x0100),
+        VR::US,
+        PrimitiveValue::from(1u16),
+    ));
+    obj.put(DataElement::new(Tag(0x6002, 0x1500), VR::LO, "Marker"));
+    // Bits 0, 5, 10 and 15
+    obj.put(DataElement::new(
+        Tag(0x6002, 0x3000),
+        VR::OB,
+        PrimitiveValue::from(vec![0b0010_0001u8, 0b1000_0100]),
+    ));
+    obj
+}
+
+#[test]
+fn overlay_bits_are_placed_at_the_origin() {
+    let overlays = overlay_planes(&with_overlay());
+    assert_eq!(overlays.len(), 1);
+    let overlay = &overlays[0];
+    assert_eq!(overlay.group, 0x6002);
+    assert_eq!(overlay.label.as_deref(), Some("Marker"));
+
+    assert!(overlay.is_set(0, 1, 2));
+    assert!(overlay.is_set(0, 4, 5));
+    assert!(!overlay.is_set(0, 1, 3));
+    assert!(!overlay.is_set(0, 0, 0));
+    // A single-frame overlay applies to every frame
+    assert!(overlay.is_set(7, 2, 3));
+}
+
+#[test]
+fn multi_frame_overlays_follow_the_image_frame_origin() {
+    let mut obj = with_overlay();
+    obj.put(DataElement::new(Tag(0x6002, 0x0015), VR::IS, "1"));
+    obj.put(DataElement::new(
+        Tag(0x6002, 0x0051),
+        VR::US,
+        PrimitiveValue::from(3u16),
+    ));
+    let overlay = &overlay_planes(&obj)[0];
+    assert!(!overlay.is_set(0, 1, 2));
+    assert!(overlay.is_set(2, 1, 2));
+    assert!(!overlay.is_set(3, 1, 2));
+}
+
+#[test]
+fn corners_show_patient_study_and_series() {
+    let mut obj = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(tags::PATIENT_NAME, VR::PN, "Doe^Jane"));
+    obj.put(DataElement::new(tags::PATIENT_ID, VR::LO, "12345"));
+    obj.put(DataElement::new(
+        tags::PATIENT_BIRTH_DATE,
+        VR::DA,
+        "19800102",
+    ));
+    obj.put(DataElement::new(tags::PATIENT_SEX, VR::CS, "F"));
+    obj.put(DataElement::new(tags::STUDY_DATE, VR::DA, "20240301"));
+    obj.put(DataElement::new(tags::STUDY_TIME, VR::TM, "134501.25"));
+    obj.put(DataElement::new(tags::MODALITY, VR::CS, "CT"));
+    obj.put(DataElement::new(tags::SERIES_NUMBER, VR::IS, "3"));
+    obj.put(DataElement::new(tags::SERIES_DESCRIPTION, VR::LO, "Axial"));
+
+    let corners = corner_text(&obj);
+    assert_eq!(corners.top_left, ["Doe Jane", "ID: 12345", "1980-01-02 F"]);
+    assert_eq!(corners.top_right, ["2024-03-01 13:45:01"]);
+    assert_eq!(corners.bottom_left, ["CT Se: 3 Axial"]);
+    assert!(corners.bottom_right.is_empty());
+}
@@ -0,0 +1,244 @@
+use dcr::dicom::{
+    presentation_states_for, DisplayTransform, FolderHeaders, GraphicType, PresentationState,
+    VoiTransform,
+};
+use dicom::core::value::{DataSetSequence, PrimitiveValue};
+use dicom::core::{DataElement, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::{FileMetaTableBuilder, InMemDicomObject};
+use std::fs;
+use std::sync::atomic::AtomicBool;
+
+const IMAGE_UID: &str = "1.2.3.4.1";
+
+fn item(elements: Vec<DataElement<InMemDicomObject>>) -> InMemDicomObject {
+    InMemDicomObject::from_element_iter(elements)
+}
+
+fn sequence(tag: dicom::core::Tag, items: Vec<InMemDicomObject>) -> DataElement<InMemDicomObject> {
+    DataElement::new(tag, VR::SQ, DataSetSequence::from(items))
+}
+
+fn image_reference(uid: &str, frames: Option<&str>) -> InMemDicomObject {
+    let mut elements = vec![DataElement::new(
+        tags::REFERENCED_SOP_INSTANCE_UID,
+        VR::UI,
+        uid,
+    )];
+    if let Some(frames) = frames {
+        elements.push(DataElement::new(
+            tags::REFERENCED_FRAME_NUMBER,
+            VR::IS,
+            frames,
+        ));
+    }
+    item(elements)
+}
+
+/// A presentation state for `IMAGE_UID` that shows its 50x40 top-left
+/// corner rotated by 90°, with a window, a circle, a polyline on frame 2
+/// and a text in display units
+fn gsps() -> InMemDicomObject {
+    item(vec![
+        DataElement::new(
+            tags::SOP_CLASS_UID,
+            VR::UI,
+            uids::GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE,
+        ),
+        DataElement::new(tags::SOP_INSTANCE_UID, VR::UI, "1.2.3.4.9"),
+        DataElement::new(tags::CONTENT_LABEL, VR::CS, "REPORTED"),
+        sequence(
+            tags::REFERENCED_SERIES_SEQUENCE,
+            vec![item(vec![sequence(
+                tags::REFERENCED_IMAGE_SEQUENCE,
+                vec![image_reference(IMAGE_UID, None)],
+            )])],
+        ),
+        sequence(
+            tags::DISPLAYED_AREA_SELECTION_SEQUENCE,
+            vec![item(vec![
+                DataElement::new(
+                    tags::DISPLAYED_AREA_TOP_LEFT_HAND_CORNER,
+                    VR::SL,
+                    PrimitiveValue::from([1i32, 1]),
+                ),
+                DataElement::new(
+                    tags::DISPLAYED_AREA_BOTTOM_RIGHT_HAND_CORNER,
+                    VR::SL,
+                    PrimitiveValue::from([50i32, 40]),
+                ),
+            ])],
+        ),
+        DataElement::new(tags::IMAGE_ROTATION, VR::US, PrimitiveValue::from(90u16)),
+        DataElement::new(tags::IMAGE_HORIZONTAL_FLIP, VR::CS, "N"),
+        sequence(
+            tags::SOFTCOPY_VOILUT_SEQUENCE,
+            vec![item(vec![
+                DataElement::new(tags::WINDOW_CENTER, VR::DS, "40"),
+                DataElement::new(tags::WINDOW_WIDTH, VR::DS, "400"),
+            ])],
+        ),
+        sequence(
+            tags::GRAPHIC_ANNOTATION_SEQUENCE,
+            vec![
+                item(vec![
+                    DataElement::new(tags::GRAPHIC_LAYER, VR::CS, "LAYER1"),
+                    sequence(
+                        tags::GRAPHIC_OBJECT_SEQUENCE,
+                        vec![item(vec![
+                            DataElement::new(tags::GRAPHIC_ANNOTATION_UNITS, VR::CS, "PIXEL"),
+                            DataElement::new(tags::GRAPHIC_TYPE, VR::CS, "CIRCLE"),
+                            DataElement::new(
+                                tags::GRAPHIC_DATA,
+                                VR::FL,
+                                PrimitiveValue::from([10.0f32, 10.0, 13.0, 14.0]),
+                            ),
+                        ])],
+                    ),
+                    sequence(
+                        tags::TEXT_OBJECT_SEQUENCE,
+                        vec![item(vec![
+                            DataElement::new(
+                                tags::ANCHOR_POINT_ANNOTATION_UNITS,
+                                VR::CS,
+                                "DISPLAY",
+                            ),
+                            DataElement::new(
+                                tags::ANCHOR_POINT,
+                                VR::FL,
+                                PrimitiveValue::from([0.5f32, 0.25]),
+                            ),
+                            DataElement::new(tags::UNFORMATTED_TEXT_VALUE, VR::ST, "Lesion"),
+                        ])],
+                    ),
+                ]),
+                item(vec![
+                    sequence(
+                        tags::REFERENCED_IMAGE_SEQUENCE,
+                        vec![image_reference(IMAGE_UID, Some("2"))],
+                    ),
+                    sequence(
+                        tags::GRAPHIC_OBJECT_SEQUENCE,
+                        vec![item(vec![
+                            DataElement::new(tags::GRAPHIC_ANNOTATION_UNITS, VR::CS, "PIXEL"),
+                            DataElement::new(tags::GRAPHIC_TYPE, VR::CS, "POLYLINE"),
+                            DataElement::new(
+                                tags::GRAPHIC_DATA,
+                                VR::FL,
+                                PrimitiveValue::from([0.0f32, 0.0, 5.0, 5.0]),
+                            ),
+                        ])],
+                    ),
+                ]),
+                item(vec![
+                    sequence(
+                        tags::REFERENCED_IMAGE_SEQUENCE,
+                        vec![image_reference("9.9.9", None)],
+                    ),
+                    sequence(
+                        tags::TEXT_OBJECT_SEQUENCE,
+                        vec![item(vec![DataElement::new(
+                            tags::UNFORMATTED_TEXT_VALUE,
+                            VR::ST,
+                            "Another image",
+                        )])],
+                    ),
+                ]),
+            ],
+        ),
+    ])
+}
+
+#[test]
+fn settings_that_apply_to_the_image_are_read() {
+    let state = PresentationState::from_obj(&gsps(), IMAGE_UID).unwrap();
+    assert_eq!(state.label, "REPORTED");
+    assert_eq!(state.displayed_area, Some([0.0, 0.0, 50.0, 40.0]));
+    assert_eq!((state.rotation, state.flip), (90, false));
+    match state.voi.map(|voi| voi.transform) {
+        Some(VoiTransform::Window(window)) => {
+            assert_eq!((window.center, window.width), (40.0, 400.0))
+        }
+        other => panic!("unexpected VOI {other:?}"),
+    }
+
+    assert_eq!(state.graphics.len(), 2);
+    assert_eq!(state.graphics[0].kind, GraphicType::Circle);
+    assert!(state.graphics[0].applies_on(0));
+    assert_eq!(state.graphics[1].frames, [1]);
+    assert!(!state.graphics[1].applies_on(0));
+    // The text of the other image is left out
+    assert_eq!(state.texts.len(), 1);
+    assert_eq!(state.texts[0].text, "Lesion");
+
+    let error = PresentationState::from_obj(&gsps(), "1.2.3.4.2").unwrap_err();
+    assert_eq!(
+        error,
+        "The presentation state does not reference this image"
+    );
+}
+
+#[test]
+fn annotations_follow_the_displayed_area_and_rotation() {
+    let state = PresentationState::from_obj(&gsps(), IMAGE_UID).unwrap();
+    let transform = state.transform((512, 512));
+    assert_eq!(transform.size(), (40, 50));
+
+    // The circle has a radius of 5 pixels around (10, 10)
+    let outline = state.graphics[0].outline(&transform);
+    assert_eq!(outline.len(), 65);
+    assert_eq!(outline[0], (15.0, 10.0));
+
+    // Display units are fractions of the rotated displayed area
+    let (x, y) = state.texts[0].anchor(&transform).unwrap();
+    assert!(
+        (x - 12.5).abs() < 1e-9 && (y - 20.0).abs() < 1e-9,
+        "{x} {y}"
+    );
+}
+
+#[test]
+fn transforms_map_both_ways() {
+    for rotation in [0, 90, 180, 270] {
+        for flip in [false, true] {
+            let transform = DisplayTransform {
+                area: [10.0, 20.0, 110.0, 70.0],
+                flip,
+                rotation,
+            };
+            let point = (35.25, 41.5);
+            let (x, y) = transform.to_image(transform.to_display(point));
+            assert!((x - point.0).abs() < 1e-9 && (y - point.1).abs() < 1e-9);
+        }
+    }
+    let transform = DisplayTransform {
+        area: [0.0, 0.0, 4.0, 2.0],
+        flip: true,
+        rotation: 90,
+    };
+    // Flipped, then turned clockwise: the top-left corner ends up bottom-right
+    assert_eq!(transform.to_display((0.0, 0.0)), (2.0, 4.0));
+}
+
+#[test]
+fn presentation_states_are_found_next_to_the_image() {
+    let dir = std::env::temp_dir().join(format!("dcr-{}-gsps", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    gsps()
+        .with_meta(FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN))
+        .unwrap()
+        .write_to_file(dir.join("ps.dcm"))
+        .unwrap();
+    fs::write(dir.join("notes.txt"), "not DICOM").unwrap();
+
+    let image = dir.join("image.dcm");
+    let headers = FolderHeaders::read(&dir, &AtomicBool::new(false)).unwrap();
+    assert!(FolderHeaders::read(&dir, &AtomicBool::new(true)).is_err());
+    assert_eq!(
+        presentation_states_for(&headers, &image, IMAGE_UID),
+        [dir.join("ps.dcm")]
+    );
+    assert!(presentation_states_for(&headers, &image, "1.2.3.4.2").is_empty());
+    fs::remove_dir_all(dir).unwrap();
+}
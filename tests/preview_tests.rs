@@ -0,0 +1,853 @@
+use dcr::app::state::preview::Preview;
+use dcr::dicom::Axis;
+use dicom::core::value::{DataSetSequence, PrimitiveValue};
+use dicom::core::{DataElement, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::{FileMetaTableBuilder, InMemDicomObject};
+use ratatui::layout::Rect;
+use ratatui_image::picker::{Picker, ProtocolType};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+fn fixture_path(filename: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("dicom")
+        .join(filename)
+}
+
+/// Writes a 2x2, 8-bit grayscale image with one flat value per frame
+fn write_multi_frame(name: &str, frames: u8) -> PathBuf {
+    let mut obj = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(
+        tags::SOP_CLASS_UID,
+        VR::UI,
+        uids::MULTI_FRAME_GRAYSCALE_BYTE_SECONDARY_CAPTURE_IMAGE_STORAGE,
+    ));
+    obj.put(DataElement::new(
+        tags::SOP_INSTANCE_UID,
+        VR::UI,
+        "1.2.3.4.5.6",
+    ));
+    obj.put(DataElement::new(
+        tags::SAMPLES_PER_PIXEL,
+        VR::US,
+        PrimitiveValue::from(1u16),
+    ));
+    obj.put(DataElement::new(
+        tags::PHOTOMETRIC_INTERPRETATION,
+        VR::CS,
+        "MONOCHROME2",
+    ));
+    obj.put(DataElement::new(
+        tags::NUMBER_OF_FRAMES,
+        VR::IS,
+        frames.to_string(),
+    ));
+    obj.put(DataElement::new(tags::FRAME_TIME, VR::DS, "40"));
+    obj.put(DataElement::new(
+        tags::ROWS,
+        VR::US,
+        PrimitiveValue::from(2u16),
+    ));
+    obj.put(DataElement::new(
+        tags::COLUMNS,
+        VR::US,
+        PrimitiveValue::from(2u16),
+    ));
+    obj.put(DataElement::new(
+        tags::BITS_ALLOCATED,
+        VR::US,
+        PrimitiveValue::from(8u16),
+    ));
+    obj.put(DataElement::new(
+        tags::BITS_STORED,
+        VR::US,
+        PrimitiveValue::from(8u16),
+    ));
+    obj.put(DataElement::new(
+        tags::HIGH_BIT,
+        VR::US,
+        PrimitiveValue::from(7u16),
+    ));
+    obj.put(DataElement::new(
+        tags::PIXEL_REPRESENTATION,
+        VR::US,
+        PrimitiveValue::from(0u16),
+    ));
+    let pixels: Vec<u8> = (0..frames).flat_map(|f| [f * 10; 4]).collect();
+    obj.put(DataElement::new(
+        tags::PIXEL_DATA,
+        VR::OB,
+        PrimitiveValue::from(pixels),
+    ));
+
+    let file = obj
+        .with_meta(FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN))
+        .unwrap();
+    let path = std::env::temp_dir().join(format!("dcr-{}-{name}.dcm", std::process::id()));
+    file.write_to_file(&path).unwrap();
+    path
+}
+
+/// Starts a background decode and polls it like the event loop does
+fn decode_and_wait(preview: &mut Preview, path: &Path) {
+    preview.decode(Some(path));
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while preview.job.is_some() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(5));
+        preview.poll_decode();
+    }
+}
+
+/// Lets the frames ahead be converted
+fn wait_for_prefetch(preview: &mut Preview) {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while preview.prefetch_job.is_some() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(5));
+        preview.poll_decode();
+    }
+}
+
+/// Lets the folder and segmentation workers finish, running what waited
+/// for them
+fn wait_for_workers(preview: &mut Preview) {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while (preview.folders.is_reading() || preview.segmentation.is_pending())
+        && Instant::now() < deadline
+    {
+        std::thread::sleep(Duration::from_millis(5));
+        preview.poll_decode();
+    }
+}
+
+fn picker_with(protocol: ProtocolType) -> Picker {
+    let mut picker = Picker::from_fontsize((8, 16));
+    picker.set_protocol_type(protocol);
+    picker
+}
+
+#[test]
+fn preview_without_picker_reports_error() {
+    let mut preview = Preview::new(None);
+    preview.decode(Some(&fixture_path("ct-tap.dcm")));
+    assert!(preview.image.is_none());
+    assert!(preview.error.is_some());
+}
+
+#[test]
+fn preview_renders_with_non_kitty_protocols() {
+    for protocol in [ProtocolType::Halfblocks, ProtocolType::Sixel, ProtocolType::Iterm2] {
+        let mut preview = Preview::new(Some(picker_with(protocol)));
+        decode_and_wait(&mut preview, &fixture_path("ct-tap.dcm"));
+        assert!(preview.error.is_none(), "{:?}: {:?}", protocol, preview.error);
+        assert!(preview.image.is_some(), "{:?} should produce an image", protocol);
+    }
+}
+
+#[test]
+fn protocol_name_follows_picker() {
+    let preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    assert_eq!(preview.protocol_name(), Some("half-blocks"));
+    assert_eq!(Preview::new(None).protocol_name(), None);
+}
+
+#[test]
+fn preview_opens_on_the_first_file_window() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &fixture_path("ct-tap.dcm"));
+    assert_eq!(
+        preview.window_description().as_deref(),
+        Some("W:300 L:40 WINDOW1")
+    );
+
+    preview.next_file_window();
+    assert_eq!(
+        preview.window_description().as_deref(),
+        Some("W:1500 L:300 WINDOW2")
+    );
+    preview.next_file_window();
+    assert_eq!(
+        preview.window_description().as_deref(),
+        Some("W:300 L:40 WINDOW1")
+    );
+}
+
+#[test]
+fn presets_and_adjustments_update_the_window() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &fixture_path("ct-tap.dcm"));
+
+    preview.apply_preset(1);
+    assert_eq!(
+        preview.window_description().as_deref(),
+        Some("W:1500 L:-600 Lung")
+    );
+
+    preview.adjust_window(1, 1);
+    assert_eq!(
+        preview.window_description().as_deref(),
+        Some("W:1650 L:-525 Custom")
+    );
+
+    preview.reset_window();
+    assert_eq!(
+        preview.window_description().as_deref(),
+        Some("W:300 L:40 WINDOW1")
+    );
+    assert!(preview.image.is_some());
+}
+
+#[test]
+fn frames_step_and_wrap() {
+    let path = write_multi_frame("frames", 3);
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &path);
+    assert!(preview.error.is_none(), "{:?}", preview.error);
+    assert_eq!(preview.frame_description().as_deref(), Some("Frame 1/3"));
+
+    preview.next_frame();
+    preview.next_frame();
+    assert_eq!(preview.frame_description().as_deref(), Some("Frame 3/3"));
+    preview.next_frame();
+    assert_eq!(preview.frame, 0);
+    preview.previous_frame();
+    assert_eq!(preview.frame, 2);
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn frames_ahead_are_prefetched() {
+    let path = write_multi_frame("prefetch", 8);
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &path);
+    // Converted on a worker, off the UI thread
+    assert!(preview.prefetch_job.is_some());
+    wait_for_prefetch(&mut preview);
+    let mut ahead: Vec<u32> = preview.frames.keys().copied().collect();
+    ahead.sort();
+    assert_eq!(ahead, vec![1, 2, 3, 4]);
+
+    preview.next_frame();
+    assert!(!preview.frames.contains_key(&1));
+    wait_for_prefetch(&mut preview);
+    assert!(preview.frames.contains_key(&5));
+
+    // Showing another file cancels the conversion in flight
+    preview.next_frame();
+    preview.clear();
+    assert!(preview.prefetch_job.is_none());
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn cine_uses_the_file_frame_time() {
+    let path = write_multi_frame("cine", 3);
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &path);
+    assert_eq!(preview.frame_interval, Duration::from_millis(40));
+
+    preview.toggle_cine();
+    assert_eq!(preview.frame_description().as_deref(), Some("Frame 1/3 ▶"));
+    assert!(preview.poll_timeout() <= Duration::from_millis(40));
+    std::thread::sleep(Duration::from_millis(45));
+    preview.tick_cine();
+    assert_eq!(preview.frame, 1);
+
+    preview.toggle_cine();
+    assert!(preview.cine.is_none());
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn single_frame_has_no_counter() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &fixture_path("ct-tap.dcm"));
+    assert_eq!(preview.frame_description(), None);
+    preview.toggle_cine();
+    assert!(preview.cine.is_none());
+}
+
+#[test]
+fn decoding_runs_in_the_background() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    preview.decode(Some(&fixture_path("ct-tap.dcm")));
+    assert!(preview.decoding_for().is_some());
+    assert!(preview.image.is_none());
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while preview.image.is_none() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(5));
+        preview.poll_decode();
+    }
+    assert!(preview.image.is_some());
+    assert!(preview.decoding_for().is_none());
+}
+
+#[test]
+fn clearing_cancels_the_decode_in_flight() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    preview.decode(Some(&fixture_path("ct-tap.dcm")));
+    preview.clear();
+    assert!(preview.decoding_for().is_none());
+
+    std::thread::sleep(Duration::from_millis(200));
+    preview.poll_decode();
+    assert!(preview.image.is_none());
+    assert!(preview.error.is_none());
+}
+
+#[test]
+fn decode_errors_come_back_from_the_worker() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, Path::new("/nonexistent/file.dcm"));
+    assert!(preview.error.as_deref().unwrap().starts_with("Failed to open DICOM file"));
+}
+
+#[test]
+fn inspector_reads_hounsfield_units_and_patient_position() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &fixture_path("ct-tap.dcm"));
+    assert_eq!(preview.inspection(), None);
+
+    preview.toggle_inspector();
+    preview.move_cursor(-6, 0);
+    let inspection = preview.inspection().unwrap();
+    assert_eq!((inspection.row, inspection.column), (250, 256));
+    let stored = inspection.stored.unwrap();
+    assert_eq!(inspection.value, Some(stored - 1024.0));
+    assert_eq!(inspection.unit.as_deref(), Some("HU"));
+    assert!(inspection.patient.is_some());
+    assert!(inspection.describe().starts_with("r250 c256 | SV "));
+
+    preview.move_cursor(-1000, 1000);
+    let inspection = preview.inspection().unwrap();
+    assert_eq!((inspection.row, inspection.column), (0, 511));
+}
+
+#[test]
+fn cells_map_back_to_image_pixels() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &fixture_path("ct-tap.dcm"));
+    // 80x32 cells of 8x16 pixels: the 512x512 image is centered with
+    // 64 pixels, or 8 cells, on each side
+    preview.set_area(Rect::new(10, 5, 80, 32));
+
+    assert!(!preview.inspect_cell(10, 5));
+    assert!(preview.inspect_cell(18, 5));
+    assert_eq!(preview.cursor, Some((8, 4)));
+    assert!(!preview.inspect_cell(82, 5));
+}
+
+#[test]
+fn measurements_are_kept_per_file_and_exported() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    let path = fixture_path("ct-tap.dcm");
+    decode_and_wait(&mut preview, &path);
+
+    preview.cycle_tool();
+    preview.mark_point();
+    preview.move_cursor(3, 3);
+    preview.mark_point();
+    assert_eq!(preview.measure.measurements.len(), 1);
+    assert!(preview.status_lines().iter().any(|line| line.starts_with("Rect min ")));
+
+    preview.clear();
+    assert!(preview.measure.measurements.is_empty());
+    decode_and_wait(&mut preview, &path);
+    assert_eq!(preview.measure.measurements.len(), 1);
+
+    let dir = std::env::temp_dir().join("dcr-measurement-export");
+    std::fs::create_dir_all(&dir).unwrap();
+    let output = preview.export_measurements(&dir).unwrap();
+    assert_eq!(output, dir.join("ct-tap.measurements.json"));
+    assert!(std::fs::read_to_string(&output).unwrap().contains("\"type\": \"roi\""));
+    // Exports go next to the file, reported by their full path
+    assert_eq!(preview.export_dir(), path.parent().unwrap());
+    preview.report_export(&Ok(PathBuf::from("ct-tap.measurements.json")));
+    let notice = preview.notice.clone().unwrap();
+    assert!(notice.starts_with("Saved /") && notice.ends_with("/ct-tap.measurements.json"));
+
+    preview.delete_last_measurement();
+    assert!(preview.measure.measurements.is_empty());
+}
+
+#[test]
+fn corner_text_carries_the_window_when_turned_on() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &fixture_path("ct-tap.dcm"));
+    assert_eq!(preview.corner_text(), None);
+
+    preview.toggle_corners();
+    let corners = preview.corner_text().unwrap();
+    assert!(!corners.top_left.is_empty());
+    assert_eq!(corners.bottom_right, [preview.window_description().unwrap()]);
+
+    preview.clear();
+    assert_eq!(preview.corner_text(), None);
+}
+
+#[test]
+fn histogram_follows_the_displayed_frame() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    let path = write_multi_frame("dcr-histogram-frames.dcm", 3);
+    decode_and_wait(&mut preview, &path);
+    assert_eq!(preview.histogram, None);
+
+    preview.toggle_histogram();
+    assert_eq!(preview.histogram.as_ref().unwrap().mean, 0.0);
+    preview.next_frame();
+    assert_eq!(preview.histogram.as_ref().unwrap().mean, 10.0);
+
+    preview.apply_preset(0);
+    assert_eq!(preview.window_bounds(), Some((0.0, 80.0)));
+}
+
+#[test]
+fn zoom_and_pan_move_the_image_under_the_cells() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &fixture_path("ct-tap.dcm"));
+    preview.set_area(Rect::new(10, 5, 80, 32));
+    assert_eq!(preview.zoom_description().as_deref(), Some("Fit"));
+
+    // Zooming out never goes below fitting the pane
+    preview.zoom_by(0.5);
+    assert_eq!(preview.viewport.zoom, 1.0);
+
+    preview.zoom_by(2.0);
+    assert_eq!(preview.zoom_description().as_deref(), Some("Zoom 2.0x"));
+    assert!(preview.inspect_cell(50, 21));
+    assert_eq!(preview.cursor, Some((260, 258)));
+
+    // At 2x the view reaches 96 pixels past the center sideways
+    preview.pan(100, 0);
+    assert_eq!(preview.viewport.pan, (96.0, 0.0));
+    preview.pan(-4, -2);
+    assert_eq!(preview.viewport.pan, (80.0, -16.0));
+
+    preview.clear();
+    assert_eq!(preview.viewport.zoom, 1.0);
+}
+
+#[test]
+fn dragging_without_a_tool_pans() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &fixture_path("ct-tap.dcm"));
+    preview.set_area(Rect::new(10, 5, 80, 32));
+    preview.zoom_by(2.0);
+
+    assert!(preview.press_cell(50, 21));
+    assert!(preview.drag_cell(48, 20));
+    assert!(preview.release_cell(48, 20));
+    assert_eq!(preview.viewport.pan, (8.0, 8.0));
+    assert_eq!(preview.cursor, None);
+
+    // A click that does not move turns on the inspector
+    assert!(preview.press_cell(50, 21));
+    assert!(preview.release_cell(50, 21));
+    assert!(preview.cursor.is_some());
+}
+
+#[test]
+fn frames_export_from_the_preview() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    let path = write_multi_frame("dcr-export-frames.dcm", 3);
+    decode_and_wait(&mut preview, &path);
+    preview.next_frame();
+
+    let dir = std::env::temp_dir().join("dcr-preview-export");
+    std::fs::create_dir_all(&dir).unwrap();
+    let stem = path.file_stem().unwrap().to_string_lossy();
+    preview.export_frame(&dir);
+    assert_eq!(preview.notice.as_deref(), Some("Exporting frame 2..."));
+    wait_for_export(&mut preview);
+    let png = dir.join(format!("{stem}-frame2.png"));
+    assert!(png.exists());
+    assert_eq!(
+        preview.notice.as_deref(),
+        Some(format!("Saved {}", png.display()).as_str())
+    );
+
+    // Showing another file leaves the export running
+    preview.export_cine(&dir);
+    preview.clear();
+    assert!(preview.export_job.is_some());
+    wait_for_export(&mut preview);
+    let gif = dir.join(format!("{stem}.gif"));
+    assert!(gif.exists());
+    assert_eq!(
+        preview.notice.as_deref(),
+        Some(format!("Saved {}", gif.display()).as_str())
+    );
+}
+
+/// Lets the export worker write its file
+fn wait_for_export(preview: &mut Preview) {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while preview.export_job.is_some() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(5));
+        preview.poll_decode();
+    }
+}
+
+/// Writes an axial series of 4x4, 8-bit slices 3 mm apart into its own
+/// folder, each slice filled with its index times 10
+fn write_series(name: &str, slices: u8) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dcr-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for slice in 0..slices {
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            tags::SOP_CLASS_UID,
+            VR::UI,
+            uids::CT_IMAGE_STORAGE,
+        ));
+        obj.put(DataElement::new(
+            tags::SOP_INSTANCE_UID,
+            VR::UI,
+            format!("1.2.3.4.5.{slice}"),
+        ));
+        obj.put(DataElement::new(
+            tags::SERIES_INSTANCE_UID,
+            VR::UI,
+            "1.2.3.4.5",
+        ));
+        obj.put(DataElement::new(
+            tags::FRAME_OF_REFERENCE_UID,
+            VR::UI,
+            "1.2.3.4.6",
+        ));
+        obj.put(DataElement::new(
+            tags::IMAGE_POSITION_PATIENT,
+            VR::DS,
+            format!("0\\0\\{}", slice as u32 * 3),
+        ));
+        obj.put(DataElement::new(
+            tags::IMAGE_ORIENTATION_PATIENT,
+            VR::DS,
+            "1\\0\\0\\0\\1\\0",
+        ));
+        obj.put(DataElement::new(tags::PIXEL_SPACING, VR::DS, "1\\1"));
+        for (tag, value) in [
+            (tags::SAMPLES_PER_PIXEL, 1u16),
+            (tags::ROWS, 4),
+            (tags::COLUMNS, 4),
+            (tags::BITS_ALLOCATED, 8),
+            (tags::BITS_STORED, 8),
+            (tags::HIGH_BIT, 7),
+            (tags::PIXEL_REPRESENTATION, 0),
+        ] {
+            obj.put(DataElement::new(tag, VR::US, PrimitiveValue::from(value)));
+        }
+        obj.put(DataElement::new(
+            tags::PHOTOMETRIC_INTERPRETATION,
+            VR::CS,
+            "MONOCHROME2",
+        ));
+        obj.put(DataElement::new(
+            tags::PIXEL_DATA,
+            VR::OB,
+            PrimitiveValue::from(vec![slice * 10; 16]),
+        ));
+        obj.with_meta(
+            FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN),
+        )
+        .unwrap()
+        .write_to_file(dir.join(format!("slice{slice}.dcm")))
+        .unwrap();
+    }
+    dir
+}
+
+/// Toggles the reformatted views and polls until the volume is built
+fn build_mpr(preview: &mut Preview) {
+    preview.toggle_mpr();
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while (preview.folders.is_reading() || preview.volume_job.is_some())
+        && Instant::now() < deadline
+    {
+        std::thread::sleep(Duration::from_millis(5));
+        preview.poll_decode();
+    }
+}
+
+#[test]
+fn mpr_views_share_a_crosshair_through_the_series() {
+    let dir = write_series("mpr", 5);
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &dir.join("slice1.dcm"));
+    build_mpr(&mut preview);
+
+    let mpr = preview.mpr.as_ref().expect("volume built");
+    assert_eq!(mpr.point, [2, 2, 1]);
+    assert_eq!(mpr.title(Axis::Slice), "Axial 2/5");
+    assert_eq!(mpr.title(Axis::Row), "Coronal 3/4");
+    assert!(mpr.views.iter().all(|view| view.image.is_some()));
+    assert_eq!(preview.status_lines(), ["(2.0, 2.0, 3.0) mm | 10.0"]);
+
+    preview.step_mpr(2);
+    preview.cycle_mpr_view();
+    preview.move_mpr_point(3, -1);
+    let mpr = preview.mpr.as_ref().unwrap();
+    assert_eq!(mpr.active, Axis::Row);
+    // Three rows per slice: moving down three rows reaches the slice below
+    assert_eq!(mpr.point, [1, 2, 2]);
+
+    preview.set_mpr_areas([
+        Rect::new(0, 0, 10, 10),
+        Rect::new(10, 0, 10, 10),
+        Rect::new(20, 0, 10, 10),
+    ]);
+    assert!(preview.press_cell(25, 5));
+    assert_eq!(preview.mpr.as_ref().unwrap().active, Axis::Column);
+
+    preview.toggle_mpr();
+    assert!(preview.mpr.is_none());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn mpr_is_refused_when_a_slice_cannot_be_placed() {
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &fixture_path("ct-tap.dcm"));
+    build_mpr(&mut preview);
+    assert!(preview.mpr.is_none());
+    // The fixture folder holds a copy of the series without its geometry
+    assert_eq!(
+        preview.notice.as_deref(),
+        Some("ct-tap-with-missing-data.dcm has no image position or orientation")
+    );
+}
+
+#[test]
+fn reference_lines_pin_a_slice_then_its_series() {
+    let dir = write_series("reference", 3);
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &dir.join("slice1.dcm"));
+    assert_eq!(preview.frame_of_reference.as_deref(), Some("1.2.3.4.6"));
+
+    preview.cycle_reference();
+    assert_eq!(
+        preview.notice.as_deref(),
+        Some("Reference line of slice1.dcm")
+    );
+    preview.cycle_reference();
+    assert_eq!(preview.notice.as_deref(), Some("Reading the folder..."));
+    wait_for_workers(&mut preview);
+    assert_eq!(
+        preview.notice.as_deref(),
+        Some("Reference lines of 3 series images")
+    );
+    assert_eq!(preview.reference.planes.len(), 3);
+
+    // The pin outlives the file it came from
+    preview.clear();
+    decode_and_wait(&mut preview, &fixture_path("ct-tap.dcm"));
+    assert_eq!(preview.reference.planes.len(), 3);
+    assert!(preview
+        .status_lines()
+        .contains(&"Reference lines need the same FrameOfReferenceUID".to_string()));
+
+    preview.clear();
+    decode_and_wait(&mut preview, &dir.join("slice1.dcm"));
+    preview.cycle_reference();
+    assert!(preview.reference.planes.is_empty());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// A presentation state for one slice of `write_series`, turning it by 90°
+/// with a window and a text
+fn write_presentation_state(dir: &Path, slice: u8) {
+    let item = |elements: Vec<DataElement<InMemDicomObject>>| {
+        InMemDicomObject::from_element_iter(elements)
+    };
+    let sequence = |tag, items: Vec<InMemDicomObject>| {
+        DataElement::new(tag, VR::SQ, DataSetSequence::from(items))
+    };
+    let reference = item(vec![sequence(
+        tags::REFERENCED_IMAGE_SEQUENCE,
+        vec![item(vec![DataElement::new(
+            tags::REFERENCED_SOP_INSTANCE_UID,
+            VR::UI,
+            format!("1.2.3.4.5.{slice}"),
+        )])],
+    )]);
+    let text = item(vec![
+        DataElement::new(tags::ANCHOR_POINT_ANNOTATION_UNITS, VR::CS, "PIXEL"),
+        DataElement::new(
+            tags::ANCHOR_POINT,
+            VR::FL,
+            PrimitiveValue::from([1.0f32, 1.0]),
+        ),
+        DataElement::new(tags::UNFORMATTED_TEXT_VALUE, VR::ST, "Cyst"),
+    ]);
+    item(vec![
+        DataElement::new(
+            tags::SOP_CLASS_UID,
+            VR::UI,
+            uids::GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE,
+        ),
+        DataElement::new(tags::SOP_INSTANCE_UID, VR::UI, "1.2.3.4.7"),
+        DataElement::new(tags::CONTENT_LABEL, VR::CS, "KEY"),
+        sequence(tags::REFERENCED_SERIES_SEQUENCE, vec![reference]),
+        DataElement::new(tags::IMAGE_ROTATION, VR::US, PrimitiveValue::from(90u16)),
+        sequence(
+            tags::SOFTCOPY_VOILUT_SEQUENCE,
+            vec![item(vec![
+                DataElement::new(tags::WINDOW_CENTER, VR::DS, "20"),
+                DataElement::new(tags::WINDOW_WIDTH, VR::DS, "40"),
+            ])],
+        ),
+        sequence(
+            tags::GRAPHIC_ANNOTATION_SEQUENCE,
+            vec![item(vec![sequence(tags::TEXT_OBJECT_SEQUENCE, vec![text])])],
+        ),
+    ])
+    .with_meta(FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN))
+    .unwrap()
+    .write_to_file(dir.join("ps.dcm"))
+    .unwrap();
+}
+
+#[test]
+fn presentation_states_turn_the_image_and_set_the_window() {
+    let dir = write_series("gsps", 3);
+    write_presentation_state(&dir, 1);
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &dir.join("slice0.dcm"));
+    preview.cycle_presentation();
+    wait_for_workers(&mut preview);
+    assert_eq!(
+        preview.notice.as_deref(),
+        Some("No presentation state references this image")
+    );
+
+    preview.clear();
+    decode_and_wait(&mut preview, &dir.join("slice1.dcm"));
+    // The folder read for slice 0 is searched again without reading it
+    preview.cycle_presentation();
+    assert!(!preview.folders.is_reading());
+    assert_eq!(preview.notice.as_deref(), Some("KEY (ps.dcm)"));
+    assert!(preview.voi.as_ref().unwrap().describe().starts_with("W:40 L:20"));
+    assert!(preview.status_lines().contains(&"GSPS: Cyst".to_string()));
+
+    // 4x4 cells of 8x16 pixels show the image one cell down; turned
+    // clockwise, its top-left corner comes from the bottom-left one
+    preview.set_area(Rect::new(0, 0, 4, 4));
+    assert!(preview.inspect_cell(0, 1));
+    assert_eq!(preview.cursor, Some((3, 1)));
+
+    preview.cycle_presentation();
+    assert_eq!(preview.notice.as_deref(), Some("Presentation state off"));
+    assert!(preview.presentations.applied.is_none());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// An RT structure set over `write_series`: "Lesion" drawn on slice 1,
+/// "Body" on the plane of slice 1 without naming it
+fn write_structure_set(dir: &Path) {
+    let item = |elements: Vec<DataElement<InMemDicomObject>>| {
+        InMemDicomObject::from_element_iter(elements)
+    };
+    let sequence = |tag, items: Vec<InMemDicomObject>| {
+        DataElement::new(tag, VR::SQ, DataSetSequence::from(items))
+    };
+    let roi = |number: i32, name: &str| {
+        item(vec![
+            DataElement::new(tags::ROI_NUMBER, VR::IS, PrimitiveValue::from(number)),
+            DataElement::new(tags::ROI_NAME, VR::LO, name),
+        ])
+    };
+    let contour = |number: i32, image: Vec<InMemDicomObject>| {
+        let mut contour = item(vec![
+            DataElement::new(tags::CONTOUR_GEOMETRIC_TYPE, VR::CS, "CLOSED_PLANAR"),
+            DataElement::new(
+                tags::CONTOUR_DATA,
+                VR::DS,
+                PrimitiveValue::F64(vec![0.0, 0.0, 3.0, 3.0, 0.0, 3.0, 3.0, 3.0, 3.0].into()),
+            ),
+        ]);
+        if !image.is_empty() {
+            contour.put(sequence(tags::CONTOUR_IMAGE_SEQUENCE, image));
+        }
+        item(vec![
+            DataElement::new(
+                tags::REFERENCED_ROI_NUMBER,
+                VR::IS,
+                PrimitiveValue::from(number),
+            ),
+            sequence(tags::CONTOUR_SEQUENCE, vec![contour]),
+        ])
+    };
+    let slice1 = item(vec![DataElement::new(
+        tags::REFERENCED_SOP_INSTANCE_UID,
+        VR::UI,
+        "1.2.3.4.5.1",
+    )]);
+    item(vec![
+        DataElement::new(tags::SOP_CLASS_UID, VR::UI, uids::RT_STRUCTURE_SET_STORAGE),
+        DataElement::new(tags::SOP_INSTANCE_UID, VR::UI, "1.2.3.4.8"),
+        DataElement::new(tags::STRUCTURE_SET_LABEL, VR::SH, "Plan"),
+        sequence(
+            tags::REFERENCED_FRAME_OF_REFERENCE_SEQUENCE,
+            vec![item(vec![DataElement::new(
+                tags::FRAME_OF_REFERENCE_UID,
+                VR::UI,
+                "1.2.3.4.6",
+            )])],
+        ),
+        sequence(
+            tags::STRUCTURE_SET_ROI_SEQUENCE,
+            vec![roi(1, "Lesion"), roi(2, "Body")],
+        ),
+        sequence(
+            tags::ROI_CONTOUR_SEQUENCE,
+            vec![contour(1, vec![slice1]), contour(2, Vec::new())],
+        ),
+    ])
+    .with_meta(FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN))
+    .unwrap()
+    .write_to_file(dir.join("rtstruct.dcm"))
+    .unwrap();
+}
+
+#[test]
+fn structure_sets_stay_over_their_series_with_per_roi_toggles() {
+    let dir = write_series("rtstruct", 3);
+    write_structure_set(&dir);
+    let mut preview = Preview::new(Some(picker_with(ProtocolType::Halfblocks)));
+    decode_and_wait(&mut preview, &dir.join("slice1.dcm"));
+    preview.cycle_segmentation();
+    wait_for_workers(&mut preview);
+    assert_eq!(
+        preview.notice.as_deref(),
+        Some("RTSTRUCT Plan: 2 ROIs (rtstruct.dcm)")
+    );
+    let status = |preview: &Preview| preview.status_lines()[0].clone();
+    assert_eq!(
+        status(&preview),
+        "RTSTRUCT ROI 1/2: Lesion (shown) | 2 on this image"
+    );
+
+    preview.select_next_roi();
+    preview.toggle_roi();
+    assert_eq!(preview.segmentation.roi_shown, [true, false]);
+    assert_eq!(
+        status(&preview),
+        "RTSTRUCT ROI 2/2: Body (hidden) | 2 on this image"
+    );
+
+    preview.clear();
+    decode_and_wait(&mut preview, &dir.join("slice2.dcm"));
+    assert_eq!(
+        status(&preview),
+        "RTSTRUCT ROI 2/2: Body (hidden) | 0 on this image"
+    );
+
+    preview.cycle_segmentation();
+    assert_eq!(preview.notice.as_deref(), Some("Segmentation off"));
+    assert!(preview.segmentation.loaded.is_none());
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,93 @@
+use dcr::dicom::{reference_planes, ImagePlane, ReferencePlane};
+use dicom::core::{DataElement, PrimitiveValue, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+/// A 100x100 axial slice at height `z`, centered on the origin
+fn axial(z: f64) -> ReferencePlane {
+    ReferencePlane {
+        label: format!("axial {z}"),
+        frame_of_reference: Some("1.2.3".into()),
+        plane: ImagePlane {
+            position: [-49.5, -49.5, z],
+            row_direction: [1.0, 0.0, 0.0],
+            column_direction: [0.0, 1.0, 0.0],
+            spacing: [1.0, 1.0],
+        },
+        size: (100, 100),
+    }
+}
+
+/// A coronal localizer covering 200 mm left-right and 400 mm head-foot,
+/// with 2 mm pixels, its top row at z = 199
+fn coronal_localizer() -> ImagePlane {
+    ImagePlane {
+        position: [-99.0, 0.0, 199.0],
+        row_direction: [1.0, 0.0, 0.0],
+        column_direction: [0.0, 0.0, -1.0],
+        spacing: [2.0, 2.0],
+    }
+}
+
+#[test]
+fn axial_slice_is_a_horizontal_line_on_a_coronal_localizer() {
+    let [from, to] = axial(39.0).line_on(&coronal_localizer()).unwrap();
+    // The slice spans x = -50..50 mm, 80 mm below the localizer's top row
+    let (mut xs, ys) = ([from.0, to.0], [from.1, to.1]);
+    xs.sort_by(f64::total_cmp);
+    assert_eq!(xs, [25.0, 75.0]);
+    assert_eq!(ys, [80.5, 80.5]);
+}
+
+#[test]
+fn parallel_or_distant_planes_have_no_line() {
+    assert_eq!(axial(0.0).line_on(&axial(10.0).plane), None);
+
+    let mut far = axial(39.0);
+    far.plane.position[1] = 200.0;
+    assert_eq!(far.line_on(&coronal_localizer()), None);
+}
+
+#[test]
+fn planes_are_read_per_frame_with_the_frame_of_reference() {
+    let mut obj = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(
+        tags::FRAME_OF_REFERENCE_UID,
+        VR::UI,
+        "1.2.3.4\0",
+    ));
+    obj.put(DataElement::new(
+        tags::ROWS,
+        VR::US,
+        PrimitiveValue::from(256u16),
+    ));
+    obj.put(DataElement::new(
+        tags::COLUMNS,
+        VR::US,
+        PrimitiveValue::from(128u16),
+    ));
+    obj.put(DataElement::new(
+        tags::IMAGE_POSITION_PATIENT,
+        VR::DS,
+        PrimitiveValue::from([0.0, 0.0, 0.0]),
+    ));
+    obj.put(DataElement::new(
+        tags::IMAGE_ORIENTATION_PATIENT,
+        VR::DS,
+        PrimitiveValue::from([1.0, 0.0, 0.0, 0.0, 1.0, 0.0]),
+    ));
+    obj.put(DataElement::new(
+        tags::PIXEL_SPACING,
+        VR::DS,
+        PrimitiveValue::from([0.5, 0.5]),
+    ));
+
+    let planes = reference_planes(&obj, "scout.dcm");
+    assert_eq!(planes.len(), 1);
+    assert_eq!(planes[0].label, "scout.dcm");
+    assert_eq!(planes[0].frame_of_reference.as_deref(), Some("1.2.3.4"));
+    assert_eq!(planes[0].size, (128, 256));
+
+    obj.remove_element(tags::PIXEL_SPACING);
+    assert!(reference_planes(&obj, "scout.dcm").is_empty());
+}
@@ -0,0 +1,183 @@
+use dcr::app::App;
+use dcr::dicom::content_tree;
+use dcr::validation::{SopClass, ValidationResult};
+use dicom::core::value::{DataSetSequence, PrimitiveValue};
+use dicom::core::{DataElement, Tag, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+fn item(elements: Vec<DataElement<InMemDicomObject>>) -> InMemDicomObject {
+    InMemDicomObject::from_element_iter(elements)
+}
+
+fn sequence(tag: Tag, items: Vec<InMemDicomObject>) -> DataElement<InMemDicomObject> {
+    DataElement::new(tag, VR::SQ, DataSetSequence::from(items))
+}
+
+fn code(tag: Tag, value: &str, scheme: &str, meaning: &str) -> DataElement<InMemDicomObject> {
+    sequence(
+        tag,
+        vec![item(vec![
+            DataElement::new(tags::CODE_VALUE, VR::SH, value),
+            DataElement::new(tags::CODING_SCHEME_DESIGNATOR, VR::SH, scheme),
+            DataElement::new(tags::CODE_MEANING, VR::LO, meaning),
+        ])],
+    )
+}
+
+fn content_item(
+    relationship: &str,
+    value_type: &str,
+    concept: &str,
+    mut elements: Vec<DataElement<InMemDicomObject>>,
+) -> InMemDicomObject {
+    elements.extend([
+        DataElement::new(tags::RELATIONSHIP_TYPE, VR::CS, relationship),
+        DataElement::new(tags::VALUE_TYPE, VR::CS, value_type),
+        code(tags::CONCEPT_NAME_CODE_SEQUENCE, "0", "99TEST", concept),
+    ]);
+    item(elements)
+}
+
+/// A measurement report with a finding, a lesion size, its source image
+/// and a container of observations
+fn report() -> InMemDicomObject {
+    let measurement = content_item(
+        "CONTAINS",
+        "NUM",
+        "Long axis",
+        vec![
+            sequence(
+                tags::MEASURED_VALUE_SEQUENCE,
+                vec![item(vec![
+                    DataElement::new(tags::NUMERIC_VALUE, VR::DS, "12.5"),
+                    code(
+                        tags::MEASUREMENT_UNITS_CODE_SEQUENCE,
+                        "mm",
+                        "UCUM",
+                        "millimeter",
+                    ),
+                ])],
+            ),
+            sequence(
+                tags::CONTENT_SEQUENCE,
+                vec![content_item(
+                    "INFERRED FROM",
+                    "IMAGE",
+                    "Source",
+                    vec![sequence(
+                        tags::REFERENCED_SOP_SEQUENCE,
+                        vec![item(vec![
+                            DataElement::new(tags::REFERENCED_SOP_INSTANCE_UID, VR::UI, "1.2.3"),
+                            DataElement::new(
+                                tags::REFERENCED_FRAME_NUMBER,
+                                VR::IS,
+                                PrimitiveValue::from(2),
+                            ),
+                        ])],
+                    )],
+                )],
+            ),
+        ],
+    );
+    let observations = content_item(
+        "CONTAINS",
+        "CONTAINER",
+        "Findings",
+        vec![sequence(
+            tags::CONTENT_SEQUENCE,
+            vec![
+                content_item(
+                    "CONTAINS",
+                    "CODE",
+                    "Finding",
+                    vec![code(tags::CONCEPT_CODE_SEQUENCE, "1", "99TEST", "Nodule")],
+                ),
+                content_item(
+                    "CONTAINS",
+                    "TEXT",
+                    "Comment",
+                    vec![DataElement::new(tags::TEXT_VALUE, VR::UT, "Stable ")],
+                ),
+            ],
+        )],
+    );
+    item(vec![
+        DataElement::new(tags::VALUE_TYPE, VR::CS, "CONTAINER"),
+        code(
+            tags::CONCEPT_NAME_CODE_SEQUENCE,
+            "126000",
+            "DCM",
+            "Imaging Measurement Report",
+        ),
+        sequence(tags::CONTENT_SEQUENCE, vec![measurement, observations]),
+    ])
+}
+
+#[test]
+fn content_items_show_relationship_concept_and_value() {
+    let tree = content_tree(&report()).unwrap();
+    let root = &tree[0];
+    assert_eq!(
+        (root.tag.as_str(), root.name.as_str(), root.vr.as_str()),
+        ("ROOT", "Imaging Measurement Report", "CONTAINER")
+    );
+    assert!(root.is_expanded);
+    assert_eq!(root.value, "<2 item(s)>");
+
+    let [measurement, observations] = &root.children[..] else {
+        panic!("two items expected");
+    };
+    assert_eq!(
+        (measurement.tag.as_str(), measurement.name.as_str()),
+        ("CONTAINS", "Long axis")
+    );
+    assert_eq!(measurement.value, "12.5 mm");
+    assert!(measurement.is_expandable && !measurement.is_expanded);
+    let image = &measurement.children[0];
+    assert_eq!((image.tag.as_str(), image.depth), ("INFERRED FROM", 2));
+    assert_eq!(image.value, "Image 1.2.3 frame 2");
+
+    let values: Vec<&str> = observations
+        .children
+        .iter()
+        .map(|c| c.value.as_str())
+        .collect();
+    assert_eq!(values, ["Nodule", "Stable"]);
+}
+
+#[test]
+fn objects_without_sr_content_have_no_tree() {
+    let mut obj = report();
+    obj.remove_element(tags::CONTENT_SEQUENCE);
+    assert!(content_tree(&obj).is_none());
+    assert!(content_tree(&InMemDicomObject::new_empty()).is_none());
+}
+
+#[test]
+fn content_tree_replaces_the_tag_table_and_collapses() {
+    let mut app = App::new(
+        Vec::new(),
+        "sr.dcm".to_string(),
+        ValidationResult::Valid,
+        SopClass::Unknown,
+    );
+    app.toggle_content_tree();
+    assert!(app.tag_view.is_none(), "no tree to show");
+
+    app.meta.content_tree = content_tree(&report());
+    app.toggle_content_tree();
+    assert!(app.tag_view.is_some());
+    // The root starts expanded
+    assert_eq!(app.tags.visible.len(), 3);
+    app.tags.scroll_down(1);
+    app.tags.expand_selected();
+    assert_eq!(app.tags.visible[2].tag, "INFERRED FROM");
+    app.tags.scroll_down(1);
+    app.tags.collapse_parent();
+    assert_eq!(app.tags.visible.len(), 3);
+
+    app.toggle_content_tree();
+    assert!(app.tag_view.is_none());
+    assert!(app.tags.visible.is_empty());
+}
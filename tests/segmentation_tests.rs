@@ -0,0 +1,233 @@
+use dcr::dicom::{lab_to_rgb, refers_to, ImagePlane, ImageTarget, Segmentation, SegmentationKind};
+use dicom::core::value::{DataSetSequence, PrimitiveValue};
+use dicom::core::{DataElement, Tag, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::InMemDicomObject;
+
+fn item(elements: Vec<DataElement<InMemDicomObject>>) -> InMemDicomObject {
+    InMemDicomObject::from_element_iter(elements)
+}
+
+fn sequence(tag: Tag, items: Vec<InMemDicomObject>) -> DataElement<InMemDicomObject> {
+    DataElement::new(tag, VR::SQ, DataSetSequence::from(items))
+}
+
+/// A 10x10 axial slice with 1 mm pixels at height `z`
+fn axial(z: f64) -> ImagePlane {
+    ImagePlane {
+        position: [0.0, 0.0, z],
+        row_direction: [1.0, 0.0, 0.0],
+        column_direction: [0.0, 1.0, 0.0],
+        spacing: [1.0, 1.0],
+    }
+}
+
+fn contour(image: Option<&str>, z: f64) -> InMemDicomObject {
+    let mut elements = vec![
+        DataElement::new(tags::CONTOUR_GEOMETRIC_TYPE, VR::CS, "CLOSED_PLANAR"),
+        DataElement::new(
+            tags::CONTOUR_DATA,
+            VR::DS,
+            PrimitiveValue::F64(vec![2.0, 2.0, z, 6.0, 2.0, z, 6.0, 5.0, z].into()),
+        ),
+    ];
+    if let Some(image) = image {
+        elements.push(sequence(
+            tags::CONTOUR_IMAGE_SEQUENCE,
+            vec![item(vec![DataElement::new(
+                tags::REFERENCED_SOP_INSTANCE_UID,
+                VR::UI,
+                image,
+            )])],
+        ));
+    }
+    item(elements)
+}
+
+/// "Liver" drawn on image 1.2.3.1 at z = 3, and an uncolored "Cord" at
+/// z = 6 that names no image
+fn structure_set() -> InMemDicomObject {
+    let roi = |number: &str, name: &str| {
+        item(vec![
+            DataElement::new(tags::ROI_NUMBER, VR::IS, number),
+            DataElement::new(tags::ROI_NAME, VR::LO, name),
+        ])
+    };
+    item(vec![
+        DataElement::new(tags::SOP_CLASS_UID, VR::UI, uids::RT_STRUCTURE_SET_STORAGE),
+        DataElement::new(tags::STRUCTURE_SET_LABEL, VR::SH, "Organs"),
+        sequence(
+            tags::REFERENCED_FRAME_OF_REFERENCE_SEQUENCE,
+            vec![item(vec![DataElement::new(
+                tags::FRAME_OF_REFERENCE_UID,
+                VR::UI,
+                "1.2.3.9",
+            )])],
+        ),
+        sequence(
+            tags::STRUCTURE_SET_ROI_SEQUENCE,
+            vec![roi("1", "Liver"), roi("2", "Cord")],
+        ),
+        sequence(
+            tags::ROI_CONTOUR_SEQUENCE,
+            vec![
+                item(vec![
+                    DataElement::new(tags::REFERENCED_ROI_NUMBER, VR::IS, "1"),
+                    DataElement::new(
+                        tags::ROI_DISPLAY_COLOR,
+                        VR::IS,
+                        PrimitiveValue::from([255, 0, 0]),
+                    ),
+                    sequence(tags::CONTOUR_SEQUENCE, vec![contour(Some("1.2.3.1"), 3.0)]),
+                ]),
+                item(vec![
+                    DataElement::new(tags::REFERENCED_ROI_NUMBER, VR::IS, "2"),
+                    sequence(tags::CONTOUR_SEQUENCE, vec![contour(None, 6.0)]),
+                ]),
+            ],
+        ),
+    ])
+}
+
+fn target<'a>(uid: &'a str, plane: &'a ImagePlane) -> ImageTarget<'a> {
+    ImageTarget {
+        sop_instance_uid: Some(uid),
+        frame: 0,
+        plane: Some(plane),
+        size: (10, 10),
+    }
+}
+
+#[test]
+fn structure_set_rois_carry_names_colors_and_contours() {
+    let segmentation = Segmentation::from_obj(&structure_set()).unwrap();
+    assert_eq!(segmentation.kind, SegmentationKind::StructureSet);
+    assert_eq!(segmentation.label, "Organs");
+    assert_eq!(segmentation.frame_of_reference.as_deref(), Some("1.2.3.9"));
+    let [liver, cord] = &segmentation.rois[..] else {
+        panic!("two ROIs expected");
+    };
+    assert_eq!((liver.name.as_str(), liver.color), ("Liver", [255, 0, 0]));
+    assert_eq!(cord.name, "Cord");
+    assert_ne!(cord.color, liver.color);
+
+    let slice = axial(3.0);
+    let outlines = liver.outlines_on(&target("1.2.3.1", &slice));
+    assert_eq!(outlines, [(vec![(2.5, 2.5), (6.5, 2.5), (6.5, 5.5)], true)]);
+    assert!(!cord.is_on(&target("1.2.3.1", &slice)));
+
+    // Without an image reference, contours are placed by their plane
+    let slice = axial(6.2);
+    assert!(cord.is_on(&target("1.2.3.2", &slice)));
+    assert!(!liver.is_on(&target("1.2.3.2", &slice)));
+}
+
+#[test]
+fn segment_frames_unpack_onto_their_source_images() {
+    let frame = |segment: &str, source: &str| {
+        item(vec![
+            sequence(
+                tags::SEGMENT_IDENTIFICATION_SEQUENCE,
+                vec![item(vec![DataElement::new(
+                    tags::REFERENCED_SEGMENT_NUMBER,
+                    VR::US,
+                    PrimitiveValue::from(segment.parse::<u16>().unwrap()),
+                )])],
+            ),
+            sequence(
+                tags::DERIVATION_IMAGE_SEQUENCE,
+                vec![item(vec![sequence(
+                    tags::SOURCE_IMAGE_SEQUENCE,
+                    vec![item(vec![DataElement::new(
+                        tags::REFERENCED_SOP_INSTANCE_UID,
+                        VR::UI,
+                        source,
+                    )])],
+                )])],
+            ),
+        ])
+    };
+    let segment = |number: u16, label: &str| {
+        item(vec![
+            DataElement::new(tags::SEGMENT_NUMBER, VR::US, PrimitiveValue::from(number)),
+            DataElement::new(tags::SEGMENT_LABEL, VR::LO, label),
+            DataElement::new(
+                tags::RECOMMENDED_DISPLAY_CIE_LAB_VALUE,
+                VR::US,
+                PrimitiveValue::from([65535u16, 32896, 32896]),
+            ),
+        ])
+    };
+    let mut obj = item(vec![
+        DataElement::new(tags::SOP_CLASS_UID, VR::UI, uids::SEGMENTATION_STORAGE),
+        DataElement::new(tags::SERIES_DESCRIPTION, VR::LO, "Tumor"),
+        DataElement::new(tags::NUMBER_OF_FRAMES, VR::IS, "2"),
+        DataElement::new(tags::SEGMENTATION_TYPE, VR::CS, "BINARY"),
+        sequence(
+            tags::SEGMENT_SEQUENCE,
+            vec![segment(1, "Core"), segment(2, "Edema")],
+        ),
+        sequence(
+            tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+            vec![frame("1", "1.2.3.1"), frame("2", "1.2.3.2")],
+        ),
+        // Two 3x3 frames, packed without padding between them: pixels 0
+        // and 8 of the first, pixel 4 (bit 13) of the second
+        DataElement::new(
+            tags::PIXEL_DATA,
+            VR::OB,
+            PrimitiveValue::from(vec![0x01u8, 0x21, 0, 0]),
+        ),
+    ]);
+    for (tag, value) in [
+        (tags::ROWS, 3u16),
+        (tags::COLUMNS, 3),
+        (tags::BITS_ALLOCATED, 1),
+    ] {
+        obj.put(DataElement::new(tag, VR::US, PrimitiveValue::from(value)));
+    }
+
+    let segmentation = Segmentation::from_obj(&obj).unwrap();
+    assert_eq!(segmentation.label, "Tumor");
+    let [core, edema] = &segmentation.rois[..] else {
+        panic!("two segments expected");
+    };
+    assert_eq!((core.name.as_str(), core.color), ("Core", [255, 255, 255]));
+
+    let on = |uid| ImageTarget {
+        sop_instance_uid: Some(uid),
+        frame: 0,
+        plane: None,
+        size: (3, 3),
+    };
+    let mask = core.masks_on(&on("1.2.3.1")).next().unwrap();
+    assert!(mask.is_set(0, 0) && mask.is_set(2, 2) && !mask.is_set(1, 1));
+    assert!(core.masks_on(&on("1.2.3.2")).next().is_none());
+    let mask = edema.masks_on(&on("1.2.3.2")).next().unwrap();
+    assert!(mask.is_set(1, 1) && !mask.is_set(0, 0) && !mask.is_set(2, 2));
+}
+
+#[test]
+fn lab_colors_convert_to_srgb() {
+    assert_eq!(lab_to_rgb([65535, 32896, 32896]), [255, 255, 255]);
+    assert_eq!(lab_to_rgb([0, 32896, 32896]), [0, 0, 0]);
+    let [r, g, b] = lab_to_rgb([35000, 53000, 50000]);
+    assert!(r > 200 && g < 80 && b < 80, "{r} {g} {b}");
+}
+
+#[test]
+fn structure_sets_refer_to_images_by_uid_or_frame_of_reference() {
+    let obj = structure_set();
+    assert!(refers_to(&obj, Some("1.2.3.1"), None));
+    assert!(refers_to(&obj, Some("4.5.6"), Some("1.2.3.9")));
+    assert!(!refers_to(&obj, Some("4.5.6"), Some("7.8.9")));
+    assert!(!refers_to(&axial_image(), Some("1.2.3.1"), Some("1.2.3.9")));
+}
+
+fn axial_image() -> InMemDicomObject {
+    item(vec![DataElement::new(
+        tags::SOP_CLASS_UID,
+        VR::UI,
+        uids::CT_IMAGE_STORAGE,
+    )])
+}
@@ -0,0 +1,173 @@
+use dcr::app::state::thumbnails::Thumbnails;
+use dcr::dicom::{icon_image, load_thumbnail, ThumbnailSource};
+use dicom::core::value::{DataSetSequence, PrimitiveValue};
+use dicom::core::{DataElement, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::{FileMetaTableBuilder, InMemDicomObject};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+/// Image module of an 8-bit image with the given photometric interpretation
+fn image(
+    rows: u16,
+    columns: u16,
+    photometric: &str,
+    data: Vec<u8>,
+) -> Vec<DataElement<InMemDicomObject>> {
+    let samples = if photometric == "RGB" { 3u16 } else { 1 };
+    let mut elements: Vec<_> = [
+        (tags::ROWS, rows),
+        (tags::COLUMNS, columns),
+        (tags::SAMPLES_PER_PIXEL, samples),
+        (tags::BITS_ALLOCATED, 8),
+        (tags::BITS_STORED, 8),
+        (tags::HIGH_BIT, 7),
+        (tags::PIXEL_REPRESENTATION, 0),
+        (tags::PLANAR_CONFIGURATION, 1),
+    ]
+    .into_iter()
+    .map(|(tag, value)| DataElement::new(tag, VR::US, PrimitiveValue::from(value)))
+    .collect();
+    elements.push(DataElement::new(
+        tags::PHOTOMETRIC_INTERPRETATION,
+        VR::CS,
+        photometric,
+    ));
+    elements.push(DataElement::new(
+        tags::PIXEL_DATA,
+        VR::OB,
+        PrimitiveValue::from(data),
+    ));
+    elements
+}
+
+/// A 100x50 gradient image, with an icon when one is given
+fn write_image(dir: &Path, name: &str, icon: Option<InMemDicomObject>) -> PathBuf {
+    let pixels: Vec<u8> = (0..50u32 * 100).map(|i| (i % 100) as u8).collect();
+    let mut elements = vec![
+        DataElement::new(
+            tags::SOP_CLASS_UID,
+            VR::UI,
+            uids::SECONDARY_CAPTURE_IMAGE_STORAGE,
+        ),
+        DataElement::new(tags::SOP_INSTANCE_UID, VR::UI, "1.2.3.4"),
+    ];
+    elements.extend(image(50, 100, "MONOCHROME2", pixels));
+    if let Some(icon) = icon {
+        elements.push(DataElement::new(
+            tags::ICON_IMAGE_SEQUENCE,
+            VR::SQ,
+            DataSetSequence::from(vec![icon]),
+        ));
+    }
+    let path = dir.join(name);
+    InMemDicomObject::from_element_iter(elements)
+        .with_meta(FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN))
+        .unwrap()
+        .write_to_file(&path)
+        .unwrap();
+    path
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dcr-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn with_icon(icon: InMemDicomObject) -> InMemDicomObject {
+    InMemDicomObject::from_element_iter([DataElement::new(
+        tags::ICON_IMAGE_SEQUENCE,
+        VR::SQ,
+        DataSetSequence::from(vec![icon]),
+    )])
+}
+
+#[test]
+fn icons_are_decoded_from_monochrome_and_rgb_pixels() {
+    let gray =
+        InMemDicomObject::from_element_iter(image(1, 3, "MONOCHROME2", vec![10, 60, 110, 0]));
+    let icon = icon_image(&with_icon(gray)).unwrap();
+    assert_eq!(icon.dimensions(), (3, 1));
+    assert_eq!(icon.get_pixel(0, 0).0, [0, 0, 0]);
+    assert_eq!(icon.get_pixel(1, 0).0, [127, 127, 127]);
+    assert_eq!(icon.get_pixel(2, 0).0, [255, 255, 255]);
+
+    // Planar: all red samples, then green, then blue
+    let rgb = InMemDicomObject::from_element_iter(image(1, 2, "RGB", vec![255, 0, 0, 255, 9, 9]));
+    let icon = icon_image(&with_icon(rgb)).unwrap();
+    assert_eq!(icon.get_pixel(0, 0).0, [255, 0, 9]);
+    assert_eq!(icon.get_pixel(1, 0).0, [0, 255, 9]);
+
+    let ybr = InMemDicomObject::from_element_iter(image(1, 1, "YBR_FULL", vec![0, 0, 0, 0]));
+    assert!(icon_image(&with_icon(ybr)).is_none());
+    assert!(icon_image(&InMemDicomObject::new_empty()).is_none());
+}
+
+#[test]
+fn thumbnails_use_the_icon_or_shrink_the_first_frame() {
+    let dir = temp_dir("thumbnails");
+    let icon = InMemDicomObject::from_element_iter(image(2, 2, "MONOCHROME2", vec![0, 1, 2, 3]));
+    let with_icon = write_image(&dir, "icon.dcm", Some(icon));
+    let running = AtomicBool::new(false);
+    let thumbnail = load_thumbnail(&with_icon, &running).unwrap();
+    assert_eq!(thumbnail.source, ThumbnailSource::Icon);
+    assert_eq!(thumbnail.image.dimensions(), (2, 2));
+
+    let without = write_image(&dir, "plain.dcm", None);
+    let thumbnail = load_thumbnail(&without, &running).unwrap();
+    assert_eq!(thumbnail.source, ThumbnailSource::Frame);
+    assert_eq!(thumbnail.image.dimensions(), (64, 32));
+
+    assert!(load_thumbnail(&dir.join("missing.dcm"), &running).is_err());
+    // A replaced request stops before decoding the frame
+    assert!(load_thumbnail(&without, &AtomicBool::new(true)).is_err());
+}
+
+#[test]
+fn thumbnails_are_read_once_per_path() {
+    let dir = temp_dir("thumbnail-cache");
+    let path = write_image(&dir, "plain.dcm", None);
+    let mut thumbnails = Thumbnails::default();
+    thumbnails.request(&path);
+    assert!(thumbnails.is_pending(&path));
+    let started = Instant::now();
+    while thumbnails.get(&path).is_none() && started.elapsed() < Duration::from_secs(10) {
+        thumbnails.poll();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert!(thumbnails.get(&path).unwrap().is_ok());
+
+    thumbnails.request(&path);
+    assert!(!thumbnails.is_pending(&path));
+}
+
+fn read(thumbnails: &mut Thumbnails, path: &Path) {
+    thumbnails.request(path);
+    let started = Instant::now();
+    while thumbnails.get(path).is_none() && started.elapsed() < Duration::from_secs(10) {
+        thumbnails.poll();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn least_recently_hovered_thumbnails_are_dropped() {
+    let dir = temp_dir("thumbnail-lru");
+    let paths: Vec<PathBuf> = ["a.dcm", "b.dcm", "c.dcm"]
+        .iter()
+        .map(|name| write_image(&dir, name, None))
+        .collect();
+    let mut thumbnails = Thumbnails::with_capacity(2);
+    read(&mut thumbnails, &paths[0]);
+    read(&mut thumbnails, &paths[1]);
+
+    // Hovering the first again keeps it over the second
+    thumbnails.request(&paths[0]);
+    read(&mut thumbnails, &paths[2]);
+    assert!(thumbnails.get(&paths[0]).is_some());
+    assert!(thumbnails.get(&paths[1]).is_none());
+    assert!(thumbnails.get(&paths[2]).is_some());
+}
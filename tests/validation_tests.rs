@@ -1,3 +1,6 @@
+// Tests match on enum variants with `assert!(true)` arms
+#![allow(clippy::assertions_on_constants)]
+
 use dcr::validation::{SopClass, ValidationResult};
 
 #[test]
@@ -0,0 +1,104 @@
+use dcr::dicom::{file_vois, MonochromeFrame, VoiFunction, VoiLut, VoiTransform, Window};
+use dicom::core::value::{DataSetSequence, PrimitiveValue};
+use dicom::core::{DataElement, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::{open_file, InMemDicomObject};
+use std::path::PathBuf;
+
+fn fixture_path(filename: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("dicom")
+        .join(filename)
+}
+
+#[test]
+fn linear_window_follows_the_standard() {
+    let window = Window::new(40.0, 400.0);
+    assert_eq!(window.apply(-200.0), 0.0);
+    assert_eq!(window.apply(300.0), 1.0);
+    assert!((window.apply(39.5) - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn linear_exact_and_sigmoid_are_centered() {
+    for function in [VoiFunction::LinearExact, VoiFunction::Sigmoid] {
+        let window = Window {
+            function,
+            ..Window::new(100.0, 50.0)
+        };
+        assert!((window.apply(100.0) - 0.5).abs() < 1e-9, "{:?}", function);
+        assert!(window.apply(0.0) < 0.05);
+        assert!(window.apply(200.0) > 0.95);
+    }
+}
+
+#[test]
+fn voi_lut_clamps_to_its_ends() {
+    let lut = VoiLut {
+        first_value: 10,
+        bits: 8,
+        data: vec![0, 128, 255],
+    };
+    assert_eq!(lut.apply(-5.0), 0.0);
+    assert!((lut.apply(11.0) - 128.0 / 255.0).abs() < 1e-9);
+    assert_eq!(lut.apply(500.0), 1.0);
+}
+
+#[test]
+fn file_windows_carry_their_explanations() {
+    let obj = open_file(fixture_path("ct-tap.dcm")).unwrap();
+    let vois = file_vois(&obj);
+    let labels: Vec<&str> = vois.iter().map(|v| v.label.as_str()).collect();
+    assert_eq!(labels, vec!["WINDOW1", "WINDOW2"]);
+    assert_eq!(
+        vois[0].transform,
+        VoiTransform::Window(Window::new(40.0, 300.0))
+    );
+    assert_eq!(vois[0].describe(), "W:300 L:40 WINDOW1");
+}
+
+#[test]
+fn voi_lut_sequence_comes_before_windows() {
+    let mut item = InMemDicomObject::new_empty();
+    item.put(DataElement::new(
+        tags::LUT_DESCRIPTOR,
+        VR::US,
+        PrimitiveValue::from([3u16, 0, 12]),
+    ));
+    item.put(DataElement::new(
+        tags::LUT_DATA,
+        VR::US,
+        PrimitiveValue::from([0u16, 2048, 4095]),
+    ));
+    item.put(DataElement::new(tags::LUT_EXPLANATION, VR::LO, "SOFT"));
+
+    let mut obj = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(
+        tags::VOILUT_SEQUENCE,
+        VR::SQ,
+        DataSetSequence::from(vec![item]),
+    ));
+    obj.put(DataElement::new(tags::WINDOW_CENTER, VR::DS, "50"));
+    obj.put(DataElement::new(tags::WINDOW_WIDTH, VR::DS, "350"));
+
+    let vois = file_vois(&obj);
+    assert_eq!(vois.len(), 2);
+    assert_eq!(vois[0].describe(), "VOI LUT SOFT");
+    assert_eq!(vois[1].label, "Window 1");
+}
+
+#[test]
+fn monochrome1_renders_inverted() {
+    let frame = MonochromeFrame {
+        rows: 1,
+        columns: 2,
+        values: vec![0.0, 100.0],
+        slope: 1.0,
+        intercept: 0.0,
+        invert: true,
+    };
+    let image = frame.render(&VoiTransform::Window(frame.full_range_window()));
+    assert_eq!(image.as_raw(), &vec![255, 0]);
+}
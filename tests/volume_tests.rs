@@ -0,0 +1,195 @@
+use dcr::dicom::{load_volume, Axis, FolderHeaders, ImagePlane, MonochromeFrame, Volume};
+use dicom::core::value::PrimitiveValue;
+use dicom::core::{DataElement, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::{FileMetaTableBuilder, InMemDicomObject};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+/// Axial plane at height `z` with 1 mm pixels
+fn plane_at(position: [f64; 3]) -> ImagePlane {
+    ImagePlane {
+        position,
+        row_direction: [1.0, 0.0, 0.0],
+        column_direction: [0.0, 1.0, 0.0],
+        spacing: [1.0, 1.0],
+    }
+}
+
+/// A 2x2 slice whose values encode the slice number and the pixel
+fn slice(number: f32) -> MonochromeFrame {
+    MonochromeFrame {
+        rows: 2,
+        columns: 2,
+        values: vec![
+            number * 10.0,
+            number * 10.0 + 1.0,
+            number * 10.0 + 2.0,
+            number * 10.0 + 3.0,
+        ],
+        slope: 1.0,
+        intercept: 0.0,
+        invert: false,
+    }
+}
+
+fn stack(heights: &[f64]) -> Vec<(ImagePlane, MonochromeFrame)> {
+    heights
+        .iter()
+        .enumerate()
+        .map(|(i, &z)| (plane_at([0.0, 0.0, z]), slice(i as f32)))
+        .collect()
+}
+
+/// Writes a 2x2, 16-bit CT slice of a series into `dir`
+fn write_slice(dir: &Path, name: &str, series: &str, z: f64, value: u16) {
+    let mut obj = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(
+        tags::SOP_CLASS_UID,
+        VR::UI,
+        uids::CT_IMAGE_STORAGE,
+    ));
+    obj.put(DataElement::new(
+        tags::SOP_INSTANCE_UID,
+        VR::UI,
+        format!("1.2.3.{name}"),
+    ));
+    obj.put(DataElement::new(tags::SERIES_INSTANCE_UID, VR::UI, series));
+    obj.put(DataElement::new(
+        tags::IMAGE_POSITION_PATIENT,
+        VR::DS,
+        format!("0\\0\\{z}"),
+    ));
+    obj.put(DataElement::new(
+        tags::IMAGE_ORIENTATION_PATIENT,
+        VR::DS,
+        "1\\0\\0\\0\\1\\0",
+    ));
+    obj.put(DataElement::new(tags::PIXEL_SPACING, VR::DS, "1\\1"));
+    obj.put(DataElement::new(
+        tags::SAMPLES_PER_PIXEL,
+        VR::US,
+        PrimitiveValue::from(1u16),
+    ));
+    obj.put(DataElement::new(
+        tags::PHOTOMETRIC_INTERPRETATION,
+        VR::CS,
+        "MONOCHROME2",
+    ));
+    for tag in [tags::ROWS, tags::COLUMNS] {
+        obj.put(DataElement::new(tag, VR::US, PrimitiveValue::from(2u16)));
+    }
+    obj.put(DataElement::new(
+        tags::BITS_ALLOCATED,
+        VR::US,
+        PrimitiveValue::from(16u16),
+    ));
+    obj.put(DataElement::new(
+        tags::BITS_STORED,
+        VR::US,
+        PrimitiveValue::from(16u16),
+    ));
+    obj.put(DataElement::new(
+        tags::HIGH_BIT,
+        VR::US,
+        PrimitiveValue::from(15u16),
+    ));
+    obj.put(DataElement::new(
+        tags::PIXEL_REPRESENTATION,
+        VR::US,
+        PrimitiveValue::from(0u16),
+    ));
+    let pixels: Vec<u8> = [value; 4].iter().flat_map(|v| v.to_le_bytes()).collect();
+    obj.put(DataElement::new(
+        tags::PIXEL_DATA,
+        VR::OW,
+        PrimitiveValue::from(pixels),
+    ));
+    obj.with_meta(FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN))
+        .unwrap()
+        .write_to_file(dir.join(format!("{name}.dcm")))
+        .unwrap();
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dcr-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn slices_are_stacked_by_position_and_reformatted() {
+    // Given out of order, 2 mm apart
+    let mut slices = stack(&[4.0, 0.0, 2.0]);
+    slices[0].1 = slice(2.0);
+    slices[1].1 = slice(0.0);
+    slices[2].1 = slice(1.0);
+    let volume = Volume::from_slices(slices).unwrap();
+    assert_eq!((volume.columns, volume.rows, volume.slices), (2, 2, 3));
+    assert_eq!(volume.spacing, [1.0, 1.0, 2.0]);
+    assert!(volume.warnings.is_empty());
+    assert_eq!(volume.value([1, 0, 2]), Some(21.0));
+    assert_eq!(volume.patient_point([1, 1, 2]), [1.0, 1.0, 4.0]);
+    assert_eq!(volume.nearest_slice([0.0, 0.0, 2.4]), 1);
+
+    assert_eq!(volume.name(Axis::Slice), "Axial");
+    assert_eq!(volume.name(Axis::Row), "Coronal");
+    assert_eq!(volume.name(Axis::Column), "Sagittal");
+
+    // Reformats are stretched to square pixels, the top slice first
+    let coronal = volume.reformat(Axis::Row, [0, 1, 0]);
+    assert_eq!((coronal.columns, coronal.rows), (2, 6));
+    assert_eq!(coronal.value(0, 0), Some(22.0));
+    assert_eq!(coronal.value(5, 1), Some(3.0));
+    let sagittal = volume.reformat(Axis::Column, [1, 0, 0]);
+    assert_eq!(sagittal.value(0, 1), Some(23.0));
+    assert_eq!(volume.view_position(Axis::Row, [0, 0, 2]), (0.5, 1.0));
+    assert_eq!(volume.view_voxel(Axis::Row, [0, 1, 0], (1, 1)), [1, 1, 2]);
+}
+
+#[test]
+fn gantry_tilt_and_shared_positions_are_refused() {
+    let mut slices = stack(&[0.0, 2.0, 4.0]);
+    for (i, (plane, _)) in slices.iter_mut().enumerate() {
+        plane.position[1] = i as f64 * 0.5;
+    }
+    let error = Volume::from_slices(slices).unwrap_err();
+    assert!(error.starts_with("Gantry tilt of 14.0°"), "{error}");
+
+    let error = Volume::from_slices(stack(&[0.0, 2.0, 2.0])).unwrap_err();
+    assert_eq!(error, "Several slices share a position");
+    let error = Volume::from_slices(stack(&[0.0, 2.0])).unwrap_err();
+    assert_eq!(error, "MPR needs at least 3 slices, found 2");
+}
+
+#[test]
+fn irregular_spacing_warns() {
+    let volume = Volume::from_slices(stack(&[0.0, 2.0, 6.0])).unwrap();
+    assert_eq!(volume.spacing[2], 3.0);
+    assert_eq!(
+        volume.warnings,
+        ["Irregular slice spacing (2.00–4.00 mm): reformats assume 3.00 mm"]
+    );
+}
+
+#[test]
+fn folder_slices_of_the_same_series_make_the_volume() {
+    let dir = temp_dir("volume");
+    for (i, z) in [5.0, 0.0, 10.0, 15.0].iter().enumerate() {
+        write_slice(&dir, &format!("s{i}"), "1.2.3.100", *z, i as u16 * 100);
+    }
+    write_slice(&dir, "other", "1.2.3.200", 20.0, 999);
+    fs::write(dir.join("notes.txt"), "not DICOM").unwrap();
+
+    let running = AtomicBool::new(false);
+    let headers = FolderHeaders::read(&dir, &running).unwrap();
+    let volume = load_volume(&dir.join("s0.dcm"), &headers, &running).unwrap();
+    assert_eq!(volume.slices, 4);
+    assert_eq!(volume.spacing[2], 5.0);
+    let column: Vec<f64> = (0..4).filter_map(|z| volume.value([0, 0, z])).collect();
+    assert_eq!(column, [100.0, 0.0, 200.0, 300.0]);
+    assert!(load_volume(&dir.join("s0.dcm"), &headers, &AtomicBool::new(true)).is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
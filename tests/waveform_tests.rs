@@ -0,0 +1,231 @@
+use dcr::app::state::waveform::WaveformView;
+use dcr::app::App;
+use dcr::dicom::waveforms;
+use dcr::validation::{SopClass, ValidationResult};
+use dicom::core::value::{DataSetSequence, PrimitiveValue};
+use dicom::core::{DataElement, Tag, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+
+fn item(elements: Vec<DataElement<InMemDicomObject>>) -> InMemDicomObject {
+    InMemDicomObject::from_element_iter(elements)
+}
+
+fn sequence(tag: Tag, items: Vec<InMemDicomObject>) -> DataElement<InMemDicomObject> {
+    DataElement::new(tag, VR::SQ, DataSetSequence::from(items))
+}
+
+/// Channel definition with a sensitivity in `units` and a baseline
+fn channel(label: &str, sensitivity: &str, baseline: &str, units: &str) -> InMemDicomObject {
+    item(vec![
+        DataElement::new(tags::CHANNEL_LABEL, VR::SH, label),
+        DataElement::new(tags::CHANNEL_SENSITIVITY, VR::DS, sensitivity),
+        DataElement::new(tags::CHANNEL_SENSITIVITY_CORRECTION_FACTOR, VR::DS, "1"),
+        DataElement::new(tags::CHANNEL_BASELINE, VR::DS, baseline),
+        sequence(
+            tags::CHANNEL_SENSITIVITY_UNITS_SEQUENCE,
+            vec![item(vec![
+                DataElement::new(tags::CODE_VALUE, VR::SH, units),
+                DataElement::new(tags::CODING_SCHEME_DESIGNATOR, VR::SH, "UCUM"),
+            ])],
+        ),
+    ])
+}
+
+/// Multiplex group of interleaved channels sampled at `frequency`, with one
+/// undefined channel when `channels` is empty
+fn group(
+    label: &str,
+    frequency: &str,
+    channels: Vec<InMemDicomObject>,
+    bits: u16,
+    interpretation: &str,
+    data: PrimitiveValue,
+    samples: u32,
+) -> InMemDicomObject {
+    item(vec![
+        DataElement::new(tags::MULTIPLEX_GROUP_LABEL, VR::SH, label),
+        DataElement::new(
+            tags::NUMBER_OF_WAVEFORM_CHANNELS,
+            VR::US,
+            PrimitiveValue::from(channels.len().max(1) as u16),
+        ),
+        DataElement::new(
+            tags::NUMBER_OF_WAVEFORM_SAMPLES,
+            VR::UL,
+            PrimitiveValue::from(samples),
+        ),
+        DataElement::new(tags::SAMPLING_FREQUENCY, VR::DS, frequency),
+        sequence(tags::CHANNEL_DEFINITION_SEQUENCE, channels),
+        DataElement::new(
+            tags::WAVEFORM_BITS_ALLOCATED,
+            VR::US,
+            PrimitiveValue::from(bits),
+        ),
+        DataElement::new(tags::WAVEFORM_SAMPLE_INTERPRETATION, VR::CS, interpretation),
+        DataElement::new(tags::WAVEFORM_DATA, VR::OW, data),
+    ])
+}
+
+/// A two-lead ECG of four samples at 500 Hz and a µ-law audio group
+fn waveform_object() -> InMemDicomObject {
+    let ecg = group(
+        "RHYTHM",
+        "500",
+        vec![
+            channel("Lead I", "2.5", "0", "uV"),
+            channel("Lead II", "1", "-10", "uV"),
+        ],
+        16,
+        "SS",
+        PrimitiveValue::I16(vec![0, 1, 100, 2, -40, 3, 4, 4].into()),
+        4,
+    );
+    let audio = group(
+        "AUDIO",
+        "8000",
+        Vec::new(),
+        8,
+        "MB",
+        PrimitiveValue::from(vec![0xFFu8, 0x00]),
+        2,
+    );
+    item(vec![sequence(tags::WAVEFORM_SEQUENCE, vec![ecg, audio])])
+}
+
+#[test]
+fn waveform_data_is_scaled_by_the_channel_definitions() {
+    let groups = waveforms(&waveform_object()).unwrap();
+    assert_eq!(groups.len(), 2);
+
+    let ecg = &groups[0];
+    assert_eq!(ecg.label.as_deref(), Some("RHYTHM"));
+    assert_eq!(ecg.sample_count(), 4);
+    assert_eq!(ecg.duration(), 4.0 / 500.0);
+    assert_eq!(ecg.channels[0].label, "Lead I");
+    assert_eq!(ecg.channels[0].units.as_deref(), Some("uV"));
+    assert_eq!(ecg.channels[0].samples, [0.0, 250.0, -100.0, 10.0]);
+    assert_eq!(ecg.channels[1].samples, [-9.0, -8.0, -7.0, -6.0]);
+
+    // Groups without channel definitions get numbered channels
+    let audio = &groups[1];
+    assert_eq!(audio.channels[0].label, "Channel 1");
+    assert_eq!(audio.channels[0].samples, [0.0, -32124.0]);
+
+    assert_eq!(waveforms(&item(Vec::new())).unwrap(), []);
+}
+
+#[test]
+fn unsupported_or_short_waveform_data_is_an_error() {
+    let odd = group(
+        "",
+        "100",
+        Vec::new(),
+        16,
+        "UV",
+        PrimitiveValue::from(vec![0u8; 4]),
+        2,
+    );
+    let error = waveforms(&item(vec![sequence(tags::WAVEFORM_SEQUENCE, vec![odd])]));
+    assert!(error.unwrap_err().contains("16 bits UV"));
+
+    let short = group(
+        "",
+        "100",
+        Vec::new(),
+        8,
+        "SB",
+        PrimitiveValue::from(vec![0u8; 4]),
+        8,
+    );
+    assert!(waveforms(&item(vec![sequence(tags::WAVEFORM_SEQUENCE, vec![short])])).is_err());
+}
+
+#[test]
+fn long_stretches_keep_their_peaks() {
+    let samples: Vec<u8> = (0..1000u32)
+        .map(|i| if i == 517 { 250 } else { 100 })
+        .collect();
+    let groups = waveforms(&item(vec![sequence(
+        tags::WAVEFORM_SEQUENCE,
+        vec![group(
+            "",
+            "1000",
+            Vec::new(),
+            8,
+            "UB",
+            PrimitiveValue::from(samples),
+            1000,
+        )],
+    )]))
+    .unwrap();
+    let points = groups[0].points(0, 0.0, 1.0, 20);
+    assert!(points.len() <= 40);
+    assert!(points.contains(&(0.517, 250.0)));
+    // Short stretches keep every sample
+    assert_eq!(groups[0].points(0, 0.5, 0.51, 20).len(), 11);
+}
+
+#[test]
+fn the_waveform_view_scrolls_zooms_and_toggles_channels() {
+    let long: Vec<u8> = vec![0; 500 * 20];
+    let mut groups = waveforms(&item(vec![sequence(
+        tags::WAVEFORM_SEQUENCE,
+        vec![group(
+            "",
+            "500",
+            Vec::new(),
+            8,
+            "UB",
+            PrimitiveValue::from(long),
+            20 * 500,
+        )],
+    )]))
+    .unwrap();
+    groups.extend(waveforms(&waveform_object()).unwrap());
+
+    let mut view = WaveformView::new(groups).unwrap();
+    assert_eq!((view.start, view.span), (0.0, 10.0));
+    view.scroll(-1);
+    assert_eq!(view.start, 0.0);
+    view.scroll(2);
+    assert_eq!(view.start, 5.0);
+    view.scroll(10);
+    assert_eq!(view.start, 10.0);
+    view.zoom_by(4.0);
+    assert_eq!((view.start, view.span), (0.0, 20.0));
+    view.zoom_by(0.0);
+    assert_eq!(view.span, 10.0 / 500.0);
+    assert!(view.title().starts_with("Group 1/3 | 500 Hz"));
+
+    view.next_group();
+    assert_eq!(view.span, 4.0 / 500.0);
+    view.select_channel(-1);
+    view.toggle_channel();
+    assert_eq!(view.shown_channels(), [0]);
+    assert!(view.title().starts_with("Group 2/3 RHYTHM"));
+
+    assert!(WaveformView::new(Vec::new()).is_none());
+}
+
+#[test]
+fn waveforms_replace_the_tag_table_while_toggled() {
+    let mut app = App::new(
+        Vec::new(),
+        "ecg.dcm".into(),
+        ValidationResult::NotApplicable,
+        SopClass::Unknown,
+    );
+    app.toggle_waveform();
+    assert!(app.waveform.is_none());
+
+    app.meta.waveforms = waveforms(&waveform_object());
+    app.toggle_waveform();
+    assert!(matches!(app.waveform, Some(Ok(_))));
+    app.toggle_waveform();
+    assert!(app.waveform.is_none());
+
+    app.meta.waveforms = Err("Unsupported waveform samples".into());
+    app.toggle_waveform();
+    assert!(matches!(app.waveform, Some(Err(_))));
+}